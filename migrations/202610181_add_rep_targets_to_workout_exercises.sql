@@ -0,0 +1,5 @@
+-- Wiederholungsbereich und Pause pro Übung im Plan (YAML-Format Version 2).
+-- Alle drei optional: Pläne aus Version 1 kennen sie nicht.
+ALTER TABLE workout_exercises ADD COLUMN target_reps_min INTEGER;
+ALTER TABLE workout_exercises ADD COLUMN target_reps_max INTEGER;
+ALTER TABLE workout_exercises ADD COLUMN rest_seconds INTEGER;
@@ -0,0 +1,11 @@
+-- Welche Quell-ID eines Kontoarchivs auf welche lokale Zeile abgebildet wurde.
+-- Daran erkennt ein zweiter Import desselben Archivs, was schon da ist.
+CREATE TABLE IF NOT EXISTS imported_ids (
+    user_id TEXT NOT NULL,
+    kind TEXT NOT NULL,          -- 'exercise', 'workout', 'session'
+    source_id TEXT NOT NULL,     -- ID im Archiv
+    local_id TEXT NOT NULL,      -- ID in dieser Datenbank
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (user_id, kind, source_id),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
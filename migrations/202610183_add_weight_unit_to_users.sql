@@ -0,0 +1,2 @@
+-- Gewichtseinheit für Eingabe und Anzeige. Gespeichert wird weiter in kg.
+ALTER TABLE users ADD COLUMN weight_unit TEXT NOT NULL DEFAULT 'kg';
@@ -0,0 +1,12 @@
+-- Stange, Verschlüsse und Scheiben pro Nutzer für den Scheibenrechner.
+-- Anders als Trainingsgewichte in der Einheit, in der die Scheiben
+-- beschriftet sind: eine 45-lb-Scheibe soll 45 bleiben.
+CREATE TABLE IF NOT EXISTS equipment_profiles (
+    user_id TEXT PRIMARY KEY,
+    unit TEXT NOT NULL DEFAULT 'kg',     -- 'kg' oder 'lb'
+    bar_weight REAL NOT NULL,
+    collar_weight REAL NOT NULL DEFAULT 0,
+    plates TEXT NOT NULL,                -- '25, 20, 10x2, 1.25', siehe plates.rs
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
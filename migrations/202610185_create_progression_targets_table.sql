@@ -0,0 +1,21 @@
+-- Progressionsstrategie pro Übung im Plan und das daraus berechnete Ziel
+-- für die nächste Einheit. Berechnet wird beim Abschluss eines Trainings,
+-- angezeigt im Live-Training.
+ALTER TABLE workout_exercises ADD COLUMN progression TEXT NOT NULL DEFAULT 'double'; -- 'double', 'linear', 'rpe'
+
+CREATE TABLE IF NOT EXISTS progression_targets (
+    workout_id TEXT NOT NULL,
+    exercise_id TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    decision TEXT NOT NULL,             -- siehe progression::Decision
+    target_weight REAL,                 -- kg, NULL bei Körpergewicht
+    target_sets INTEGER NOT NULL,
+    target_reps_min INTEGER NOT NULL,
+    target_reps_max INTEGER NOT NULL,
+    basis_weight REAL,                  -- Arbeitsgewicht der letzten Einheit
+    completed_workout_id TEXT,          -- Training, nach dem berechnet wurde
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (workout_id, exercise_id),
+    FOREIGN KEY (workout_id) REFERENCES workouts(id) ON DELETE CASCADE,
+    FOREIGN KEY (exercise_id) REFERENCES exercises(id) ON DELETE CASCADE
+);
@@ -0,0 +1,21 @@
+-- Protokoll aller übernommenen Zieländerungen im Plan, damit der Verlauf
+-- der Ziele sichtbar bleibt, auch wenn workout_exercises nur den letzten
+-- Stand kennt.
+CREATE TABLE IF NOT EXISTS target_changes (
+    id TEXT PRIMARY KEY,
+    workout_id TEXT NOT NULL,
+    exercise_id TEXT NOT NULL,
+    completed_workout_id TEXT,          -- Training, das die Änderung ausgelöst hat
+    decision TEXT NOT NULL,             -- 'increase' oder 'deload'
+    old_weight REAL,                    -- kg
+    new_weight REAL,                    -- kg
+    old_reps_min INTEGER,
+    old_reps_max INTEGER,
+    new_reps_min INTEGER,
+    new_reps_max INTEGER,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (workout_id) REFERENCES workouts(id) ON DELETE CASCADE,
+    FOREIGN KEY (exercise_id) REFERENCES exercises(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_target_changes_workout ON target_changes(workout_id, created_at);
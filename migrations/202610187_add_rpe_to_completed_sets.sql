@@ -0,0 +1,3 @@
+-- Anstrengung pro Satz auf der RPE-Skala (1–10, halbe Schritte). RIR wird
+-- beim Speichern umgerechnet: RPE = 10 - RIR. NULL heißt: nicht angegeben.
+ALTER TABLE completed_sets ADD COLUMN rpe REAL;
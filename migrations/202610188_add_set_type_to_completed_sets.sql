@@ -0,0 +1,6 @@
+-- Satzart: warmup, working, drop, failure, amrap. Bestehende Sätze waren
+-- alle Arbeitssätze. Aufwärmsätze zählen nicht zu Volumen und Rekorden.
+ALTER TABLE completed_sets ADD COLUMN set_type TEXT NOT NULL DEFAULT 'working';
+
+-- Automatische Aufwärmrampe im Training, pro Nutzer einschaltbar.
+ALTER TABLE users ADD COLUMN warmup_ramps BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- Supersätze und Zirkel: aufeinanderfolgende Übungen mit derselben Gruppe
+-- (z. B. 'A') werden im Training abwechselnd geführt. NULL heißt: allein.
+ALTER TABLE workout_exercises ADD COLUMN group_label TEXT;
@@ -0,0 +1,17 @@
+-- Übungen einer Trainingssitzung, beim Start aus dem Plan kopiert. Hier
+-- liegt, was nur für diese Sitzung gilt: die Reihenfolge nach „später" oder
+-- „springen" und der Ersatz einer Übung. Der Plan bleibt unberührt.
+--
+-- session_id ist active_workouts.id und nach dem Abschluss
+-- completed_workouts.id — wie bei completed_sets ohne Fremdschlüssel, damit
+-- die Zeilen das Ende der Sitzung überleben.
+CREATE TABLE IF NOT EXISTS session_exercises (
+    session_id TEXT NOT NULL,
+    position INTEGER NOT NULL,          -- Position im Plan
+    planned_exercise_id TEXT NOT NULL,  -- Übung laut Plan
+    exercise_id TEXT NOT NULL,          -- tatsächlich trainiert, bei Ersatz abweichend
+    sort_order INTEGER NOT NULL,        -- Reihenfolge in dieser Sitzung
+    PRIMARY KEY (session_id, position),
+    FOREIGN KEY (planned_exercise_id) REFERENCES exercises(id) ON DELETE CASCADE,
+    FOREIGN KEY (exercise_id) REFERENCES exercises(id) ON DELETE CASCADE
+);
@@ -0,0 +1,7 @@
+-- Sätze über den Plan hinaus, nur für diese Sitzung. Die Vorgabe der
+-- Übung in der Sitzung ist target_sets aus dem Plan plus extra_sets.
+--
+-- Spontan hinzugefügte Übungen stehen nicht im Plan: Sie bekommen negative
+-- Positionen, damit sie nie mit einer Planposition kollidieren, und ihre
+-- Sätze stehen ganz in extra_sets.
+ALTER TABLE session_exercises ADD COLUMN extra_sets INTEGER NOT NULL DEFAULT 0;
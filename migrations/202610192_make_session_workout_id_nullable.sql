@@ -0,0 +1,52 @@
+-- Freies Training ohne Vorlage: workout_id darf NULL sein, bei laufenden
+-- wie bei abgeschlossenen Sitzungen. Die Übungen einer freien Sitzung
+-- stehen nur in session_exercises.
+--
+-- SQLite kann NOT NULL nicht entfernen, also Tabellen neu anlegen wie in
+-- 20251107_fix_completed_sets_cascade.sql.
+
+CREATE TABLE active_workouts_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL,
+    workout_id TEXT,                    -- NULL bei freiem Training
+    started_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (workout_id) REFERENCES workouts(id) ON DELETE CASCADE
+);
+
+INSERT INTO active_workouts_new (id, user_id, workout_id, started_at, created_at)
+SELECT id, user_id, workout_id, started_at, created_at FROM active_workouts;
+
+DROP TABLE active_workouts;
+ALTER TABLE active_workouts_new RENAME TO active_workouts;
+
+CREATE INDEX idx_active_workouts_user_id ON active_workouts(user_id);
+CREATE INDEX idx_active_workouts_started_at ON active_workouts(user_id, started_at);
+
+CREATE TABLE completed_workouts_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL,
+    workout_id TEXT,                    -- NULL bei freiem Training
+    started_at TEXT NOT NULL,
+    completed_at TEXT NOT NULL,
+    total_duration_minutes INTEGER NOT NULL,
+    total_sets INTEGER NOT NULL,
+    total_volume_kg REAL NOT NULL,
+    notes TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (workout_id) REFERENCES workouts(id) ON DELETE RESTRICT
+);
+
+INSERT INTO completed_workouts_new
+SELECT id, user_id, workout_id, started_at, completed_at, total_duration_minutes,
+       total_sets, total_volume_kg, notes, created_at
+FROM completed_workouts;
+
+DROP TABLE completed_workouts;
+ALTER TABLE completed_workouts_new RENAME TO completed_workouts;
+
+CREATE INDEX idx_completed_workouts_user_id ON completed_workouts(user_id);
+CREATE INDEX idx_completed_workouts_date ON completed_workouts(user_id, completed_at);
+CREATE INDEX idx_completed_workouts_workout ON completed_workouts(workout_id);
@@ -0,0 +1,8 @@
+-- Pause im laufenden Training. paused_at ist gesetzt, solange pausiert
+-- wird; paused_seconds sammelt frühere Pausen. Beides zählt nicht zur
+-- Trainingsdauer.
+ALTER TABLE active_workouts ADD COLUMN paused_at TEXT;
+ALTER TABLE active_workouts ADD COLUMN paused_seconds INTEGER NOT NULL DEFAULT 0;
+
+-- Vom Hintergrundjob geschlossen, weil die Sitzung zu lange offen war.
+ALTER TABLE completed_workouts ADD COLUMN auto_closed BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+-- Zeit je Übung, aus den Abständen der Sätze berechnet. JSON-Liste
+-- [{"exercise_id": "...", "seconds": 420}, ...] in der Reihenfolge des
+-- ersten Satzes; NULL bei Trainings von vor dieser Spalte und Importen.
+ALTER TABLE completed_workouts ADD COLUMN exercise_times TEXT;
@@ -0,0 +1,8 @@
+-- Idempotenzschlüssel für Sätze, die offline geloggt und später vom
+-- Service Worker nachgereicht werden. Kommt derselbe Satz zweimal an, gilt
+-- der erste. NULL bei Sätzen ohne Schlüssel (ältere Clients, Importe).
+ALTER TABLE completed_sets ADD COLUMN client_request_id TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_completed_sets_client_request
+    ON completed_sets(active_workout_id, client_request_id)
+    WHERE client_request_id IS NOT NULL;
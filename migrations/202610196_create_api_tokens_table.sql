@@ -0,0 +1,13 @@
+-- Persönliche Zugangstoken für die JSON-API (/api/v1). Gespeichert wird nur
+-- der SHA-256-Hash, das Token selbst sieht der Nutzer einmal beim Anlegen.
+CREATE TABLE IF NOT EXISTS api_tokens (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,    -- hex, siehe api_token::hash
+    created_at TEXT NOT NULL,
+    last_used_at TEXT,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_tokens_user ON api_tokens(user_id);
@@ -0,0 +1,3 @@
+-- Umfang eines Tokens: 'read' darf nur lesen (GET), 'read_write' alles.
+-- Bestehende Token hatten bisher vollen Zugriff und behalten ihn.
+ALTER TABLE api_tokens ADD COLUMN scope TEXT NOT NULL DEFAULT 'read_write';
@@ -0,0 +1,10 @@
+-- Kalender-Abo je Nutzer. Das Token steht in der URL, die Kalender-Apps
+-- immer wieder abrufen, und bleibt daher im Klartext, damit die Profilseite
+-- den Link erneut zeigen kann. Ein neuer Link ersetzt den alten.
+CREATE TABLE IF NOT EXISTS calendar_feeds (
+    user_id TEXT PRIMARY KEY NOT NULL,
+    token TEXT NOT NULL UNIQUE,
+    include_history BOOLEAN NOT NULL DEFAULT 0,  -- abgeschlossene Trainings als vergangene Termine
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- Abgelehnte Vorschläge werden ebenfalls protokolliert. Bestehende Einträge
+-- sind alle übernommen worden.
+ALTER TABLE target_changes ADD COLUMN status TEXT NOT NULL DEFAULT 'applied';  -- 'applied' oder 'rejected'
@@ -0,0 +1,51 @@
+-- Ziele und ihr Protokoll hängen an der Zeile im Plan, nicht an der Übung:
+-- ein Plan darf dieselbe Übung zweimal führen, etwa schwer und leicht.
+--
+-- top_of_range: alle Arbeitssätze der letzten Einheit am oberen Ende des
+-- Bereichs. Nur dann (oder beim Deload) wird der Plan selbst angepasst,
+-- egal welche Strategie das Ziel im Training bestimmt.
+--
+-- SQLite kann den Primärschlüssel nicht ändern, also neu anlegen wie in
+-- 202610192_make_session_workout_id_nullable.sql. Ziele sind abgeleitet
+-- und werden nach dem nächsten Training ohnehin neu berechnet.
+
+CREATE TABLE progression_targets_new (
+    workout_exercise_id TEXT PRIMARY KEY NOT NULL,
+    workout_id TEXT NOT NULL,
+    exercise_id TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    decision TEXT NOT NULL,             -- siehe progression::Decision
+    target_weight REAL,                 -- kg, NULL bei Körpergewicht
+    target_sets INTEGER NOT NULL,
+    target_reps_min INTEGER NOT NULL,
+    target_reps_max INTEGER NOT NULL,
+    basis_weight REAL,                  -- Arbeitsgewicht der letzten Einheit
+    top_of_range BOOLEAN NOT NULL DEFAULT 0,
+    completed_workout_id TEXT,          -- Training, nach dem berechnet wurde
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (workout_exercise_id) REFERENCES workout_exercises(id) ON DELETE CASCADE,
+    FOREIGN KEY (workout_id) REFERENCES workouts(id) ON DELETE CASCADE,
+    FOREIGN KEY (exercise_id) REFERENCES exercises(id) ON DELETE CASCADE
+);
+
+INSERT INTO progression_targets_new
+    (workout_exercise_id, workout_id, exercise_id, strategy, decision, target_weight, target_sets,
+     target_reps_min, target_reps_max, basis_weight, completed_workout_id, created_at)
+SELECT we.id, pt.workout_id, pt.exercise_id, pt.strategy, pt.decision, pt.target_weight, pt.target_sets,
+       pt.target_reps_min, pt.target_reps_max, pt.basis_weight, pt.completed_workout_id, pt.created_at
+FROM progression_targets pt
+INNER JOIN workout_exercises we ON we.workout_id = pt.workout_id AND we.exercise_id = pt.exercise_id;
+
+DROP TABLE progression_targets;
+ALTER TABLE progression_targets_new RENAME TO progression_targets;
+
+CREATE INDEX idx_progression_targets_session ON progression_targets(workout_id, completed_workout_id);
+
+-- Alte Einträge zeigen bei doppelter Übung auf die erste Zeile; NULL, wenn
+-- es keine mehr gibt.
+ALTER TABLE target_changes ADD COLUMN workout_exercise_id TEXT;
+
+UPDATE target_changes SET workout_exercise_id = (
+    SELECT MIN(we.id) FROM workout_exercises we
+    WHERE we.workout_id = target_changes.workout_id AND we.exercise_id = target_changes.exercise_id
+);
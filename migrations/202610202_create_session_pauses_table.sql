@@ -0,0 +1,11 @@
+-- Beendete Pausen einer laufenden Sitzung mit Anfang und Ende.
+-- paused_seconds kennt nur die Summe; die Dauer von Satz zu Satz muss
+-- wissen, welche Pause zwischen welchen Sätzen lag. Wie session_exercises
+-- ohne Fremdschlüssel; beim Abschließen und Verwerfen gelöscht.
+CREATE TABLE IF NOT EXISTS session_pauses (
+    session_id TEXT NOT NULL,
+    paused_at TEXT NOT NULL,
+    resumed_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_pauses_session ON session_pauses(session_id);
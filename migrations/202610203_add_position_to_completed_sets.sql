@@ -0,0 +1,5 @@
+-- Zeile der Sitzung (session_exercises.position), zu der ein Satz gehört.
+-- Steht dieselbe Übung zweimal im Plan, etwa schwer und leicht, trennt
+-- erst die Position ihre Sätze. NULL bei älteren Sätzen und Importen; sie
+-- zählen für die erste Zeile des Plans mit der Übung.
+ALTER TABLE completed_sets ADD COLUMN position INTEGER;
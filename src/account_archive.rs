@@ -0,0 +1,623 @@
+//! Kontoarchiv: alle Daten eines Nutzers als ein JSON-Dokument — Pläne,
+//! abgeschlossene Trainings mit Sätzen, Rekorde und die benutzten Übungen.
+//!
+//! Anders als das Plan-YAML (`workout_yaml`) trägt das Archiv die IDs der
+//! Quellinstanz. Sie dienen nur als Verweise innerhalb des Dokuments und als
+//! Schlüssel, an dem ein zweiter Import erkennt, was schon da ist — in der
+//! Datenbank landen neue IDs (siehe `handlers::account`).
+//!
+//! Körpermaße gibt es in diesem Schema noch nicht. Kommen sie, bekommen sie
+//! einen eigenen Abschnitt und die Version steigt.
+
+use crate::plates::Equipment;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Kennung im Feld `format` — ein beliebiges JSON soll nicht als Archiv
+/// durchgehen, nur weil es zufällig `version` enthält.
+pub const FORMAT_NAME: &str = "woplanner-account";
+
+/// Die Version, die der Export schreibt. Gelesen wird jede von
+/// `OLDEST_VERSION` bis hier.
+///
+/// Wer sie erhöht: neue Felder in `FIELDS_SINCE` eintragen. Ein älterer
+/// Build lehnt die Datei dann mit „zu neu" ab, statt Felder stillschweigend
+/// zu verlieren.
+pub const ARCHIVE_VERSION: i64 = 4;
+pub const OLDEST_VERSION: i64 = 1;
+
+/// Felder, die nach Version 1 dazukamen: Abschnitt, Feld, Version. Eine
+/// Datei, die ein Feld vor seiner Zeit benutzt, ist ein Fehler — sonst
+/// hätte ein älterer Build sie ohne Meldung anders gelesen.
+const FIELDS_SINCE: [(&str, &str, i64); 11] = [
+    ("workouts[].exercises[]", "progression", 2),
+    ("workouts[].exercises[]", "group_label", 2),
+    ("sessions[]", "auto_closed", 2),
+    ("sessions[].sets[]", "rpe", 2),
+    ("sessions[].sets[]", "set_type", 2),
+    ("user", "weight_unit", 3),
+    ("user", "warmup_ramps", 3),
+    ("workouts[]", "target_changes", 3),
+    ("sessions[]", "exercises", 3),
+    ("sessions[]", "exercise_times", 3),
+    ("sessions[].sets[]", "position", 4),
+];
+
+/// Dasselbe für Abschnitte der obersten Ebene.
+const TOP_LEVEL_SINCE: [(&str, i64); 1] = [("equipment", 3)];
+
+/// Ab dieser Version darf `sessions[].workout_id` fehlen (freies Training).
+const FREESTYLE_SINCE: i64 = 2;
+
+/// Obergrenze für ein hochgeladenes Archiv. Jahre an Sätzen sind wenige MB.
+pub const MAX_ARCHIVE_BYTES: usize = 32 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountArchive {
+    pub format: String,
+    pub version: i64,
+    pub exported_at: String,
+    pub user: ArchiveUser,
+    pub exercises: Vec<ArchiveExercise>,
+    pub workouts: Vec<ArchiveWorkout>,
+    pub sessions: Vec<ArchiveSession>,
+    /// Zur Information und für andere Werkzeuge. Der Import übernimmt sie
+    /// nicht, sondern rechnet sie aus den Sätzen neu.
+    pub personal_records: Vec<ArchiveRecord>,
+    /// Stange und Scheiben; fehlt, solange der Nutzer die Vorgabe benutzt.
+    #[serde(default)]
+    pub equipment: Option<ArchiveEquipment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveUser {
+    pub id: String,
+    pub name: String,
+    pub created_at: String,
+    /// `kg` oder `lb`; fehlt in älteren Archiven, dann bleibt die Einstellung.
+    #[serde(default)]
+    pub weight_unit: Option<String>,
+    #[serde(default)]
+    pub warmup_ramps: Option<bool>,
+}
+
+/// Wie `equipment_profiles`: Gewichte in `unit`, Scheiben als Formulartext.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveEquipment {
+    pub unit: String,
+    pub bar_weight: f64,
+    pub collar_weight: f64,
+    pub plates: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveExercise {
+    pub id: String,
+    pub name: String,
+    pub instructions: String,
+    pub video_url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveWorkout {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_active: bool,
+    pub schedule_type: String,
+    pub schedule_day: Option<i32>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub exercises: Vec<ArchiveWorkoutExercise>,
+    /// Übernommene und abgelehnte Zieländerungen; fehlt in älteren Archiven.
+    #[serde(default)]
+    pub target_changes: Vec<ArchiveTargetChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveWorkoutExercise {
+    pub exercise_id: String,
+    pub position: i32,
+    pub target_sets: i32,
+    pub target_weight: Option<f32>,
+    pub target_reps_min: Option<i32>,
+    pub target_reps_max: Option<i32>,
+    pub rest_seconds: Option<i32>,
+    pub notes: Option<String>,
+    /// Fehlt in Archiven von vor der Progressions-Engine.
+    #[serde(default)]
+    pub progression: Option<String>,
+    /// Supersatz oder Zirkel; fehlt in älteren Archiven.
+    #[serde(default)]
+    pub group_label: Option<String>,
+}
+
+/// Eintrag aus `target_changes`. Die Zeile im Plan steht als Position, das
+/// auslösende Training als Archiv-ID.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveTargetChange {
+    pub position: Option<i32>,
+    pub exercise_id: String,
+    pub session_id: Option<String>,
+    pub decision: String,
+    pub old_weight: Option<f64>,
+    pub new_weight: Option<f64>,
+    pub old_reps_min: Option<i32>,
+    pub old_reps_max: Option<i32>,
+    pub new_reps_min: Option<i32>,
+    pub new_reps_max: Option<i32>,
+    pub status: String,
+    pub created_at: String,
+}
+
+/// Ein abgeschlossenes Training. Laufende Trainings gehören nicht ins Archiv.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveSession {
+    pub id: String,
+    /// Fehlt bei freiem Training ohne Vorlage.
+    #[serde(default)]
+    pub workout_id: Option<String>,
+    pub started_at: String,
+    pub completed_at: String,
+    pub total_duration_minutes: i32,
+    pub total_sets: i32,
+    pub total_volume_kg: f32,
+    pub notes: Option<String>,
+    /// Vom Hintergrundjob geschlossen; fehlt in älteren Archiven.
+    #[serde(default)]
+    pub auto_closed: bool,
+    pub sets: Vec<ArchiveSet>,
+    /// Reihenfolge, Ersatz und Zusatzsätze dieser Sitzung
+    /// (`session_exercises`); fehlt in älteren Archiven.
+    #[serde(default)]
+    pub exercises: Vec<ArchiveSessionExercise>,
+    /// Zeit je Übung; fehlt in älteren Archiven und bei alten Trainings.
+    #[serde(default)]
+    pub exercise_times: Option<Vec<ArchiveExerciseTime>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveSessionExercise {
+    /// Position im Plan, negativ bei spontan hinzugefügten Übungen.
+    pub position: i32,
+    pub planned_exercise_id: String,
+    pub exercise_id: String,
+    pub sort_order: i32,
+    pub extra_sets: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveExerciseTime {
+    pub exercise_id: String,
+    pub seconds: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveSet {
+    pub exercise_id: String,
+    pub set_number: i32,
+    pub weight: Option<f32>,
+    pub reps: i32,
+    pub notes: Option<String>,
+    /// Fehlt in Archiven von vor der RPE-Erfassung.
+    #[serde(default)]
+    pub rpe: Option<f64>,
+    /// Fehlt in Archiven von vor den Satzarten, dann Arbeitssatz.
+    #[serde(default)]
+    pub set_type: Option<String>,
+    /// Zeile der Sitzung, zu der der Satz gehört; fehlt in älteren Archiven.
+    #[serde(default)]
+    pub position: Option<i32>,
+    pub completed_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveRecord {
+    pub exercise_id: String,
+    pub record_type: String,
+    pub weight: Option<f32>,
+    pub reps: Option<i32>,
+    pub volume_kg: Option<f32>,
+    pub achieved_at: String,
+}
+
+/// Archiv lesen und prüfen. Erst Kennung und Version, dann der Rest — eine
+/// Datei aus einem neueren Build soll „zu neu" melden, nicht „Feld fehlt".
+pub fn from_json(input: &str) -> Result<AccountArchive, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(input).map_err(|e| format!("not valid JSON: {e}"))?;
+
+    match value.get("format").and_then(|f| f.as_str()) {
+        Some(FORMAT_NAME) => {}
+        _ => return Err(format!("not a WOPlanner account archive (missing 'format: {FORMAT_NAME}')")),
+    }
+    let version = value
+        .get("version")
+        .and_then(|v| v.as_i64())
+        .ok_or_else(|| "missing or invalid 'version'".to_string())?;
+    if !(OLDEST_VERSION..=ARCHIVE_VERSION).contains(&version) {
+        return Err(format!(
+            "unsupported archive version {version}, this build reads versions {OLDEST_VERSION} to {ARCHIVE_VERSION}"
+        ));
+    }
+
+    check_fields_since(&value, version)?;
+
+    let archive: AccountArchive =
+        serde_json::from_value(value).map_err(|e| format!("invalid archive: {e}"))?;
+    validate(&archive)?;
+    Ok(archive)
+}
+
+pub fn to_json(archive: &AccountArchive) -> String {
+    // Eingerückt: ein Backup soll man zur Not auch von Hand lesen können.
+    serde_json::to_string_pretty(archive).unwrap_or_default()
+}
+
+/// Dateiname für den Download: Profilname und Datum, damit mehrere
+/// Backups nebeneinander liegen können.
+pub fn filename_for(user_name: &str, date: &str) -> String {
+    let mut slug = String::new();
+    for c in user_name.to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        format!("woplanner-{date}.json")
+    } else {
+        format!("woplanner-{slug}-{date}.json")
+    }
+}
+
+/// Die Objekte eines Abschnitts wie `sessions[].sets[]` oder `user`.
+fn section<'a>(value: &'a serde_json::Value, path: &str) -> Vec<&'a serde_json::Value> {
+    let mut current = vec![value];
+    for key in path.split('.') {
+        current = match key.strip_suffix("[]") {
+            Some(key) => current
+                .into_iter()
+                .filter_map(|v| v.get(key).and_then(|list| list.as_array()))
+                .flatten()
+                .collect(),
+            None => current.into_iter().filter_map(|v| v.get(key)).collect(),
+        };
+    }
+    current
+}
+
+/// Felder aus `FIELDS_SINCE` und freies Training erst ab ihrer Version.
+fn check_fields_since(value: &serde_json::Value, version: i64) -> Result<(), String> {
+    for (path, field, since) in FIELDS_SINCE {
+        if version < since && section(value, path).iter().any(|item| item.get(field).is_some()) {
+            return Err(format!(
+                "'{field}' in {path} needs archive version {since}, but the file says version {version}"
+            ));
+        }
+    }
+    for (field, since) in TOP_LEVEL_SINCE {
+        if version < since && value.get(field).is_some() {
+            return Err(format!(
+                "'{field}' needs archive version {since}, but the file says version {version}"
+            ));
+        }
+    }
+    if version < FREESTYLE_SINCE
+        && section(value, "sessions[]")
+            .iter()
+            .any(|session| session.get("workout_id").is_none_or(|id| id.is_null()))
+    {
+        return Err(format!(
+            "sessions without 'workout_id' need archive version {FREESTYLE_SINCE}, but the file says version {version}"
+        ));
+    }
+    Ok(())
+}
+
+/// Verweise innerhalb des Dokuments prüfen. Der Import schreibt erst, wenn
+/// alles auflösbar ist — ein halb eingespieltes Archiv lässt sich nicht
+/// sauber wiederholen.
+fn validate(archive: &AccountArchive) -> Result<(), String> {
+    if let Some(unit) = &archive.user.weight_unit
+        && unit != "kg"
+        && unit != "lb"
+    {
+        return Err(format!("user: unknown weight unit '{unit}'"));
+    }
+    if let Some(equipment) = &archive.equipment {
+        if equipment.unit != "kg" && equipment.unit != "lb" {
+            return Err(format!("equipment: unknown unit '{}'", equipment.unit));
+        }
+        Equipment::parse_plates(&equipment.plates).map_err(|e| format!("equipment: {e}"))?;
+    }
+
+    let mut exercise_ids = HashSet::new();
+    for exercise in &archive.exercises {
+        if exercise.name.trim().is_empty() {
+            return Err(format!("exercise '{}': name is empty", exercise.id));
+        }
+        if !exercise_ids.insert(exercise.id.as_str()) {
+            return Err(format!("exercise '{}' appears twice", exercise.id));
+        }
+    }
+
+    let mut workout_ids = HashSet::new();
+    for workout in &archive.workouts {
+        if !workout_ids.insert(workout.id.as_str()) {
+            return Err(format!("workout '{}' appears twice", workout.id));
+        }
+        let linked = workout.exercises.iter().map(|link| &link.exercise_id);
+        let changed = workout.target_changes.iter().map(|change| &change.exercise_id);
+        for exercise_id in linked.chain(changed) {
+            if !exercise_ids.contains(exercise_id.as_str()) {
+                return Err(format!(
+                    "workout '{}' refers to unknown exercise '{}'",
+                    workout.name, exercise_id
+                ));
+            }
+        }
+    }
+
+    let mut session_ids = HashSet::new();
+    for session in &archive.sessions {
+        if !session_ids.insert(session.id.as_str()) {
+            return Err(format!("session '{}' appears twice", session.id));
+        }
+        if let Some(workout_id) = &session.workout_id
+            && !workout_ids.contains(workout_id.as_str())
+        {
+            return Err(format!(
+                "session '{}' refers to unknown workout '{}'",
+                session.id, workout_id
+            ));
+        }
+        for set in &session.sets {
+            if !exercise_ids.contains(set.exercise_id.as_str()) {
+                return Err(format!(
+                    "session '{}' has a set for unknown exercise '{}'",
+                    session.id, set.exercise_id
+                ));
+            }
+        }
+        let planned = session.exercises.iter().flat_map(|e| [&e.planned_exercise_id, &e.exercise_id]);
+        let timed = session.exercise_times.iter().flatten().map(|t| &t.exercise_id);
+        for exercise_id in planned.chain(timed) {
+            if !exercise_ids.contains(exercise_id.as_str()) {
+                return Err(format!(
+                    "session '{}' refers to unknown exercise '{}'",
+                    session.id, exercise_id
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> AccountArchive {
+        AccountArchive {
+            format: FORMAT_NAME.into(),
+            version: ARCHIVE_VERSION,
+            exported_at: "2026-10-01T10:00:00+00:00".into(),
+            user: ArchiveUser {
+                id: "u-1".into(),
+                name: "Anna".into(),
+                created_at: "2026-01-01T00:00:00+00:00".into(),
+                weight_unit: Some("lb".into()),
+                warmup_ramps: Some(true),
+            },
+            exercises: vec![ArchiveExercise {
+                id: "ex-1".into(),
+                name: "Bench Press".into(),
+                instructions: "Sauber ablassen.".into(),
+                video_url: None,
+            }],
+            workouts: vec![ArchiveWorkout {
+                id: "w-1".into(),
+                name: "Push Day".into(),
+                description: None,
+                is_active: true,
+                schedule_type: "manual".into(),
+                schedule_day: None,
+                created_at: "2026-01-02T00:00:00+00:00".into(),
+                updated_at: "2026-01-02T00:00:00+00:00".into(),
+                exercises: vec![ArchiveWorkoutExercise {
+                    exercise_id: "ex-1".into(),
+                    position: 1,
+                    target_sets: 3,
+                    target_weight: Some(80.0),
+                    target_reps_min: Some(6),
+                    target_reps_max: Some(8),
+                    rest_seconds: Some(120),
+                    notes: None,
+                    progression: Some("linear".into()),
+                    group_label: Some("A".into()),
+                }],
+                target_changes: vec![ArchiveTargetChange {
+                    position: Some(1),
+                    exercise_id: "ex-1".into(),
+                    session_id: Some("s-1".into()),
+                    decision: "increase".into(),
+                    old_weight: Some(77.5),
+                    new_weight: Some(80.0),
+                    old_reps_min: Some(6),
+                    old_reps_max: Some(8),
+                    new_reps_min: Some(6),
+                    new_reps_max: Some(8),
+                    status: "applied".into(),
+                    created_at: "2026-02-01T18:46:00+00:00".into(),
+                }],
+            }],
+            sessions: vec![ArchiveSession {
+                id: "s-1".into(),
+                workout_id: Some("w-1".into()),
+                started_at: "2026-02-01T18:00:00+00:00".into(),
+                completed_at: "2026-02-01T18:45:00+00:00".into(),
+                total_duration_minutes: 45,
+                total_sets: 1,
+                total_volume_kg: 640.0,
+                notes: None,
+                auto_closed: false,
+                sets: vec![ArchiveSet {
+                    exercise_id: "ex-1".into(),
+                    set_number: 1,
+                    weight: Some(80.0),
+                    reps: 8,
+                    notes: None,
+                    rpe: Some(8.0),
+                    set_type: Some("working".into()),
+                    position: Some(1),
+                    completed_at: "2026-02-01T18:10:00+00:00".into(),
+                }],
+                exercises: vec![ArchiveSessionExercise {
+                    position: 1,
+                    planned_exercise_id: "ex-1".into(),
+                    exercise_id: "ex-1".into(),
+                    sort_order: 0,
+                    extra_sets: 1,
+                }],
+                exercise_times: Some(vec![ArchiveExerciseTime { exercise_id: "ex-1".into(), seconds: 600 }]),
+            }],
+            personal_records: vec![],
+            equipment: Some(ArchiveEquipment {
+                unit: "lb".into(),
+                bar_weight: 45.0,
+                collar_weight: 0.0,
+                plates: "45, 25, 10x2, 5, 2.5".into(),
+                updated_at: "2026-01-03T00:00:00+00:00".into(),
+            }),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let archive = sample();
+        assert_eq!(from_json(&to_json(&archive)).unwrap(), archive);
+    }
+
+    #[test]
+    fn filename_carries_name_and_date() {
+        assert_eq!(filename_for("Anna Maria", "2026-10-18"), "woplanner-anna-maria-2026-10-18.json");
+        assert_eq!(filename_for("Ümit", "2026-10-18"), "woplanner-mit-2026-10-18.json");
+        assert_eq!(filename_for("!!", "2026-10-18"), "woplanner-2026-10-18.json");
+    }
+
+    #[test]
+    fn foreign_json_is_rejected() {
+        let err = from_json(r#"{"version": 1}"#).unwrap_err();
+        assert!(err.contains("not a WOPlanner account archive"), "{err}");
+        assert!(from_json("nope").unwrap_err().contains("not valid JSON"));
+    }
+
+    #[test]
+    fn newer_version_is_rejected_before_fields_are_checked() {
+        let json = format!(
+            r#"{{"format": "{FORMAT_NAME}", "version": {}}}"#,
+            ARCHIVE_VERSION + 1
+        );
+        let err = from_json(&json).unwrap_err();
+        assert!(err.contains("unsupported archive version"), "{err}");
+    }
+
+    /// Das Beispiel als JSON ohne die Felder, die nach `version` kamen.
+    fn as_version(version: i64) -> serde_json::Value {
+        let mut archive = sample();
+        archive.version = version;
+        let mut value = serde_json::to_value(&archive).unwrap();
+        for (path, field, since) in FIELDS_SINCE {
+            if since > version {
+                for item in section_mut(&mut value, path) {
+                    item.as_object_mut().unwrap().remove(field);
+                }
+            }
+        }
+        for (field, since) in TOP_LEVEL_SINCE {
+            if since > version {
+                value.as_object_mut().unwrap().remove(field);
+            }
+        }
+        value
+    }
+
+    #[test]
+    fn version_1_archives_still_import() {
+        let value = as_version(1);
+        let read = from_json(&value.to_string()).unwrap();
+        assert_eq!(read.version, 1);
+        assert_eq!(read.sessions[0].sets[0].rpe, None);
+        assert_eq!(read.workouts[0].exercises[0].group_label, None);
+
+        let read = from_json(&as_version(2).to_string()).unwrap();
+        assert_eq!(read.sessions[0].sets[0].rpe, Some(8.0));
+        assert_eq!(read.sessions[0].exercise_times, None);
+        assert!(read.workouts[0].target_changes.is_empty());
+        assert_eq!((read.user.weight_unit, read.equipment), (None, None));
+
+        let read = from_json(&as_version(3).to_string()).unwrap();
+        assert_eq!(read.sessions[0].sets[0].position, None);
+        assert!(read.equipment.is_some());
+    }
+
+    #[test]
+    fn fields_before_their_version_are_rejected() {
+        let mut archive = sample();
+        archive.version = 1;
+        let err = from_json(&to_json(&archive)).unwrap_err();
+        assert!(err.contains("'progression' in workouts[].exercises[] needs archive version 2"), "{err}");
+
+        let mut value = as_version(1);
+        value["sessions"][0]["workout_id"] = serde_json::Value::Null;
+        let err = from_json(&value.to_string()).unwrap_err();
+        assert!(err.contains("without 'workout_id' need archive version 2"), "{err}");
+
+        let mut value = as_version(2);
+        value["equipment"] = serde_json::to_value(&sample().equipment).unwrap();
+        let err = from_json(&value.to_string()).unwrap_err();
+        assert!(err.contains("'equipment' needs archive version 3"), "{err}");
+    }
+
+    fn section_mut<'a>(value: &'a mut serde_json::Value, path: &str) -> Vec<&'a mut serde_json::Value> {
+        let mut current = vec![value];
+        for key in path.split('.') {
+            current = match key.strip_suffix("[]") {
+                Some(key) => current
+                    .into_iter()
+                    .filter_map(|v| v.get_mut(key).and_then(|list| list.as_array_mut()))
+                    .flatten()
+                    .collect(),
+                None => current.into_iter().filter_map(|v| v.get_mut(key)).collect(),
+            };
+        }
+        current
+    }
+
+    #[test]
+    fn dangling_references_are_rejected() {
+        let mut archive = sample();
+        archive.sessions[0].sets[0].exercise_id = "ex-missing".into();
+        let err = from_json(&to_json(&archive)).unwrap_err();
+        assert!(err.contains("unknown exercise 'ex-missing'"), "{err}");
+
+        let mut archive = sample();
+        archive.sessions[0].workout_id = Some("w-missing".into());
+        let err = from_json(&to_json(&archive)).unwrap_err();
+        assert!(err.contains("unknown workout 'w-missing'"), "{err}");
+
+        let mut archive = sample();
+        archive.sessions[0].exercises[0].exercise_id = "ex-sub".into();
+        let err = from_json(&to_json(&archive)).unwrap_err();
+        assert!(err.contains("unknown exercise 'ex-sub'"), "{err}");
+
+        let mut archive = sample();
+        archive.equipment.as_mut().unwrap().plates = "heavy".into();
+        assert!(from_json(&to_json(&archive)).unwrap_err().starts_with("equipment: "));
+    }
+}
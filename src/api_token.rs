@@ -0,0 +1,113 @@
+//! Persönliche Zugangstoken für die JSON-API. Ein Token ist eine zufällige
+//! Zeichenkette mit erkennbarem Präfix; in der Datenbank steht nur sein
+//! SHA-256-Hash. Ein langsamer Passwort-Hash ist unnötig — 244 Bit Zufall
+//! lassen sich nicht durchprobieren.
+
+use axum::http::Method;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Macht Token in Logs und Secret-Scannern erkennbar.
+pub const PREFIX: &str = "wop_";
+
+/// Was ein Token darf. Die Browser-Sitzung darf immer alles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenScope {
+    /// Nur lesende Requests — für Statistik-Abrufe und Dashboards.
+    #[default]
+    Read,
+    ReadWrite,
+}
+
+impl TokenScope {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim() {
+            "read" => Some(TokenScope::Read),
+            "read_write" => Some(TokenScope::ReadWrite),
+            _ => None,
+        }
+    }
+
+    /// Unbekanntes aus der Datenbank gilt als das Engere.
+    pub fn from_db(raw: &str) -> Self {
+        Self::parse(raw).unwrap_or(TokenScope::Read)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::Read => "read",
+            TokenScope::ReadWrite => "read_write",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TokenScope::Read => "Read only",
+            TokenScope::ReadWrite => "Read & write",
+        }
+    }
+
+    /// Lesen heißt: eine Methode ohne Nebenwirkungen.
+    pub fn allows(&self, method: &Method) -> bool {
+        match self {
+            TokenScope::Read => matches!(*method, Method::GET | Method::HEAD),
+            TokenScope::ReadWrite => true,
+        }
+    }
+}
+
+/// Neues Token; zwei UUIDs v4 liefern den Zufall aus dem Betriebssystem.
+pub fn generate() -> String {
+    format!("{PREFIX}{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// Hex-kodierter SHA-256, wie in `api_tokens.token_hash`.
+pub fn hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Token aus einem `Authorization`-Header, `None` für alles außer `Bearer`.
+pub fn from_authorization(header: &str) -> Option<&str> {
+    let (scheme, token) = header.trim().split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_are_unique_and_hashed_stably() {
+        let token = generate();
+        assert!(token.starts_with(PREFIX));
+        assert_eq!(token.len(), PREFIX.len() + 64);
+        assert_ne!(token, generate());
+        assert_eq!(hash(&token), hash(&token));
+        assert_eq!(hash(&token).len(), 64);
+        assert_ne!(hash(&token), hash(&generate()));
+    }
+
+    #[test]
+    fn test_read_scope_only_allows_safe_methods() {
+        assert!(TokenScope::Read.allows(&Method::GET));
+        assert!(!TokenScope::Read.allows(&Method::POST));
+        assert!(!TokenScope::Read.allows(&Method::DELETE));
+        assert!(TokenScope::ReadWrite.allows(&Method::DELETE));
+        assert_eq!(TokenScope::parse("read_write"), Some(TokenScope::ReadWrite));
+        assert_eq!(TokenScope::parse("admin"), None);
+        assert_eq!(TokenScope::from_db("admin"), TokenScope::Read);
+    }
+
+    #[test]
+    fn test_from_authorization() {
+        assert_eq!(from_authorization("Bearer wop_abc"), Some("wop_abc"));
+        assert_eq!(from_authorization("bearer  wop_abc "), Some("wop_abc"));
+        assert_eq!(from_authorization("Basic dXNlcjpwdw=="), None);
+        assert_eq!(from_authorization("Bearer "), None);
+        assert_eq!(from_authorization("wop_abc"), None);
+    }
+}
@@ -0,0 +1,167 @@
+//! CSV für Tabellenkalkulationen — RFC 4180, Zeilenende CRLF.
+//!
+//! Ohne Bibliothek, aus demselben Grund wie `workout_yaml`: es braucht nur
+//! das Quoting. Gelesen wird, was Export-Funktionen anderer Apps schreiben —
+//! Komma oder Semikolon, Felder in Anführungszeichen auch über Zeilen.
+
+/// Ein Feld so ausgeben, dass Excel, Numbers und LibreOffice es als Text
+/// lesen. Notizen sind Freitext: ein führendes `=`, `+`, `-` oder `@` würde
+/// dort als Formel ausgeführt, deshalb bekommt es ein `'` davor.
+pub fn field(value: &str) -> String {
+    let value = match value.chars().next() {
+        Some('=' | '+' | '-' | '@') => format!("'{value}"),
+        _ => value.to_string(),
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Eine Zeile aus bereits formatierten Werten, mit Zeilenende.
+pub fn row<S: AsRef<str>>(fields: &[S]) -> String {
+    let mut line = fields
+        .iter()
+        .map(|f| field(f.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
+
+/// Zahl ohne überflüssige Nachkommastellen: `80`, `82.5`. Leer für `None`
+/// — Körpergewicht hat kein Gewicht, nicht `0`.
+pub fn number(value: Option<f64>) -> String {
+    match value {
+        Some(v) => {
+            let rounded = (v * 100.0).round() / 100.0;
+            format!("{rounded}")
+        }
+        None => String::new(),
+    }
+}
+
+/// Eine gelesene Zeile mit der Zeilennummer, auf der sie beginnt.
+#[derive(Debug, PartialEq)]
+pub struct Record {
+    pub line: usize,
+    pub fields: Vec<String>,
+}
+
+/// Trennzeichen aus der Kopfzeile raten. Apps mit deutschem oder
+/// französischem Gebietsschema schreiben `;`, weil `,` das Dezimalzeichen ist.
+pub fn detect_delimiter(input: &str) -> char {
+    let header = input.lines().next().unwrap_or("");
+    if header.matches(';').count() > header.matches(',').count() { ';' } else { ',' }
+}
+
+/// CSV lesen. Leere Zeilen fallen weg, ein BOM am Anfang auch.
+pub fn parse(input: &str, delimiter: char) -> Result<Vec<Record>, String> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            c if c == delimiter => fields.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                if fields.iter().any(|f| !f.is_empty()) {
+                    records.push(Record { line: record_line, fields: std::mem::take(&mut fields) });
+                }
+                fields.clear();
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(format!("line {record_line}: quoted field is not closed"));
+    }
+    fields.push(field);
+    if fields.iter().any(|f| !f.is_empty()) {
+        records.push(Record { line: record_line, fields });
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_fields_stay_unquoted() {
+        assert_eq!(row(&["2026-02-01", "Push Day", "8"]), "2026-02-01,Push Day,8\r\n");
+    }
+
+    #[test]
+    fn separators_quotes_and_newlines_are_quoted() {
+        assert_eq!(field("schwer, aber sauber"), "\"schwer, aber sauber\"");
+        assert_eq!(field("der \"letzte\" Satz"), "\"der \"\"letzte\"\" Satz\"");
+        assert_eq!(field("zwei\nZeilen"), "\"zwei\nZeilen\"");
+    }
+
+    #[test]
+    fn formulas_are_defused() {
+        assert_eq!(field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(field("-2 reps"), "'-2 reps");
+        assert_eq!(field("@home"), "'@home");
+    }
+
+    #[test]
+    fn parse_reads_quotes_newlines_and_line_numbers() {
+        let input = "\u{feff}a,b\r\n\"x, y\",\"zwei\nZeilen\"\r\n\r\n\"sagt \"\"hi\"\"\",\n";
+        let records = parse(input, ',').unwrap();
+        assert_eq!(records, vec![
+            Record { line: 1, fields: vec!["a".into(), "b".into()] },
+            Record { line: 2, fields: vec!["x, y".into(), "zwei\nZeilen".into()] },
+            Record { line: 5, fields: vec!["sagt \"hi\"".into(), "".into()] },
+        ]);
+    }
+
+    #[test]
+    fn parse_round_trips_what_row_writes() {
+        let written = row(&["schwer, aber \"sauber\"", "x"]);
+        let records = parse(&written, ',').unwrap();
+        assert_eq!(records[0].fields, vec!["schwer, aber \"sauber\"", "x"]);
+    }
+
+    #[test]
+    fn semicolon_files_are_detected() {
+        assert_eq!(detect_delimiter("Date;Workout Name;Weight\n1;2;3,5"), ';');
+        assert_eq!(detect_delimiter("Date,Exercise,Weight (kgs)"), ',');
+        assert!(parse("\"offen,1\n", ',').unwrap_err().contains("not closed"));
+    }
+
+    #[test]
+    fn numbers_drop_trailing_zeros() {
+        assert_eq!(number(Some(80.0)), "80");
+        assert_eq!(number(Some(82.5)), "82.5");
+        assert_eq!(number(Some(0.1 + 0.2)), "0.3");
+        assert_eq!(number(None), "");
+    }
+}
@@ -24,6 +24,9 @@ pub enum AppError {
     #[error("Not authenticated")]
     Unauthorized,
 
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("Resource not found: {0}")]
     NotFound(String),
 
@@ -134,6 +137,23 @@ pub fn error_to_html(error: &AppError, headers: Option<&axum::http::HeaderMap>)
                 page.render().unwrap_or_else(|_| "Unauthorized.".to_string())
             }
         }
+        AppError::Forbidden(msg) => {
+            let fragment = ErrorFragment {
+                message: format!("Forbidden: {}", msg),
+            };
+            if is_htmx {
+                fragment.render().unwrap_or_else(|_| "Forbidden.".to_string())
+            } else {
+                let page = ErrorPage {
+                    status_code: 403,
+                    message: "Forbidden".to_string(),
+                    detail: msg.clone(),
+                    current_user: None,
+                    is_dashboard: false,
+                };
+                page.render().unwrap_or_else(|_| "Forbidden.".to_string())
+            }
+        }
         AppError::NotFound(msg) => {
             let fragment = ErrorFragment {
                 message: format!("Not found: {}", msg),
@@ -222,6 +242,11 @@ impl IntoResponse for AppError {
                     "error": "Not authenticated"
                 }))).into_response()
             }
+            AppError::Forbidden(msg) => {
+                (StatusCode::FORBIDDEN, Json(json!({
+                    "error": msg
+                }))).into_response()
+            }
             AppError::NotFound(msg) => {
                 (StatusCode::NOT_FOUND, Json(json!({
                     "error": msg
@@ -0,0 +1,877 @@
+use crate::account_archive::{
+    ARCHIVE_VERSION, AccountArchive, ArchiveEquipment, ArchiveExercise, ArchiveExerciseTime,
+    ArchiveRecord, ArchiveSession, ArchiveSessionExercise, ArchiveSet, ArchiveTargetChange,
+    ArchiveUser, ArchiveWorkout, ArchiveWorkoutExercise, FORMAT_NAME, MAX_ARCHIVE_BYTES,
+    filename_for, from_json, to_json,
+};
+use crate::error::AppError;
+use crate::handlers::get_current_user;
+use crate::handlers::personal_records::recompute_prs;
+use crate::handlers::workouts::{find_exercise_by_name, insert_exercise};
+use crate::models::{Exercise, ExerciseTime, SetType, User};
+use crate::progression::Strategy;
+use axum::{
+    Form, Router,
+    extract::{DefaultBodyLimit, State},
+    http::{HeaderMap, HeaderValue},
+    response::{Html, IntoResponse},
+    routing::{get, post},
+};
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::{SqliteConnection, SqlitePool};
+use std::collections::{HashMap, HashSet};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+/// Alle Daten des Nutzers als Archiv herunterladen. Format: `account_archive`.
+pub async fn export_account(
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+
+    let mut conn = database_pool.acquire().await?;
+    let archive = build_archive(&mut conn, &current_user).await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "Content-Type",
+        HeaderValue::from_static("application/json; charset=utf-8"),
+    );
+    let date = Utc::now().format("%Y-%m-%d").to_string();
+    headers.insert(
+        "Content-Disposition",
+        HeaderValue::from_str(&format!(
+            "attachment; filename=\"{}\"",
+            filename_for(&current_user.name, &date)
+        ))
+        .unwrap_or(HeaderValue::from_static("attachment; filename=\"woplanner.json\"")),
+    );
+
+    Ok((headers, to_json(&archive)).into_response())
+}
+
+/// Das Archiv aus der Datenbank zusammenstellen. Übungen nur, soweit Pläne
+/// oder Sätze des Nutzers sie benutzen — die Bibliothek ist geteilt.
+pub async fn build_archive(
+    conn: &mut SqliteConnection,
+    user: &User,
+) -> Result<AccountArchive, AppError> {
+    let mut workouts = Vec::new();
+    let workout_rows = sqlx::query!(
+        r#"SELECT id, name, description, COALESCE(is_active, 1) as "is_active!: bool",
+                  schedule_type as "schedule_type!: String", schedule_day as "schedule_day: i32",
+                  created_at, updated_at
+           FROM workouts WHERE user_id = ? ORDER BY created_at"#,
+        user.id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for row in workout_rows {
+        let exercises = sqlx::query_as!(
+            ArchiveWorkoutExercise,
+            r#"SELECT exercise_id, position as "position: i32", target_sets as "target_sets: i32",
+                      target_weight as "target_weight: f32",
+                      target_reps_min as "target_reps_min: i32", target_reps_max as "target_reps_max: i32",
+                      rest_seconds as "rest_seconds: i32", notes, progression as "progression?",
+                      group_label
+               FROM workout_exercises WHERE workout_id = ? ORDER BY position"#,
+            row.id
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let target_changes = sqlx::query_as!(
+            ArchiveTargetChange,
+            r#"SELECT we.position as "position: i32", tc.exercise_id, tc.completed_workout_id as session_id,
+                      tc.decision, tc.old_weight as "old_weight: f64", tc.new_weight as "new_weight: f64",
+                      tc.old_reps_min as "old_reps_min: i32", tc.old_reps_max as "old_reps_max: i32",
+                      tc.new_reps_min as "new_reps_min: i32", tc.new_reps_max as "new_reps_max: i32",
+                      tc.status, tc.created_at
+               FROM target_changes tc
+               LEFT JOIN workout_exercises we ON we.id = tc.workout_exercise_id
+               WHERE tc.workout_id = ? ORDER BY tc.created_at"#,
+            row.id
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        workouts.push(ArchiveWorkout {
+            id: row.id,
+            name: row.name,
+            description: row.description,
+            is_active: row.is_active,
+            schedule_type: row.schedule_type,
+            schedule_day: row.schedule_day,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            exercises,
+            target_changes,
+        });
+    }
+
+    let mut sessions = Vec::new();
+    let session_rows = sqlx::query!(
+        r#"SELECT id, workout_id, started_at, completed_at,
+                  total_duration_minutes as "total_duration_minutes: i32",
+                  total_sets as "total_sets: i32", total_volume_kg as "total_volume_kg: f32", notes,
+                  auto_closed as "auto_closed: bool", exercise_times
+           FROM completed_workouts WHERE user_id = ? ORDER BY completed_at"#,
+        user.id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for row in session_rows {
+        let sets = sqlx::query_as!(
+            ArchiveSet,
+            r#"SELECT exercise_id, set_number as "set_number: i32", weight as "weight: f32",
+                      reps as "reps: i32", notes, rpe as "rpe: f64", set_type as "set_type?",
+                      position as "position: i32", completed_at
+               FROM completed_sets WHERE active_workout_id = ? ORDER BY completed_at, set_number"#,
+            row.id
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let exercises = sqlx::query_as!(
+            ArchiveSessionExercise,
+            r#"SELECT position as "position: i32", planned_exercise_id, exercise_id,
+                      sort_order as "sort_order: i32", extra_sets as "extra_sets: i32"
+               FROM session_exercises WHERE session_id = ? ORDER BY sort_order, position"#,
+            row.id
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let exercise_times = row
+            .exercise_times
+            .and_then(|json| serde_json::from_str::<Vec<ExerciseTime>>(&json).ok())
+            .map(|times| {
+                times
+                    .into_iter()
+                    .map(|t| ArchiveExerciseTime { exercise_id: t.exercise_id, seconds: t.seconds })
+                    .collect()
+            });
+
+        sessions.push(ArchiveSession {
+            id: row.id,
+            workout_id: row.workout_id,
+            started_at: row.started_at,
+            completed_at: row.completed_at,
+            total_duration_minutes: row.total_duration_minutes,
+            total_sets: row.total_sets,
+            total_volume_kg: row.total_volume_kg,
+            notes: row.notes,
+            auto_closed: row.auto_closed,
+            sets,
+            exercises,
+            exercise_times,
+        });
+    }
+
+    // Rekorde aus einem laufenden Training fehlen: ihr Satz steht (noch)
+    // nicht im Archiv.
+    let personal_records = sqlx::query_as!(
+        ArchiveRecord,
+        r#"SELECT pr.exercise_id, pr.record_type, pr.weight as "weight: f32", pr.reps as "reps: i32",
+                  pr.volume_kg as "volume_kg: f32", pr.achieved_at
+           FROM personal_records pr
+           JOIN completed_sets cs ON cs.id = pr.completed_set_id
+           JOIN completed_workouts cw ON cw.id = cs.active_workout_id
+           WHERE pr.user_id = ?
+           ORDER BY pr.exercise_id, pr.record_type"#,
+        user.id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let exercises = sqlx::query_as!(
+        ArchiveExercise,
+        r#"SELECT id as "id!", name, instructions, video_url FROM exercises
+           WHERE id IN (SELECT we.exercise_id FROM workout_exercises we
+                        JOIN workouts w ON w.id = we.workout_id WHERE w.user_id = ?)
+              OR id IN (SELECT tc.exercise_id FROM target_changes tc
+                        JOIN workouts w ON w.id = tc.workout_id WHERE w.user_id = ?)
+              OR id IN (SELECT cs.exercise_id FROM completed_sets cs
+                        JOIN completed_workouts cw ON cw.id = cs.active_workout_id WHERE cw.user_id = ?)
+              OR id IN (SELECT se.exercise_id FROM session_exercises se
+                        JOIN completed_workouts cw ON cw.id = se.session_id WHERE cw.user_id = ?)
+              OR id IN (SELECT se.planned_exercise_id FROM session_exercises se
+                        JOIN completed_workouts cw ON cw.id = se.session_id WHERE cw.user_id = ?)
+           ORDER BY name"#,
+        user.id,
+        user.id,
+        user.id,
+        user.id,
+        user.id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let equipment = sqlx::query_as!(
+        ArchiveEquipment,
+        r#"SELECT unit, bar_weight as "bar_weight: f64", collar_weight as "collar_weight: f64", plates, updated_at
+           FROM equipment_profiles WHERE user_id = ?"#,
+        user.id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    Ok(AccountArchive {
+        format: FORMAT_NAME.to_string(),
+        version: ARCHIVE_VERSION,
+        exported_at: Utc::now().to_rfc3339(),
+        user: ArchiveUser {
+            id: user.id.clone(),
+            name: user.name.clone(),
+            created_at: user.created_at.clone(),
+            weight_unit: Some(user.weight_unit.clone()),
+            warmup_ramps: Some(user.warmup_ramps),
+        },
+        exercises,
+        workouts,
+        sessions,
+        personal_records,
+        equipment,
+    })
+}
+
+#[derive(Deserialize)]
+pub struct ImportAccountForm {
+    pub archive: String,
+}
+
+/// Was ein Import geschrieben hat. Bereits vorhandene Einträge zählen unter
+/// `skipped` — beim zweiten Import desselben Archivs steht dort alles.
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportSummary {
+    pub workouts: usize,
+    pub sessions: usize,
+    pub sets: usize,
+    pub exercises_created: usize,
+    pub skipped: usize,
+}
+
+/// Archiv in das angemeldete Profil einspielen. Erst vollständig prüfen,
+/// dann in einer Transaktion schreiben.
+pub async fn import_account(
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Form(form): Form<ImportAccountForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+
+    let archive = from_json(&form.archive).map_err(AppError::BadRequest)?;
+
+    let mut tx = database_pool.begin().await?;
+    let summary = import_archive(&mut tx, &current_user.id, &archive).await?;
+    tx.commit().await?;
+
+    Ok(Html(format!(
+        "Imported {} plans, {} sessions and {} sets ({} new exercises). {} entries were already present.",
+        summary.workouts, summary.sessions, summary.sets, summary.exercises_created, summary.skipped
+    )))
+}
+
+/// Lokale ID, auf die eine Quell-ID schon einmal abgebildet wurde.
+async fn mapped_id(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+    kind: &str,
+    source_id: &str,
+) -> Result<Option<String>, AppError> {
+    Ok(sqlx::query_scalar!(
+        "SELECT local_id FROM imported_ids WHERE user_id = ? AND kind = ? AND source_id = ?",
+        user_id,
+        kind,
+        source_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?)
+}
+
+async fn remember_id(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+    kind: &str,
+    source_id: &str,
+    local_id: &str,
+) -> Result<(), AppError> {
+    let now = Utc::now().to_rfc3339();
+    sqlx::query!(
+        "INSERT OR REPLACE INTO imported_ids (user_id, kind, source_id, local_id, created_at)
+         VALUES (?, ?, ?, ?, ?)",
+        user_id,
+        kind,
+        source_id,
+        local_id,
+        now
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// Das geprüfte Archiv schreiben. Jede Zeile bekommt eine neue ID, außer sie
+/// ist schon da — erkannt an der Zuordnung eines früheren Imports oder, beim
+/// Zurückspielen eines eigenen Backups, an derselben ID beim selben Nutzer.
+///
+/// Einheit, Aufwärmrampe und Scheiben übernimmt das Profil aus dem Archiv,
+/// soweit es sie enthält.
+pub async fn import_archive(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+    archive: &AccountArchive,
+) -> Result<ImportSummary, AppError> {
+    let mut summary = ImportSummary::default();
+
+    if let Some(unit) = &archive.user.weight_unit {
+        sqlx::query!("UPDATE users SET weight_unit = ? WHERE id = ?", unit, user_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+    if let Some(warmup_ramps) = archive.user.warmup_ramps {
+        sqlx::query!("UPDATE users SET warmup_ramps = ? WHERE id = ?", warmup_ramps, user_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+    if let Some(equipment) = &archive.equipment {
+        sqlx::query!(
+            "INSERT INTO equipment_profiles (user_id, unit, bar_weight, collar_weight, plates, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(user_id) DO UPDATE SET
+                unit = excluded.unit,
+                bar_weight = excluded.bar_weight,
+                collar_weight = excluded.collar_weight,
+                plates = excluded.plates,
+                updated_at = excluded.updated_at",
+            user_id,
+            equipment.unit,
+            equipment.bar_weight,
+            equipment.collar_weight,
+            equipment.plates,
+            equipment.updated_at
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    // Übungen: frühere Zuordnung, gleiche ID, gleicher Name — erst dann neu.
+    // Vorhandene Übungen bleiben unverändert, wie beim Plan-Import.
+    let mut exercise_ids: HashMap<&str, String> = HashMap::new();
+    for exercise in &archive.exercises {
+        let mut local = None;
+        let candidates = [
+            mapped_id(conn, user_id, "exercise", &exercise.id).await?,
+            Some(exercise.id.clone()),
+        ];
+        for candidate in candidates.into_iter().flatten() {
+            if sqlx::query_scalar!("SELECT id FROM exercises WHERE id = ?", candidate)
+                .fetch_optional(&mut *conn)
+                .await?
+                .is_some()
+            {
+                local = Some(candidate);
+                break;
+            }
+        }
+        if local.is_none() {
+            local = find_exercise_by_name(conn, &exercise.name).await?;
+        }
+        let local = match local {
+            Some(id) => id,
+            None => {
+                let new_exercise = Exercise::new(
+                    exercise.name.trim().to_string(),
+                    exercise.instructions.clone(),
+                    exercise.video_url.clone(),
+                );
+                insert_exercise(conn, &new_exercise).await?;
+                summary.exercises_created += 1;
+                new_exercise.id
+            }
+        };
+        remember_id(conn, user_id, "exercise", &exercise.id, &local).await?;
+        exercise_ids.insert(exercise.id.as_str(), local);
+    }
+
+    let mut workout_ids: HashMap<&str, String> = HashMap::new();
+    // Zeilen im Plan neu importierter Pläne, für das Protokoll der Ziele.
+    let mut link_ids: HashMap<(&str, i32), String> = HashMap::new();
+    let mut new_workouts = Vec::new();
+    for workout in &archive.workouts {
+        if let Some(local) = existing_row(conn, user_id, "workout", &workout.id).await? {
+            remember_id(conn, user_id, "workout", &workout.id, &local).await?;
+            workout_ids.insert(workout.id.as_str(), local);
+            summary.skipped += 1;
+            continue;
+        }
+
+        let local = Uuid::new_v4().to_string();
+        sqlx::query!(
+            "INSERT INTO workouts (id, user_id, name, description, is_active, schedule_type, schedule_day, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            local,
+            user_id,
+            workout.name,
+            workout.description,
+            workout.is_active,
+            workout.schedule_type,
+            workout.schedule_day,
+            workout.created_at,
+            workout.updated_at
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        for link in &workout.exercises {
+            let link_id = Uuid::new_v4().to_string();
+            let exercise_id = &exercise_ids[link.exercise_id.as_str()];
+            let progression = Strategy::from_db(link.progression.as_deref().unwrap_or_default()).as_str();
+            sqlx::query!(
+                "INSERT INTO workout_exercises (id, workout_id, exercise_id, position, target_sets, target_weight, target_reps_min, target_reps_max, rest_seconds, notes, progression, group_label, created_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                link_id,
+                local,
+                exercise_id,
+                link.position,
+                link.target_sets,
+                link.target_weight,
+                link.target_reps_min,
+                link.target_reps_max,
+                link.rest_seconds,
+                link.notes,
+                progression,
+                link.group_label,
+                workout.created_at
+            )
+            .execute(&mut *conn)
+            .await?;
+            link_ids.insert((workout.id.as_str(), link.position), link_id);
+        }
+
+        remember_id(conn, user_id, "workout", &workout.id, &local).await?;
+        new_workouts.push(workout);
+        workout_ids.insert(workout.id.as_str(), local);
+        summary.workouts += 1;
+    }
+
+    let mut session_ids: HashMap<&str, String> = HashMap::new();
+    let mut touched_exercises = HashSet::new();
+    for archived in &archive.sessions {
+        if let Some(local) = existing_row(conn, user_id, "session", &archived.id).await? {
+            remember_id(conn, user_id, "session", &archived.id, &local).await?;
+            session_ids.insert(archived.id.as_str(), local);
+            summary.skipped += 1;
+            continue;
+        }
+
+        let local = Uuid::new_v4().to_string();
+        let workout_id = archived.workout_id.as_deref().map(|id| &workout_ids[id]);
+        let exercise_times = archived.exercise_times.as_ref().and_then(|times| {
+            let times: Vec<ExerciseTime> = times
+                .iter()
+                .map(|t| ExerciseTime {
+                    exercise_id: exercise_ids[t.exercise_id.as_str()].clone(),
+                    seconds: t.seconds,
+                })
+                .collect();
+            serde_json::to_string(&times).ok()
+        });
+        sqlx::query!(
+            "INSERT INTO completed_workouts (id, user_id, workout_id, started_at, completed_at, total_duration_minutes, total_sets, total_volume_kg, notes, created_at, auto_closed, exercise_times)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            local,
+            user_id,
+            workout_id,
+            archived.started_at,
+            archived.completed_at,
+            archived.total_duration_minutes,
+            archived.total_sets,
+            archived.total_volume_kg,
+            archived.notes,
+            archived.completed_at,
+            archived.auto_closed,
+            exercise_times
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        for slot in &archived.exercises {
+            let planned = &exercise_ids[slot.planned_exercise_id.as_str()];
+            let exercise_id = &exercise_ids[slot.exercise_id.as_str()];
+            sqlx::query!(
+                "INSERT INTO session_exercises (session_id, position, planned_exercise_id, exercise_id, sort_order, extra_sets)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+                local,
+                slot.position,
+                planned,
+                exercise_id,
+                slot.sort_order,
+                slot.extra_sets
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        for set in &archived.sets {
+            let set_id = Uuid::new_v4().to_string();
+            let exercise_id = &exercise_ids[set.exercise_id.as_str()];
+            let set_type = SetType::from_db(set.set_type.as_deref().unwrap_or_default()).as_str();
+            sqlx::query!(
+                "INSERT INTO completed_sets (id, active_workout_id, exercise_id, set_number, weight, reps, notes, rpe, set_type, position, completed_at, created_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                set_id,
+                local,
+                exercise_id,
+                set.set_number,
+                set.weight,
+                set.reps,
+                set.notes,
+                set.rpe,
+                set_type,
+                set.position,
+                set.completed_at,
+                set.completed_at
+            )
+            .execute(&mut *conn)
+            .await?;
+            touched_exercises.insert(exercise_id.clone());
+            summary.sets += 1;
+        }
+
+        remember_id(conn, user_id, "session", &archived.id, &local).await?;
+        session_ids.insert(archived.id.as_str(), local);
+        summary.sessions += 1;
+    }
+
+    // Erst jetzt, weil ein Eintrag auf sein Training verweist. Fehlt das
+    // Training im Archiv (gelöscht), bleibt der Verweis leer.
+    for workout in new_workouts {
+        let local = &workout_ids[workout.id.as_str()];
+        for change in &workout.target_changes {
+            let id = Uuid::new_v4().to_string();
+            let link_id = change.position.and_then(|position| link_ids.get(&(workout.id.as_str(), position)));
+            let exercise_id = &exercise_ids[change.exercise_id.as_str()];
+            let session_id = change.session_id.as_deref().and_then(|id| session_ids.get(id));
+            sqlx::query!(
+                "INSERT INTO target_changes
+                    (id, workout_id, workout_exercise_id, exercise_id, completed_workout_id, decision, old_weight,
+                     new_weight, old_reps_min, old_reps_max, new_reps_min, new_reps_max, status, created_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                id,
+                local,
+                link_id,
+                exercise_id,
+                session_id,
+                change.decision,
+                change.old_weight,
+                change.new_weight,
+                change.old_reps_min,
+                change.old_reps_max,
+                change.new_reps_min,
+                change.new_reps_max,
+                change.status,
+                change.created_at
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    // Rekorde nicht übernehmen, sondern neu rechnen: nur so passen sie, wenn
+    // in diesem Profil schon eigene Sätze liegen.
+    for exercise_id in &touched_exercises {
+        recompute_prs(conn, user_id, exercise_id).await?;
+    }
+
+    Ok(summary)
+}
+
+/// Lokale Zeile zu einem Plan oder Training aus dem Archiv, falls schon da.
+async fn existing_row(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+    kind: &str,
+    source_id: &str,
+) -> Result<Option<String>, AppError> {
+    let candidates = [mapped_id(conn, user_id, kind, source_id).await?, Some(source_id.to_string())];
+    for candidate in candidates.into_iter().flatten() {
+        let found = match kind {
+            "workout" => sqlx::query_scalar!(
+                "SELECT id FROM workouts WHERE id = ? AND user_id = ?",
+                candidate,
+                user_id
+            )
+            .fetch_optional(&mut *conn)
+            .await?,
+            _ => sqlx::query_scalar!(
+                "SELECT id FROM completed_workouts WHERE id = ? AND user_id = ?",
+                candidate,
+                user_id
+            )
+            .fetch_optional(&mut *conn)
+            .await?,
+        };
+        if found.is_some() {
+            return Ok(found);
+        }
+    }
+    Ok(None)
+}
+
+pub fn router() -> Router<SqlitePool> {
+    Router::new()
+        .route("/account/export", get(export_account))
+        .route(
+            "/account/import",
+            // Ein Archiv ist größer als die 2 MB, die axum sonst annimmt.
+            post(import_account).layer(DefaultBodyLimit::max(MAX_ARCHIVE_BYTES)),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn seed_user(pool: &SqlitePool, id: &str, name: &str) -> User {
+        sqlx::query!(
+            "INSERT INTO users (id, name, created_at) VALUES (?, ?, ?)",
+            id, name, "2026-01-01T00:00:00Z"
+        ).execute(pool).await.unwrap();
+        User { id: id.into(), name: name.into(), created_at: "2026-01-01T00:00:00Z".into(), weight_unit: "kg".into(), warmup_ramps: false }
+    }
+
+    /// Ein Plan mit zwei Übungen und zwei Trainings — das zweite mit
+    /// schwererem Bankdrücken, damit der Rekord eindeutig ist.
+    async fn seed_history(pool: &SqlitePool, user_id: &str) {
+        sqlx::query!(
+            "INSERT INTO exercises (id, name, instructions, video_url, created_at) VALUES
+             ('ex-bench', 'Bench Press', 'Ablassen, drücken.', NULL, '2026-01-01T00:00:00Z'),
+             ('ex-pull', 'Pull-up', 'Hochziehen.', NULL, '2026-01-01T00:00:00Z')"
+        ).execute(pool).await.unwrap();
+        sqlx::query!(
+            "INSERT INTO workouts (id, user_id, name, description, is_active, schedule_type, schedule_day, created_at, updated_at)
+             VALUES ('w-1', ?, 'Push Day', NULL, 1, 'manual', NULL, '2026-01-02T00:00:00Z', '2026-01-02T00:00:00Z')",
+            user_id
+        ).execute(pool).await.unwrap();
+        sqlx::query!(
+            "INSERT INTO workout_exercises (id, workout_id, exercise_id, position, target_sets, target_weight, target_reps_min, target_reps_max, rest_seconds, notes, created_at) VALUES
+             ('we-1', 'w-1', 'ex-bench', 1, 3, 80.0, 6, 8, 120, NULL, '2026-01-02T00:00:00Z'),
+             ('we-2', 'w-1', 'ex-pull', 2, 3, NULL, NULL, NULL, NULL, NULL, '2026-01-02T00:00:00Z')"
+        ).execute(pool).await.unwrap();
+        for (session, day, weight) in [("s-1", "2026-02-01", 80.0), ("s-2", "2026-02-03", 85.0)] {
+            let started = format!("{day}T18:00:00Z");
+            let finished = format!("{day}T18:45:00Z");
+            sqlx::query!(
+                "INSERT INTO completed_workouts (id, user_id, workout_id, started_at, completed_at, total_duration_minutes, total_sets, total_volume_kg, notes, created_at)
+                 VALUES (?, ?, 'w-1', ?, ?, 45, 2, 0, NULL, ?)",
+                session, user_id, started, finished, finished
+            ).execute(pool).await.unwrap();
+            let bench = format!("{session}-bench");
+            let pull = format!("{session}-pull");
+            let at = format!("{day}T18:10:00Z");
+            sqlx::query!(
+                "INSERT INTO completed_sets (id, active_workout_id, exercise_id, set_number, weight, reps, notes, completed_at, created_at) VALUES
+                 (?, ?, 'ex-bench', 1, ?, 8, NULL, ?, ?),
+                 (?, ?, 'ex-pull', 1, NULL, 10, NULL, ?, ?)",
+                bench, session, weight, at, at, pull, session, at, at
+            ).execute(pool).await.unwrap();
+        }
+    }
+
+    async fn counts(pool: &SqlitePool, user_id: &str) -> (i32, i32, i32, i32) {
+        let workouts = sqlx::query_scalar!(r#"SELECT COUNT(*) as "c!: i32" FROM workouts WHERE user_id = ?"#, user_id)
+            .fetch_one(pool).await.unwrap();
+        let sessions = sqlx::query_scalar!(r#"SELECT COUNT(*) as "c!: i32" FROM completed_workouts WHERE user_id = ?"#, user_id)
+            .fetch_one(pool).await.unwrap();
+        let sets = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "c!: i32" FROM completed_sets cs
+               JOIN completed_workouts cw ON cw.id = cs.active_workout_id WHERE cw.user_id = ?"#,
+            user_id
+        ).fetch_one(pool).await.unwrap();
+        let exercises = sqlx::query_scalar!(r#"SELECT COUNT(*) as "c!: i32" FROM exercises"#)
+            .fetch_one(pool).await.unwrap();
+        (workouts, sessions, sets, exercises)
+    }
+
+    async fn import(pool: &SqlitePool, user_id: &str, archive: &AccountArchive) -> ImportSummary {
+        let mut tx = pool.begin().await.unwrap();
+        let summary = import_archive(&mut tx, user_id, archive).await.unwrap();
+        tx.commit().await.unwrap();
+        summary
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn archive_moves_into_another_profile_with_new_ids(pool: SqlitePool) {
+        let anna = seed_user(&pool, "u-anna", "Anna").await;
+        seed_history(&pool, &anna.id).await;
+        let bert = seed_user(&pool, "u-bert", "Bert").await;
+
+        let archive = build_archive(&mut pool.acquire().await.unwrap(), &anna).await.unwrap();
+        // Über den Text, wie beim echten Umzug.
+        let archive = from_json(&to_json(&archive)).unwrap();
+        assert_eq!(archive.sessions.len(), 2);
+        assert_eq!(archive.exercises.len(), 2);
+
+        let summary = import(&pool, &bert.id, &archive).await;
+        assert_eq!(
+            summary,
+            ImportSummary { workouts: 1, sessions: 2, sets: 4, exercises_created: 0, skipped: 0 }
+        );
+        assert_eq!(counts(&pool, &bert.id).await, counts(&pool, &anna.id).await);
+
+        let ids = sqlx::query_scalar!("SELECT id FROM completed_workouts WHERE user_id = ?", bert.id)
+            .fetch_all(&pool).await.unwrap();
+        assert!(ids.iter().all(|id| id != "s-1" && id != "s-2"), "imported rows need new IDs");
+
+        let targets = sqlx::query!(
+            r#"SELECT we.target_reps_min as "min: i32", we.rest_seconds as "rest: i32"
+               FROM workout_exercises we JOIN workouts w ON w.id = we.workout_id
+               WHERE w.user_id = ? AND we.position = 1"#,
+            bert.id
+        ).fetch_one(&pool).await.unwrap();
+        assert_eq!((targets.min, targets.rest), (Some(6), Some(120)));
+
+        let max_weight = sqlx::query!(
+            r#"SELECT weight as "weight: f64", achieved_at FROM personal_records
+               WHERE user_id = ? AND exercise_id = 'ex-bench' AND record_type = 'max_weight'"#,
+            bert.id
+        ).fetch_one(&pool).await.unwrap();
+        assert_eq!(max_weight.weight, Some(85.0));
+        assert_eq!(max_weight.achieved_at, "2026-02-03T18:10:00Z");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn settings_session_plans_and_target_log_survive_the_round_trip(pool: SqlitePool) {
+        let anna = seed_user(&pool, "u-anna", "Anna").await;
+        seed_history(&pool, &anna.id).await;
+        sqlx::query!("UPDATE users SET weight_unit = 'lb', warmup_ramps = 1 WHERE id = ?", anna.id)
+            .execute(&pool).await.unwrap();
+        let anna = User { weight_unit: "lb".into(), warmup_ramps: true, ..anna };
+        sqlx::query!(
+            "INSERT INTO equipment_profiles (user_id, unit, bar_weight, collar_weight, plates, updated_at)
+             VALUES (?, 'lb', 45, 0, '45, 25, 10x2, 5', '2026-01-03T00:00:00Z')",
+            anna.id
+        ).execute(&pool).await.unwrap();
+        // s-1: Klimmzüge durch Bankdrücken ersetzt und vorgezogen, ein Zusatzsatz.
+        sqlx::query!(
+            "INSERT INTO session_exercises (session_id, position, planned_exercise_id, exercise_id, sort_order, extra_sets) VALUES
+             ('s-1', 2, 'ex-pull', 'ex-bench', 0, 1),
+             ('s-1', 1, 'ex-bench', 'ex-bench', 1, 0)"
+        ).execute(&pool).await.unwrap();
+        sqlx::query!(
+            r#"UPDATE completed_workouts SET exercise_times = '[{"exercise_id":"ex-bench","seconds":540}]' WHERE id = 's-2'"#
+        ).execute(&pool).await.unwrap();
+        sqlx::query!(
+            "INSERT INTO target_changes (id, workout_id, workout_exercise_id, exercise_id, completed_workout_id, decision, old_weight, new_weight, old_reps_min, old_reps_max, new_reps_min, new_reps_max, status, created_at)
+             VALUES ('tc-1', 'w-1', 'we-1', 'ex-bench', 's-1', 'increase', 80, 82.5, 6, 8, 6, 8, 'rejected', '2026-02-01T18:46:00Z')"
+        ).execute(&pool).await.unwrap();
+
+        let exported = build_archive(&mut pool.acquire().await.unwrap(), &anna).await.unwrap();
+        let bert = seed_user(&pool, "u-bert", "Bert").await;
+        import(&pool, &bert.id, &from_json(&to_json(&exported)).unwrap()).await;
+        let bert = sqlx::query_as!(User, "SELECT * FROM users WHERE id = ?", bert.id)
+            .fetch_one(&pool).await.unwrap();
+        let restored = build_archive(&mut pool.acquire().await.unwrap(), &bert).await.unwrap();
+
+        assert_eq!((bert.weight_unit.as_str(), bert.warmup_ramps), ("lb", true));
+        assert_eq!(restored.equipment, exported.equipment);
+        assert!(exported.equipment.is_some());
+        for (before, after) in exported.sessions.iter().zip(&restored.sessions) {
+            assert_eq!(after.exercises, before.exercises);
+            assert_eq!(after.exercise_times, before.exercise_times);
+        }
+        assert_eq!(restored.sessions[0].exercises.len(), 2);
+        assert_eq!(restored.sessions[1].exercise_times.as_ref().map(Vec::len), Some(1));
+
+        let before = &exported.workouts[0].target_changes;
+        let after = &restored.workouts[0].target_changes;
+        assert_eq!(after.len(), 1);
+        assert_eq!(after[0].session_id.as_ref(), Some(&restored.sessions[0].id));
+        assert_eq!(
+            ArchiveTargetChange { session_id: None, ..after[0].clone() },
+            ArchiveTargetChange { session_id: None, ..before[0].clone() }
+        );
+        assert_eq!(after[0].position, Some(1));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn importing_the_same_archive_twice_adds_nothing(pool: SqlitePool) {
+        let anna = seed_user(&pool, "u-anna", "Anna").await;
+        seed_history(&pool, &anna.id).await;
+        let bert = seed_user(&pool, "u-bert", "Bert").await;
+        let archive = build_archive(&mut pool.acquire().await.unwrap(), &anna).await.unwrap();
+
+        import(&pool, &bert.id, &archive).await;
+        let after_first = counts(&pool, &bert.id).await;
+        let second = import(&pool, &bert.id, &archive).await;
+
+        assert_eq!(counts(&pool, &bert.id).await, after_first);
+        assert_eq!(second.skipped, 3, "one plan and two sessions were already there");
+        assert_eq!((second.workouts, second.sessions, second.sets), (0, 0, 0));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn restoring_an_own_backup_adds_nothing(pool: SqlitePool) {
+        let anna = seed_user(&pool, "u-anna", "Anna").await;
+        seed_history(&pool, &anna.id).await;
+        let archive = build_archive(&mut pool.acquire().await.unwrap(), &anna).await.unwrap();
+        let before = counts(&pool, &anna.id).await;
+
+        let summary = import(&pool, &anna.id, &archive).await;
+        assert_eq!(summary.skipped, 3);
+        assert_eq!(counts(&pool, &anna.id).await, before);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn exercises_resolve_by_name_and_unknown_ones_are_created(pool: SqlitePool) {
+        let anna = seed_user(&pool, "u-anna", "Anna").await;
+        seed_history(&pool, &anna.id).await;
+        let mut archive = build_archive(&mut pool.acquire().await.unwrap(), &anna).await.unwrap();
+
+        // Wie von einer anderen Instanz: fremde IDs, andere Schreibweise.
+        for exercise in &mut archive.exercises {
+            let foreign = format!("remote-{}", exercise.id);
+            for workout in &mut archive.workouts {
+                for link in &mut workout.exercises {
+                    if link.exercise_id == exercise.id { link.exercise_id = foreign.clone(); }
+                }
+            }
+            for session in &mut archive.sessions {
+                for set in &mut session.sets {
+                    if set.exercise_id == exercise.id { set.exercise_id = foreign.clone(); }
+                }
+            }
+            exercise.id = foreign;
+        }
+        archive.exercises[0].name = "  BENCH press ".into();
+        archive.exercises[1].name = "Chin-up".into();
+        archive.exercises[1].instructions = "Untergriff.".into();
+
+        let bert = seed_user(&pool, "u-bert", "Bert").await;
+        let summary = import(&pool, &bert.id, &archive).await;
+        assert_eq!(summary.exercises_created, 1);
+
+        let bench_sets = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "c!: i32" FROM completed_sets cs
+               JOIN completed_workouts cw ON cw.id = cs.active_workout_id
+               WHERE cw.user_id = ? AND cs.exercise_id = 'ex-bench'"#,
+            bert.id
+        ).fetch_one(&pool).await.unwrap();
+        assert_eq!(bench_sets, 2, "sets must land on the existing exercise");
+
+        let instructions = sqlx::query_scalar!("SELECT instructions FROM exercises WHERE name = 'Chin-up'")
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!(instructions, "Untergriff.");
+    }
+}
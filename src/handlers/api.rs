@@ -0,0 +1,722 @@
+//! Versionierte JSON-API unter `/api/v1` für Skripte und die Uhren-App.
+//! Dieselben Daten und Abläufe wie die HTMX-Seiten; Antworten sind die
+//! Modelle selbst, Fehler kommen als `{"error": "..."}` aus [`AppError`].
+//!
+//! Angemeldet wird mit `Authorization: Bearer <token>` (siehe
+//! [`crate::api_token`]), im Browser genügt die Sitzung. Gewichte sind in
+//! beide Richtungen immer kg, unabhängig von `weight_unit` — was eine
+//! Antwort liefert, lässt sich unverändert zurückschicken.
+
+use crate::api_token::{self, TokenScope};
+use crate::error::AppError;
+use crate::handlers::openapi::ApiRoute;
+use crate::handlers::tokens;
+use crate::handlers::live_training::{LoggedSet, begin_session, close_session, log_set, session_sets};
+use crate::models::{
+    ActiveWorkout, ApiToken, CompleteSetForm, CompletedSet, CompletedWorkout, Exercise, FinishTrainingForm,
+    PersonalRecord, User, Workout, WorkoutExercise, WorkoutWithExercises,
+};
+use crate::units::WeightUnit;
+use axum::{
+    Json, Router,
+    extract::{FromRequest, FromRequestParts, Path, Request, State},
+    http::{StatusCode, header::AUTHORIZATION, request::Parts},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sqlx::SqlitePool;
+use tower_sessions::Session;
+
+/// Angemeldeter Nutzer eines API-Requests: per Bearer-Token oder, ohne
+/// `Authorization`-Header, über die Browser-Sitzung. Ein nur lesendes
+/// Token kommt nur durch GET-Routen, sonst 403.
+pub struct ApiUser(pub User);
+
+impl FromRequestParts<SqlitePool> for ApiUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, pool: &SqlitePool) -> Result<Self, Self::Rejection> {
+        let user_id = match parts.headers.get(AUTHORIZATION) {
+            // Ein falscher Header fällt nicht auf die Sitzung zurück.
+            Some(header) => {
+                let token = header
+                    .to_str()
+                    .ok()
+                    .and_then(api_token::from_authorization)
+                    .ok_or(AppError::Unauthorized)?;
+                let token_hash = api_token::hash(token);
+                let now = Utc::now().to_rfc3339();
+                let Some(row) = sqlx::query!(
+                    "UPDATE api_tokens SET last_used_at = ? WHERE token_hash = ? RETURNING user_id, scope",
+                    now,
+                    token_hash
+                )
+                .fetch_optional(pool)
+                .await?
+                else {
+                    return Err(AppError::Unauthorized);
+                };
+                if !TokenScope::from_db(&row.scope).allows(&parts.method) {
+                    return Err(AppError::Forbidden("This token is read-only".to_string()));
+                }
+                Some(row.user_id)
+            }
+            None => {
+                let session = Session::from_request_parts(parts, pool)
+                    .await
+                    .map_err(|_| AppError::Unauthorized)?;
+                session.get::<String>("current_user_id").await.ok().flatten()
+            }
+        };
+        let user_id = user_id.ok_or(AppError::Unauthorized)?;
+        let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = ?", user_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+        Ok(ApiUser(user))
+    }
+}
+
+/// Wie [`Json`], aber ein unlesbarer Body wird zu einem JSON-Fehler statt
+/// axums Klartext-Antwort.
+pub struct ApiJson<T>(pub T);
+
+impl<T: DeserializeOwned, S: Send + Sync> FromRequest<S> for ApiJson<T> {
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(|rejection| AppError::BadRequest(rejection.body_text()))?;
+        Ok(ApiJson(value))
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateTokenRequest {
+    pub name: String,
+    /// Ohne Angabe nur lesend.
+    #[serde(default)]
+    pub scope: TokenScope,
+}
+
+/// Das Token steht nur in dieser einen Antwort im Klartext.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CreatedToken {
+    pub id: String,
+    pub name: String,
+    pub scope: TokenScope,
+    pub token: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct StartSessionRequest {
+    /// Ohne Plan: freies Training.
+    pub workout_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SessionWithSets {
+    pub session: ActiveWorkout,
+    pub sets: Vec<CompletedSet>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct LoggedSetResponse {
+    pub set: CompletedSet,
+    /// Mit diesem Satz erreichte Rekorde, als Anzeigetext.
+    pub records: Vec<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct HistoryEntry {
+    pub workout: CompletedWorkout,
+    pub sets: Vec<CompletedSet>,
+}
+
+pub async fn me(ApiUser(user): ApiUser) -> Json<User> {
+    Json(user)
+}
+
+pub async fn create_token(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    ApiJson(request): ApiJson<CreateTokenRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let (created, token) = tokens::create_token(&pool, &user.id, &request.name, request.scope).await?;
+    let created = CreatedToken {
+        id: created.id,
+        name: created.name,
+        scope: request.scope,
+        token,
+        created_at: created.created_at,
+    };
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+pub async fn list_tokens(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+) -> Result<Json<Vec<ApiToken>>, AppError> {
+    Ok(Json(tokens::list_tokens(&pool, &user.id).await?))
+}
+
+/// Fremde und unbekannte Tokens sind gleichermaßen 404.
+pub async fn revoke_token(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    if !tokens::revoke_token(&pool, &user.id, &id).await? {
+        return Err(AppError::NotFound(format!("Token {id}")));
+    }
+    tracing::info!("API token {} revoked by user {}", id, user.id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn list_exercises(
+    State(pool): State<SqlitePool>,
+    _user: ApiUser,
+) -> Result<Json<Vec<Exercise>>, AppError> {
+    let exercises = sqlx::query_as!(Exercise, "SELECT id, name, instructions, video_url, created_at FROM exercises ORDER BY name")
+        .fetch_all(&pool)
+        .await?;
+    Ok(Json(exercises))
+}
+
+pub async fn get_exercise(
+    State(pool): State<SqlitePool>,
+    _user: ApiUser,
+    Path(exercise_id): Path<String>,
+) -> Result<Json<Exercise>, AppError> {
+    let exercise = sqlx::query_as!(
+        Exercise,
+        "SELECT id, name, instructions, video_url, created_at FROM exercises WHERE id = ?",
+        exercise_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Exercise '{exercise_id}' not found")))?;
+    Ok(Json(exercise))
+}
+
+pub async fn list_workouts(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+) -> Result<Json<Vec<Workout>>, AppError> {
+    let workouts = sqlx::query_as!(
+        Workout,
+        r#"SELECT id, user_id, name, description, is_active,
+                  schedule_type as "schedule_type!: String", schedule_day as "schedule_day: i32",
+                  created_at, updated_at
+           FROM workouts WHERE user_id = ? ORDER BY name"#,
+        user.id
+    )
+    .fetch_all(&pool)
+    .await?;
+    Ok(Json(workouts))
+}
+
+pub async fn get_workout(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Path(workout_id): Path<String>,
+) -> Result<Json<WorkoutWithExercises>, AppError> {
+    let workout = sqlx::query_as!(
+        Workout,
+        r#"SELECT id, user_id, name, description, is_active,
+                  schedule_type as "schedule_type!: String", schedule_day as "schedule_day: i32",
+                  created_at, updated_at
+           FROM workouts WHERE id = ? AND user_id = ?"#,
+        workout_id,
+        user.id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Workout '{workout_id}' not found")))?;
+
+    let exercises = sqlx::query_as!(
+        WorkoutExercise,
+        r#"SELECT id, workout_id, exercise_id, position as "position: i32",
+                  target_sets as "target_sets: i32", target_weight as "target_weight: f32",
+                  target_reps_min as "target_reps_min: i32", target_reps_max as "target_reps_max: i32",
+                  rest_seconds as "rest_seconds: i32", notes, progression, group_label, created_at
+           FROM workout_exercises WHERE workout_id = ? ORDER BY position"#,
+        workout_id
+    )
+    .fetch_all(&pool)
+    .await?;
+    Ok(Json(WorkoutWithExercises { workout, exercises }))
+}
+
+/// Laufende Sitzung des Nutzers; fremde und beendete gibt es hier nicht.
+async fn owned_session(pool: &SqlitePool, session_id: &str, user: &User) -> Result<ActiveWorkout, AppError> {
+    sqlx::query_as!(
+        ActiveWorkout,
+        "SELECT * FROM active_workouts WHERE id = ? AND user_id = ?",
+        session_id,
+        user.id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Active workout not found".to_string()))
+}
+
+async fn with_sets(pool: &SqlitePool, session: ActiveWorkout) -> Result<SessionWithSets, AppError> {
+    let sets = session_sets(&mut *pool.acquire().await?, &session.id).await?;
+    Ok(SessionWithSets { session, sets })
+}
+
+/// 201 für eine neue Sitzung, 200, wenn schon eine läuft — dann diese.
+pub async fn start_session(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    ApiJson(request): ApiJson<StartSessionRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let workout_id = request.workout_id.filter(|id| !id.trim().is_empty());
+    let (session, started) = begin_session(&pool, &user.id, workout_id).await?;
+    let status = if started { StatusCode::CREATED } else { StatusCode::OK };
+    Ok((status, Json(with_sets(&pool, session).await?)))
+}
+
+pub async fn active_session(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+) -> Result<Json<SessionWithSets>, AppError> {
+    let session = sqlx::query_as!(
+        ActiveWorkout,
+        "SELECT * FROM active_workouts WHERE user_id = ? LIMIT 1",
+        user.id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("No active workout".to_string()))?;
+    Ok(Json(with_sets(&pool, session).await?))
+}
+
+pub async fn get_session(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Path(session_id): Path<String>,
+) -> Result<Json<SessionWithSets>, AppError> {
+    let session = owned_session(&pool, &session_id, &user).await?;
+    Ok(Json(with_sets(&pool, session).await?))
+}
+
+/// 201 mit dem neuen Satz. Ein wiederholter `client_request_id` liefert
+/// 200 und den schon gespeicherten Satz.
+pub async fn log_session_set(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Path(session_id): Path<String>,
+    ApiJson(form): ApiJson<CompleteSetForm>,
+) -> Result<Response, AppError> {
+    owned_session(&pool, &session_id, &user).await?;
+    let client_request_id = form.client_request_id.as_deref().map(str::trim).map(str::to_string);
+
+    match log_set(&pool, &session_id, form, WeightUnit::Kg).await? {
+        LoggedSet::Logged { set, records } => {
+            Ok((StatusCode::CREATED, Json(LoggedSetResponse { set: *set, records })).into_response())
+        }
+        LoggedSet::Duplicate => {
+            let set = sqlx::query_as!(
+                CompletedSet,
+                r#"SELECT id, active_workout_id, exercise_id, set_number as "set_number: i32",
+                          weight as "weight: f32", reps as "reps: i32", notes, rpe, set_type, completed_at, created_at
+                   FROM completed_sets WHERE active_workout_id = ? AND client_request_id = ?"#,
+                session_id,
+                client_request_id
+            )
+            .fetch_one(&pool)
+            .await?;
+            Ok((StatusCode::OK, Json(LoggedSetResponse { set, records: Vec::new() })).into_response())
+        }
+    }
+}
+
+pub async fn finish_session(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Path(session_id): Path<String>,
+    ApiJson(form): ApiJson<FinishTrainingForm>,
+) -> Result<Json<CompletedWorkout>, AppError> {
+    let notes = form.notes.filter(|n| !n.trim().is_empty());
+    if let Some(completed) = close_session(&pool, &session_id, &user.id, notes, Utc::now(), false).await? {
+        return Ok(Json(completed));
+    }
+    // Schon beendet: das Ergebnis noch einmal statt eines Fehlers.
+    Ok(Json(finished_session(&pool, &session_id, &user).await?))
+}
+
+async fn finished_session(pool: &SqlitePool, session_id: &str, user: &User) -> Result<CompletedWorkout, AppError> {
+    sqlx::query_as!(
+        CompletedWorkout,
+        r#"SELECT id, user_id, workout_id, started_at, completed_at,
+                  total_duration_minutes as "total_duration_minutes: i32", total_sets as "total_sets: i32",
+                  total_volume_kg as "total_volume_kg: f32", notes, created_at, auto_closed, exercise_times
+           FROM completed_workouts WHERE id = ? AND user_id = ?"#,
+        session_id,
+        user.id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Completed workout not found".to_string()))
+}
+
+/// Die letzten 50 Trainings, neueste zuerst — wie die Verlaufsseite.
+pub async fn list_history(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+) -> Result<Json<Vec<CompletedWorkout>>, AppError> {
+    let history = sqlx::query_as!(
+        CompletedWorkout,
+        r#"SELECT id, user_id, workout_id, started_at, completed_at,
+                  total_duration_minutes as "total_duration_minutes: i32", total_sets as "total_sets: i32",
+                  total_volume_kg as "total_volume_kg: f32", notes, created_at, auto_closed, exercise_times
+           FROM completed_workouts WHERE user_id = ? ORDER BY completed_at DESC LIMIT 50"#,
+        user.id
+    )
+    .fetch_all(&pool)
+    .await?;
+    Ok(Json(history))
+}
+
+pub async fn get_history_entry(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Path(session_id): Path<String>,
+) -> Result<Json<HistoryEntry>, AppError> {
+    let workout = finished_session(&pool, &session_id, &user).await?;
+    let sets = session_sets(&mut *pool.acquire().await?, &session_id).await?;
+    Ok(Json(HistoryEntry { workout, sets }))
+}
+
+pub async fn list_records(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+) -> Result<Json<Vec<PersonalRecord>>, AppError> {
+    let records = sqlx::query_as!(
+        PersonalRecord,
+        r#"SELECT id, user_id, exercise_id, record_type, weight, reps, volume_kg,
+                  completed_set_id, achieved_at, created_at
+           FROM personal_records WHERE user_id = ? ORDER BY exercise_id, record_type"#,
+        user.id
+    )
+    .fetch_all(&pool)
+    .await?;
+    Ok(Json(records))
+}
+
+/// Alle Routen der API. Router und OpenAPI-Dokument entstehen aus dieser
+/// Liste; eine Route ohne Eintrag im Dokument gibt es so nicht.
+pub fn routes() -> Vec<ApiRoute> {
+    vec![
+        ApiRoute::get::<User, _, _>("/api/v1/users/me", "getMe", "Signed-in user", me),
+        ApiRoute::post::<CreateTokenRequest, CreatedToken, _, _>(
+            "/api/v1/tokens",
+            "createToken",
+            "Create a personal access token; the token is only returned here",
+            StatusCode::CREATED,
+            create_token,
+        ),
+        ApiRoute::get::<Vec<ApiToken>, _, _>("/api/v1/tokens", "listTokens", "Own tokens, without the secrets", list_tokens),
+        ApiRoute::delete("/api/v1/tokens/{id}", "revokeToken", "Revoke a token", revoke_token),
+        ApiRoute::get::<Vec<Exercise>, _, _>("/api/v1/exercises", "listExercises", "Exercise library", list_exercises),
+        ApiRoute::get::<Exercise, _, _>("/api/v1/exercises/{id}", "getExercise", "One exercise", get_exercise),
+        ApiRoute::get::<Vec<Workout>, _, _>("/api/v1/workouts", "listWorkouts", "Own workout plans", list_workouts),
+        ApiRoute::get::<WorkoutWithExercises, _, _>(
+            "/api/v1/workouts/{id}",
+            "getWorkout",
+            "Workout plan with its exercises",
+            get_workout,
+        ),
+        ApiRoute::post::<StartSessionRequest, SessionWithSets, _, _>(
+            "/api/v1/sessions",
+            "startSession",
+            "Start a session, or return the one already running (200)",
+            StatusCode::CREATED,
+            start_session,
+        ),
+        ApiRoute::get::<SessionWithSets, _, _>(
+            "/api/v1/sessions/active",
+            "getActiveSession",
+            "The running session",
+            active_session,
+        ),
+        ApiRoute::get::<SessionWithSets, _, _>("/api/v1/sessions/{id}", "getSession", "A running session", get_session),
+        ApiRoute::post::<CompleteSetForm, LoggedSetResponse, _, _>(
+            "/api/v1/sessions/{id}/sets",
+            "logSet",
+            "Log a set (weight in kg); a repeated client_request_id returns the stored set (200)",
+            StatusCode::CREATED,
+            log_session_set,
+        ),
+        ApiRoute::post::<FinishTrainingForm, CompletedWorkout, _, _>(
+            "/api/v1/sessions/{id}/finish",
+            "finishSession",
+            "Finish a session; finishing again returns the result",
+            StatusCode::OK,
+            finish_session,
+        ),
+        ApiRoute::get::<Vec<CompletedWorkout>, _, _>("/api/v1/history", "listHistory", "Last 50 trainings", list_history),
+        ApiRoute::get::<HistoryEntry, _, _>(
+            "/api/v1/history/{id}",
+            "getHistoryEntry",
+            "A finished training with its sets",
+            get_history_entry,
+        ),
+        ApiRoute::get::<Vec<PersonalRecord>, _, _>("/api/v1/records", "listRecords", "Personal records", list_records),
+    ]
+}
+
+pub fn router() -> Router<SqlitePool> {
+    routes()
+        .into_iter()
+        .fold(Router::new(), |router, route| router.route(route.path, route.handler))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::to_bytes;
+
+    async fn seed(pool: &SqlitePool) -> User {
+        for (id, name) in [("u-1", "Anna"), ("u-2", "Ben")] {
+            sqlx::query!(
+                "INSERT INTO users (id, name, created_at) VALUES (?, ?, '2026-01-01T00:00:00Z')",
+                id,
+                name
+            )
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query!(
+            "INSERT INTO exercises (id, name, instructions, video_url, created_at)
+             VALUES ('ex-squat', 'Squat', '', NULL, '2026-01-01T00:00:00Z')"
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query!(
+            "INSERT INTO workouts (id, user_id, name, description, is_active, schedule_type, schedule_day, created_at, updated_at)
+             VALUES ('w-1', 'u-1', 'Legs', NULL, 1, 'manual', NULL, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')"
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query!(
+            "INSERT INTO workout_exercises (id, workout_id, exercise_id, position, target_sets, created_at)
+             VALUES ('we-1', 'w-1', 'ex-squat', 1, 3, '2026-01-01T00:00:00Z')"
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        user(pool, "u-1").await
+    }
+
+    async fn user(pool: &SqlitePool, id: &str) -> User {
+        sqlx::query_as!(User, "SELECT * FROM users WHERE id = ?", id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn authenticate(pool: &SqlitePool, authorization: Option<&str>) -> Result<User, AppError> {
+        let mut request = axum::http::Request::builder();
+        if let Some(value) = authorization {
+            request = request.header(AUTHORIZATION, value);
+        }
+        let (mut parts, _) = request.body(()).unwrap().into_parts();
+        ApiUser::from_request_parts(&mut parts, pool).await.map(|ApiUser(user)| user)
+    }
+
+    async fn json(response: Response) -> serde_json::Value {
+        serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap()
+    }
+
+    fn squat_set(client_request_id: &str) -> CompleteSetForm {
+        CompleteSetForm {
+            exercise_id: "ex-squat".to_string(),
+            weight: Some(100.0),
+            reps: 5,
+            notes: None,
+            effort: None,
+            effort_scale: None,
+            set_type: None,
+            client_request_id: Some(client_request_id.to_string()),
+            logged_at: None,
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn bearer_tokens_authenticate_and_only_their_hash_is_stored(pool: SqlitePool) {
+        let anna = seed(&pool).await;
+        let request = CreateTokenRequest { name: "Watch".to_string(), scope: TokenScope::ReadWrite };
+        let response = create_token(State(pool.clone()), ApiUser(anna), ApiJson(request))
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let token = json(response).await["token"].as_str().unwrap().to_string();
+
+        let stored = sqlx::query_scalar!("SELECT token_hash FROM api_tokens").fetch_one(&pool).await.unwrap();
+        assert_eq!(stored, api_token::hash(&token));
+
+        let user = authenticate(&pool, Some(&format!("Bearer {token}"))).await.unwrap();
+        assert_eq!(user.id, "u-1");
+        assert!(matches!(authenticate(&pool, Some("Bearer wop_unknown")).await, Err(AppError::Unauthorized)));
+        assert!(matches!(authenticate(&pool, Some(&format!("Basic {token}"))).await, Err(AppError::Unauthorized)));
+        // Ohne Header und ohne Sitzung.
+        assert!(matches!(authenticate(&pool, None).await, Err(AppError::Unauthorized)));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn read_only_tokens_cannot_write_and_revoked_tokens_stop_working(pool: SqlitePool) {
+        let anna = seed(&pool).await;
+        let (read_only, secret) = tokens::create_token(&pool, &anna.id, "Stats", TokenScope::Read).await.unwrap();
+        let mut app = router().with_state(pool.clone());
+        let bearer = format!("Bearer {secret}");
+        let mut call = async |method: &str, uri: &str| {
+            let request = axum::http::Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(AUTHORIZATION, &bearer)
+                .header("Content-Type", "application/json")
+                .body(axum::body::Body::from(r#"{"name": "Escalate", "scope": "read_write"}"#))
+                .unwrap();
+            tower::Service::call(&mut app, request).await.unwrap()
+        };
+
+        assert_eq!(call("GET", "/api/v1/workouts").await.status(), StatusCode::OK);
+        let listed = json(call("GET", "/api/v1/tokens").await).await;
+        assert_eq!(listed[0]["scope"], "read");
+        assert!(listed[0].get("token_hash").is_none());
+        // Auch kein Umweg über ein neues, schreibendes Token.
+        assert_eq!(call("POST", "/api/v1/tokens").await.status(), StatusCode::FORBIDDEN);
+        assert_eq!(call("POST", "/api/v1/sessions").await.status(), StatusCode::FORBIDDEN);
+        assert_eq!(call("DELETE", &format!("/api/v1/tokens/{}", read_only.id)).await.status(), StatusCode::FORBIDDEN);
+        assert!(sqlx::query_scalar!("SELECT last_used_at FROM api_tokens").fetch_one(&pool).await.unwrap().is_some());
+
+        // Widerrufen nur durch den Besitzer.
+        assert!(!tokens::revoke_token(&pool, "u-2", &read_only.id).await.unwrap());
+        assert!(tokens::revoke_token(&pool, &anna.id, &read_only.id).await.unwrap());
+        assert_eq!(call("GET", "/api/v1/workouts").await.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn sessions_are_private_and_replayed_sets_return_the_first(pool: SqlitePool) {
+        let anna = seed(&pool).await;
+        let ben = user(&pool, "u-2").await;
+
+        let request = StartSessionRequest { workout_id: Some("w-1".to_string()) };
+        assert!(matches!(
+            start_session(State(pool.clone()), ApiUser(ben.clone()), ApiJson(request)).await,
+            Err(AppError::NotFound(_))
+        ));
+        let request = StartSessionRequest { workout_id: Some("w-1".to_string()) };
+        let response = start_session(State(pool.clone()), ApiUser(anna.clone()), ApiJson(request))
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let session_id = json(response).await["session"]["id"].as_str().unwrap().to_string();
+
+        let log = |user: &User| {
+            log_session_set(State(pool.clone()), ApiUser(user.clone()), Path(session_id.clone()), ApiJson(squat_set("req-1")))
+        };
+        assert!(matches!(log(&ben).await, Err(AppError::NotFound(_))));
+        let first = log(&anna).await.unwrap();
+        assert_eq!(first.status(), StatusCode::CREATED);
+        let first = json(first).await;
+        assert_eq!(first["set"]["weight"], 100.0);
+        let again = log(&anna).await.unwrap();
+        assert_eq!(again.status(), StatusCode::OK);
+        assert_eq!(json(again).await["set"]["id"], first["set"]["id"]);
+
+        let finish = || {
+            finish_session(
+                State(pool.clone()),
+                ApiUser(anna.clone()),
+                Path(session_id.clone()),
+                ApiJson(FinishTrainingForm { notes: None }),
+            )
+        };
+        let Json(completed) = finish().await.unwrap();
+        assert_eq!(completed.total_sets, 1);
+        let Json(again) = finish().await.unwrap();
+        assert_eq!(again.id, completed.id);
+
+        let Json(history) = list_history(State(pool.clone()), ApiUser(anna)).await.unwrap();
+        assert_eq!(history.len(), 1);
+        let Json(history) = list_history(State(pool.clone()), ApiUser(ben)).await.unwrap();
+        assert!(history.is_empty());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn set_weights_are_kg_both_ways_even_for_lb_users(pool: SqlitePool) {
+        seed(&pool).await;
+        sqlx::query!("UPDATE users SET weight_unit = 'lb' WHERE id = 'u-1'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let anna = user(&pool, "u-1").await;
+        let request = StartSessionRequest { workout_id: Some("w-1".to_string()) };
+        let response = start_session(State(pool.clone()), ApiUser(anna.clone()), ApiJson(request))
+            .await
+            .unwrap()
+            .into_response();
+        let session_id = json(response).await["session"]["id"].as_str().unwrap().to_string();
+
+        let log = |form: CompleteSetForm| {
+            log_session_set(State(pool.clone()), ApiUser(anna.clone()), Path(session_id.clone()), ApiJson(form))
+        };
+        let first = json(log(squat_set("req-1")).await.unwrap()).await;
+        assert_eq!(first["set"]["weight"], 100.0);
+
+        // Das Gewicht aus der Antwort zurückschicken loggt dieselbe Last.
+        let mut again = squat_set("req-2");
+        again.weight = first["set"]["weight"].as_f64().map(|kg| kg as f32);
+        let second = json(log(again).await.unwrap()).await;
+        assert_eq!(second["set"]["weight"], 100.0);
+
+        let stored = sqlx::query_scalar!(r#"SELECT weight as "weight: f64" FROM completed_sets"#)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(stored, vec![Some(100.0), Some(100.0)]);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn sets_for_exercises_outside_the_session_are_rejected(pool: SqlitePool) {
+        let anna = seed(&pool).await;
+        sqlx::query!(
+            "INSERT INTO exercises (id, name, instructions, video_url, created_at)
+             VALUES ('ex-curl', 'Curl', '', NULL, '2026-01-01T00:00:00Z')"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let request = StartSessionRequest { workout_id: Some("w-1".to_string()) };
+        let response = start_session(State(pool.clone()), ApiUser(anna.clone()), ApiJson(request))
+            .await
+            .unwrap()
+            .into_response();
+        let session_id = json(response).await["session"]["id"].as_str().unwrap().to_string();
+
+        for (index, exercise_id) in ["ex-curl", "ex-unknown"].into_iter().enumerate() {
+            let mut form = squat_set(&format!("req-{index}"));
+            form.exercise_id = exercise_id.to_string();
+            let logged =
+                log_session_set(State(pool.clone()), ApiUser(anna.clone()), Path(session_id.clone()), ApiJson(form)).await;
+            assert!(matches!(logged, Err(AppError::BadRequest(_))), "{exercise_id}");
+        }
+        let sets = sqlx::query_scalar!("SELECT COUNT(*) FROM completed_sets").fetch_one(&pool).await.unwrap();
+        assert_eq!(sets, 0);
+        let records = sqlx::query_scalar!("SELECT COUNT(*) FROM personal_records").fetch_one(&pool).await.unwrap();
+        assert_eq!(records, 0);
+    }
+}
@@ -0,0 +1,341 @@
+//! Kalender-Link: `/calendar/{token}.ics` zum Abonnieren in Kalender-Apps,
+//! verwaltet auf der Profilseite.
+
+use crate::error::AppError;
+use crate::handlers::get_current_user;
+use crate::ical::{self, Event, EventTime};
+use crate::models::{CalendarFeed, User, Workout};
+use askama::Template;
+use axum::{
+    Form, Router,
+    extract::{Path, State},
+    http::{HeaderMap, HeaderValue},
+    response::{Html, IntoResponse},
+    routing::{get, post},
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use tower_sessions::Session;
+use uuid::Uuid;
+
+/// So viele abgeschlossene Trainings kommen höchstens in den Kalender,
+/// neueste zuerst.
+const HISTORY_LIMIT: i64 = 500;
+
+/// `on` oder `off`.
+#[derive(Debug, Deserialize)]
+pub struct CalendarHistoryForm {
+    pub include_history: String,
+}
+
+#[derive(Template)]
+#[template(path = "users/calendar_partial.html")]
+pub struct CalendarPartialTemplate {
+    pub calendar: Option<CalendarFeed>,
+}
+
+pub async fn load_calendar_feed(pool: &SqlitePool, user_id: &str) -> Result<Option<CalendarFeed>, sqlx::Error> {
+    sqlx::query_as!(
+        CalendarFeed,
+        r#"SELECT user_id, token, include_history as "include_history: bool", created_at
+           FROM calendar_feeds WHERE user_id = ?"#,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+/// Termine des Nutzers: wöchentlich geplante, aktive Trainings als Serie,
+/// auf Wunsch die abgeschlossenen dazu. Rotationen haben keinen festen Tag
+/// und fehlen deshalb.
+pub async fn calendar_events(pool: &SqlitePool, user: &User, include_history: bool) -> Result<Vec<Event>, AppError> {
+    let workouts = sqlx::query_as!(
+        Workout,
+        r#"SELECT
+            id,
+            user_id,
+            name,
+            description,
+            is_active,
+            schedule_type as "schedule_type!: String",
+            schedule_day as "schedule_day: i32",
+            created_at,
+            updated_at
+            FROM workouts
+            WHERE user_id = ? AND schedule_type = 'weekly' AND schedule_day IS NOT NULL
+              AND COALESCE(is_active, 1) = 1
+            ORDER BY schedule_day, name"#,
+        user.id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut events = Vec::new();
+    for workout in workouts {
+        // Die Serie beginnt mit dem Plan, nicht in der Vergangenheit.
+        let created = DateTime::parse_from_rfc3339(&workout.created_at)
+            .map(|created| created.date_naive())
+            .unwrap_or_else(|_| Utc::now().date_naive());
+        let Some(first) = workout.schedule_day.and_then(|day| ical::first_weekday_from(created, day)) else {
+            continue;
+        };
+        events.push(Event {
+            uid: format!("workout-{}@woplanner", workout.id),
+            summary: workout.name,
+            description: workout.description.filter(|description| !description.trim().is_empty()),
+            time: EventTime::Weekly { first },
+        });
+    }
+
+    if include_history {
+        let unit = user.unit();
+        let sessions = sqlx::query!(
+            r#"SELECT
+                cw.id as "id!",
+                COALESCE(w.name, 'Freestyle') as "workout_name!: String",
+                cw.started_at,
+                cw.completed_at,
+                cw.total_duration_minutes as "total_duration_minutes: i32",
+                cw.total_sets as "total_sets: i32",
+                cw.total_volume_kg as "total_volume_kg: f32",
+                cw.notes,
+                cw.auto_closed as "auto_closed: bool"
+                FROM completed_workouts cw
+                LEFT JOIN workouts w ON w.id = cw.workout_id
+                WHERE cw.user_id = ?
+                ORDER BY cw.completed_at DESC LIMIT ?"#,
+            user.id,
+            HISTORY_LIMIT
+        )
+        .fetch_all(pool)
+        .await?;
+
+        for session in sessions {
+            let (Ok(start), Ok(end)) = (
+                DateTime::parse_from_rfc3339(&session.started_at),
+                DateTime::parse_from_rfc3339(&session.completed_at),
+            ) else {
+                continue;
+            };
+            let mut description = format!(
+                "{} sets · {} volume",
+                session.total_sets,
+                unit.format_volume(session.total_volume_kg)
+            );
+            if let Some(duration) = crate::models::active_workout::duration_display(session.total_duration_minutes) {
+                description.push_str(&format!(" · {duration}"));
+            }
+            if session.auto_closed {
+                description.push_str("\nClosed automatically after inactivity");
+            }
+            if let Some(notes) = crate::models::active_workout::notes_display(&session.notes) {
+                description.push_str(&format!("\n{notes}"));
+            }
+            events.push(Event {
+                uid: format!("session-{}@woplanner", session.id),
+                summary: format!("✓ {}", session.workout_name),
+                description: Some(description),
+                time: EventTime::Timed { start: start.to_utc(), end: end.to_utc() },
+            });
+        }
+    }
+
+    Ok(events)
+}
+
+/// Der Kalender selbst. Ohne Sitzung — das Token in der URL ist der Zugang,
+/// Kalender-Apps melden sich nicht an.
+pub async fn calendar_feed(
+    Path(file): Path<String>,
+    State(database_pool): State<SqlitePool>,
+) -> Result<impl IntoResponse, AppError> {
+    let not_found = || AppError::NotFound("Calendar not found".to_string());
+    let token = file.strip_suffix(".ics").ok_or_else(not_found)?;
+    let feed = sqlx::query_as!(
+        CalendarFeed,
+        r#"SELECT user_id, token, include_history as "include_history: bool", created_at
+           FROM calendar_feeds WHERE token = ?"#,
+        token
+    )
+    .fetch_optional(&database_pool)
+    .await?
+    .ok_or_else(not_found)?;
+    let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = ?", feed.user_id)
+        .fetch_one(&database_pool)
+        .await?;
+
+    let events = calendar_events(&database_pool, &user, feed.include_history).await?;
+    let body = ical::render(&format!("WOPlanner – {}", user.name), &events, Utc::now());
+
+    let mut headers = HeaderMap::new();
+    headers.insert("Content-Type", HeaderValue::from_static("text/calendar; charset=utf-8"));
+    headers.insert(
+        "Content-Disposition",
+        HeaderValue::from_static("inline; filename=\"woplanner.ics\""),
+    );
+    Ok((headers, body).into_response())
+}
+
+/// Legt den Link an oder ersetzt ihn; der alte funktioniert danach nicht mehr.
+pub async fn create_calendar_feed(
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let now = Utc::now().to_rfc3339();
+    sqlx::query!(
+        "INSERT INTO calendar_feeds (user_id, token, created_at) VALUES (?, ?, ?)
+         ON CONFLICT(user_id) DO UPDATE SET token = excluded.token, created_at = excluded.created_at",
+        current_user.id,
+        token,
+        now
+    )
+    .execute(&database_pool)
+    .await?;
+    tracing::info!("calendar link (re)created for user {}", current_user.id);
+
+    let template = CalendarPartialTemplate {
+        calendar: load_calendar_feed(&database_pool, &current_user.id).await?,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+pub async fn delete_calendar_feed(
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+
+    sqlx::query!("DELETE FROM calendar_feeds WHERE user_id = ?", current_user.id)
+        .execute(&database_pool)
+        .await?;
+
+    Ok(Html(CalendarPartialTemplate { calendar: None }.render()?).into_response())
+}
+
+/// Abgeschlossene Trainings im Kalender zeigen oder nicht.
+pub async fn update_calendar_history(
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Form(form): Form<CalendarHistoryForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+    let include_history = match form.include_history.as_str() {
+        "on" => true,
+        "off" => false,
+        other => return Err(AppError::BadRequest(format!("Unknown calendar setting '{other}'"))),
+    };
+
+    let result = sqlx::query!(
+        "UPDATE calendar_feeds SET include_history = ? WHERE user_id = ?",
+        include_history,
+        current_user.id
+    )
+    .execute(&database_pool)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Calendar link".to_string()));
+    }
+
+    let message = if include_history {
+        "Finished trainings are shown as past events."
+    } else {
+        "Only planned trainings are shown."
+    };
+    Ok(Html(message.to_string()).into_response())
+}
+
+pub fn router() -> Router<SqlitePool> {
+    Router::new()
+        .route("/calendar/{file}", get(calendar_feed))
+        .route("/users/calendar", post(create_calendar_feed))
+        .route("/users/calendar/delete", post(delete_calendar_feed))
+        .route("/users/calendar/history", post(update_calendar_history))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn seed(pool: &SqlitePool) {
+        sqlx::query!(
+            "INSERT INTO users (id, name, created_at) VALUES ('u-1', 'Anna', '2026-01-01T00:00:00Z'), ('u-2', 'Bert', '2026-01-01T00:00:00Z')"
+        ).execute(pool).await.unwrap();
+        // 2026-10-14 ist ein Mittwoch.
+        sqlx::query!(
+            "INSERT INTO workouts (id, user_id, name, description, is_active, schedule_type, schedule_day, created_at, updated_at) VALUES
+             ('w-mon', 'u-1', 'Push', 'Bench, then dips', 1, 'weekly', 1, '2026-10-14T08:00:00Z', '2026-10-14T08:00:00Z'),
+             ('w-off', 'u-1', 'Old Legs', NULL, 0, 'weekly', 3, '2026-10-14T08:00:00Z', '2026-10-14T08:00:00Z'),
+             ('w-rot', 'u-1', 'Pull', NULL, 1, 'rotation', NULL, '2026-10-14T08:00:00Z', '2026-10-14T08:00:00Z'),
+             ('w-man', 'u-1', 'Arms', NULL, 1, 'manual', NULL, '2026-10-14T08:00:00Z', '2026-10-14T08:00:00Z'),
+             ('w-bert', 'u-2', 'Fremd', NULL, 1, 'weekly', 2, '2026-10-14T08:00:00Z', '2026-10-14T08:00:00Z')"
+        ).execute(pool).await.unwrap();
+        sqlx::query!(
+            "INSERT INTO completed_workouts (id, user_id, workout_id, started_at, completed_at, total_duration_minutes, total_sets, total_volume_kg, notes, created_at) VALUES
+             ('s-1', 'u-1', 'w-mon', '2026-10-12T18:00:00+00:00', '2026-10-12T18:45:00+00:00', 45, 12, 640, 'gut', '2026-10-12T18:45:00+00:00'),
+             ('s-2', 'u-2', 'w-bert', '2026-10-13T18:00:00+00:00', '2026-10-13T18:50:00+00:00', 50, 1, 100, NULL, '2026-10-13T18:50:00+00:00')"
+        ).execute(pool).await.unwrap();
+        sqlx::query!(
+            "INSERT INTO calendar_feeds (user_id, token, include_history, created_at) VALUES ('u-1', 'secret-anna', 0, '2026-10-14T08:00:00Z')"
+        ).execute(pool).await.unwrap();
+    }
+
+    async fn fetch(pool: &SqlitePool, file: &str) -> Result<String, AppError> {
+        let response = calendar_feed(Path(file.to_string()), State(pool.clone())).await?.into_response();
+        assert_eq!(response.headers()["Content-Type"], "text/calendar; charset=utf-8");
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        Ok(String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn feed_lists_weekly_plans_and_optionally_finished_trainings(pool: SqlitePool) {
+        seed(&pool).await;
+
+        let planned = fetch(&pool, "secret-anna.ics").await.unwrap();
+        assert_eq!(planned.matches("BEGIN:VEVENT").count(), 1);
+        assert!(planned.contains("UID:workout-w-mon@woplanner\r\n"));
+        assert!(planned.contains("DTSTART;VALUE=DATE:20261019\r\n"));
+        assert!(planned.contains("RRULE:FREQ=WEEKLY;BYDAY=MO\r\n"));
+        assert!(planned.contains("DESCRIPTION:Bench\\, then dips\r\n"));
+        assert!(!planned.contains("Fremd") && !planned.contains("Old Legs") && !planned.contains("Pull"));
+
+        sqlx::query!("UPDATE calendar_feeds SET include_history = 1 WHERE user_id = 'u-1'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let with_history = fetch(&pool, "secret-anna.ics").await.unwrap();
+        assert_eq!(with_history.matches("BEGIN:VEVENT").count(), 2);
+        assert!(with_history.contains("UID:session-s-1@woplanner\r\n"));
+        assert!(with_history.contains("DTSTART:20261012T180000Z\r\nDTEND:20261012T184500Z\r\n"));
+        assert!(with_history.contains("SUMMARY:✓ Push\r\n"));
+        assert!(with_history.contains("DESCRIPTION:12 sets · 640kg volume"));
+        assert!(!with_history.contains("session-s-2"));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn unknown_or_replaced_tokens_find_nothing(pool: SqlitePool) {
+        seed(&pool).await;
+
+        assert!(matches!(fetch(&pool, "secret-anna").await, Err(AppError::NotFound(_))));
+        assert!(matches!(fetch(&pool, "guess.ics").await, Err(AppError::NotFound(_))));
+
+        sqlx::query!("UPDATE calendar_feeds SET token = 'secret-new' WHERE user_id = 'u-1'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(matches!(fetch(&pool, "secret-anna.ics").await, Err(AppError::NotFound(_))));
+        assert!(fetch(&pool, "secret-new.ics").await.is_ok());
+    }
+}
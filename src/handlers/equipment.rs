@@ -0,0 +1,158 @@
+use crate::error::AppError;
+use crate::handlers::get_current_user;
+use crate::models::User;
+use crate::plates::Equipment;
+use crate::units::WeightUnit;
+use axum::{
+    Form, Router,
+    extract::State,
+    response::{Html, IntoResponse},
+    routing::post,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use tower_sessions::Session;
+
+#[derive(Debug, Deserialize)]
+pub struct EquipmentForm {
+    pub unit: String,
+    pub bar_weight: String,
+    pub collar_weight: String,
+    pub plates: String,
+}
+
+/// Ausstattung des Nutzers. Wer nichts eingestellt hat, bekommt Stange und
+/// Scheiben in seiner Anzeigeeinheit.
+pub async fn load_equipment(pool: &SqlitePool, user: &User) -> Result<Equipment, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"SELECT unit, bar_weight as "bar_weight: f64", collar_weight as "collar_weight: f64", plates
+           FROM equipment_profiles WHERE user_id = ?"#,
+        user.id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(row) = row else {
+        return Ok(Equipment::default_for(user.unit()));
+    };
+    let unit = WeightUnit::from_db(&row.unit);
+    // Gespeichert wird nur, was `parse_plates` angenommen hat.
+    let plates = Equipment::parse_plates(&row.plates)
+        .unwrap_or_else(|_| Equipment::default_for(unit).plates);
+    Ok(Equipment {
+        unit,
+        bar: row.bar_weight,
+        collar: row.collar_weight,
+        plates,
+    })
+}
+
+fn weight_field(raw: &str, name: &str, max: f64) -> Result<f64, AppError> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Ok(0.0);
+    }
+    raw.parse::<f64>()
+        .ok()
+        .filter(|w| w.is_finite() && (0.0..=max).contains(w))
+        .ok_or_else(|| AppError::BadRequest(format!("{name} must be a number between 0 and {max}")))
+}
+
+pub async fn update_equipment(
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Form(form): Form<EquipmentForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+
+    let unit = WeightUnit::parse(&form.unit)
+        .ok_or_else(|| AppError::BadRequest(format!("Unknown weight unit '{}'", form.unit)))?;
+    let equipment = Equipment {
+        unit,
+        bar: weight_field(&form.bar_weight, "Bar weight", 100.0)?,
+        collar: weight_field(&form.collar_weight, "Collar weight", 10.0)?,
+        plates: Equipment::parse_plates(&form.plates).map_err(AppError::BadRequest)?,
+    };
+    if equipment.plates.is_empty() {
+        return Err(AppError::BadRequest("List at least one plate size".to_string()));
+    }
+
+    let unit_str = unit.as_str();
+    let plates = equipment.plates_text();
+    let now = Utc::now().to_rfc3339();
+    sqlx::query!(
+        "INSERT INTO equipment_profiles (user_id, unit, bar_weight, collar_weight, plates, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?)
+         ON CONFLICT(user_id) DO UPDATE SET
+            unit = excluded.unit,
+            bar_weight = excluded.bar_weight,
+            collar_weight = excluded.collar_weight,
+            plates = excluded.plates,
+            updated_at = excluded.updated_at",
+        current_user.id,
+        unit_str,
+        equipment.bar,
+        equipment.collar,
+        plates,
+        now
+    )
+    .execute(&database_pool)
+    .await?;
+
+    Ok(Html(format!("Saved: {}{unit} bar, plates {plates}.", equipment.bar_text())).into_response())
+}
+
+pub fn router() -> Router<SqlitePool> {
+    Router::new().route("/users/equipment", post(update_equipment))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plates::Plate;
+
+    async fn seed_user(pool: &SqlitePool, unit: &str) -> User {
+        sqlx::query!(
+            "INSERT INTO users (id, name, created_at, weight_unit) VALUES ('u-1', 'Anna', '2026-01-01T00:00:00Z', ?)",
+            unit
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query_as!(User, "SELECT * FROM users WHERE id = 'u-1'")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn without_a_profile_the_defaults_follow_the_display_unit(pool: SqlitePool) {
+        let user = seed_user(&pool, "lb").await;
+        let equipment = load_equipment(&pool, &user).await.unwrap();
+        assert_eq!(equipment, Equipment::default_for(WeightUnit::Lb));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn saved_profile_is_read_back_in_its_own_unit(pool: SqlitePool) {
+        let user = seed_user(&pool, "lb").await;
+        sqlx::query!(
+            "INSERT INTO equipment_profiles (user_id, unit, bar_weight, collar_weight, plates, updated_at)
+             VALUES ('u-1', 'kg', 15, 2.5, '20, 10x2', '2026-01-01T00:00:00Z')"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let equipment = load_equipment(&pool, &user).await.unwrap();
+        assert_eq!(equipment.unit, WeightUnit::Kg);
+        assert_eq!(equipment.empty_bar(), 20.0);
+        assert_eq!(equipment.plates, vec![
+            Plate { weight: 20.0, per_side: None },
+            Plate { weight: 10.0, per_side: Some(2) },
+        ]);
+    }
+}
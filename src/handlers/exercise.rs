@@ -1,5 +1,8 @@
 use crate::error::AppError;
+use crate::handlers::get_current_user;
 use crate::models::{Exercise, User};
+use crate::progression::estimated_1rm;
+use crate::units::WeightUnit;
 use askama::Template;
 use axum::{
     Form, Router,
@@ -32,12 +35,17 @@ pub struct ExerciseListPartialTemplate {
     pub exercises: Vec<Exercise>,
 }
 
+/// Gewicht und Volumen schon in der Einheit des Nutzers — das Diagramm
+/// rechnet nicht selbst um.
 #[derive(Debug, Serialize)]
 pub struct ProgressionDataPoint {
     pub date: String,
     pub weight: Option<f32>,
     pub reps: i32,
     pub volume: Option<f32>,
+    /// Geschätztes 1RM, mit RPE wo angegeben.
+    pub e1rm: Option<f32>,
+    pub rpe: Option<f64>,
     pub set_number: i32,
 }
 
@@ -55,21 +63,11 @@ pub struct ExerciseProgressionTemplate {
     pub exercise: Exercise,
     pub progression_data: Vec<ProgressionDataPoint>,
     pub progression_data_json: String, // JSON string for JavaScript
+    pub unit: WeightUnit,
     pub current_user: Option<User>,
     pub is_dashboard: bool,
 }
 
-async fn get_current_user(session: &Session, pool: &SqlitePool) -> Result<Option<User>, AppError> {
-    if let Ok(Some(user_id)) = session.get::<String>("current_user_id").await {
-        let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = ?", user_id)
-            .fetch_optional(pool)
-            .await?;
-        Ok(user)
-    } else {
-        Ok(None)
-    }
-}
-
 pub async fn list_exercises(
     State(database_pool): State<SqlitePool>,
     session: Session,
@@ -147,8 +145,8 @@ pub async fn show_exercise_progression(
         None => return Err(AppError::NotFound("Exercise not found".to_string())),
     };
 
-    let user_id = match &current_user {
-        Some(user) => &user.id,
+    let (user_id, unit) = match &current_user {
+        Some(user) => (&user.id, user.unit()),
         None => return Err(AppError::Unauthorized),
     };
 
@@ -157,12 +155,14 @@ pub async fn show_exercise_progression(
             cs.completed_at as date,
             cs.weight as "weight: f32",
             cs.reps as "reps: i32",
+            cs.rpe as "rpe: f64",
             cs.set_number as "set_number: i32",
             cs.exercise_id
            FROM completed_sets cs
            LEFT JOIN active_workouts aw ON cs.active_workout_id = aw.id
            LEFT JOIN completed_workouts cw ON cs.active_workout_id = cw.id
            WHERE cs.exercise_id = ? AND (aw.user_id = ? OR cw.user_id = ?)
+             AND cs.set_type != 'warmup'
            ORDER BY cs.completed_at DESC
            LIMIT 50"#,
         exercise_id,
@@ -179,9 +179,11 @@ pub async fn show_exercise_progression(
             // RFC-3339 mit Nanosekunden, das stand so in der Zeile
             // ("2025-11-06T13:27:24.856992504+00:00").
             date: format_date(&row.date),
-            weight: row.weight,
+            weight: row.weight.map(|w| unit.convert_kg(w as f64) as f32),
             reps: row.reps,
-            volume: row.weight.map(|w| w * row.reps as f32),
+            volume: row.weight.map(|w| unit.convert_kg(w as f64 * row.reps as f64) as f32),
+            e1rm: row.weight.map(|w| unit.convert_kg(estimated_1rm(w as f64, row.reps, row.rpe)) as f32),
+            rpe: row.rpe,
             set_number: row.set_number,
         })
         .collect();
@@ -194,6 +196,7 @@ pub async fn show_exercise_progression(
         exercise,
         progression_data: progression_data_vec,
         progression_data_json,
+        unit,
         current_user,
         is_dashboard: false,
     };
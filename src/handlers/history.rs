@@ -1,13 +1,26 @@
+use crate::csv;
 use crate::error::AppError;
-use crate::models::User;
+use crate::handlers::get_current_user;
+use crate::handlers::live_training::load_substitutions;
+use crate::handlers::workouts::write_plan;
+use crate::models::{
+    ExerciseTime, SetType, Substitution, User, duration_display, rpe_display, set_number_display,
+};
+use crate::units::WeightUnit;
+use crate::workout_yaml::{PlanExercise, WorkoutPlan};
 use askama::Template;
 use axum::{
-    Router,
-    extract::{Path, State},
+    Form, Router,
+    body::Body,
+    extract::{Path, Query, State},
+    http::{HeaderMap, HeaderValue},
     response::{Html, IntoResponse},
-    routing::get,
+    routing::{get, post},
 };
+use futures_util::{StreamExt, stream};
+use serde::Deserialize;
 use sqlx::SqlitePool;
+use tokio::sync::mpsc;
 use tower_sessions::Session;
 
 #[derive(Template)]
@@ -15,6 +28,9 @@ use tower_sessions::Session;
 pub struct HistoryListTemplate {
     pub current_user: Option<User>,
     pub workouts: Vec<CompletedWorkoutWithName>,
+    /// Für den Übungsfilter des CSV-Exports: nur, was der Nutzer trainiert hat.
+    pub exercises: Vec<ExerciseOption>,
+    pub unit: WeightUnit,
     pub is_dashboard: bool,
 }
 
@@ -24,6 +40,11 @@ pub struct HistoryDetailTemplate {
     pub current_user: Option<User>,
     pub workout: CompletedWorkoutWithName,
     pub exercises: Vec<ExerciseWithSets>,
+    /// Für diese Sitzung ersetzte Übungen.
+    pub substitutions: Vec<Substitution>,
+    /// Ohne Vorlage trainiert: Angebot, daraus einen Plan zu machen.
+    pub is_freestyle: bool,
+    pub unit: WeightUnit,
     pub is_dashboard: bool,
 }
 
@@ -36,6 +57,8 @@ pub struct CompletedWorkoutWithName {
     pub total_sets: i32,
     pub total_volume_kg: f32,
     pub notes: Option<String>,
+    /// Nicht vom Nutzer beendet, sondern nach zu langer Inaktivität.
+    pub auto_closed: bool,
 }
 
 impl CompletedWorkoutWithName {
@@ -65,10 +88,25 @@ impl CompletedWorkoutWithName {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct ExerciseOption {
+    pub id: String,
+    pub name: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct ExerciseWithSets {
+    pub exercise_id: String,
     pub exercise_name: String,
     pub sets: Vec<SetDetail>,
+    /// Zeit an der Übung, falls beim Abschluss gemessen.
+    pub seconds: Option<i64>,
+}
+
+impl ExerciseWithSets {
+    pub fn time_display(&self) -> Option<String> {
+        self.seconds.and_then(|s| duration_display((s / 60) as i32))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -77,28 +115,31 @@ pub struct SetDetail {
     pub weight: Option<f32>,
     pub reps: i32,
     pub notes: Option<String>,
+    pub rpe: Option<f64>,
+    pub set_type: String,
 }
 
 impl SetDetail {
-    pub fn weight_display(&self) -> String {
+    pub fn rpe_display(&self) -> Option<String> {
+        self.rpe.map(rpe_display)
+    }
+
+    pub fn set_type(&self) -> SetType {
+        SetType::from_db(&self.set_type)
+    }
+
+    pub fn number_display(&self) -> String {
+        set_number_display(self.set_type(), self.set_number)
+    }
+
+    pub fn weight_display(&self, unit: WeightUnit) -> String {
         match self.weight {
-            Some(w) => format!("{:.1}kg", w),
+            Some(w) => unit.format(w),
             None => "BW".to_string(),
         }
     }
 }
 
-async fn get_current_user(session: &Session, database_pool: &SqlitePool) -> Result<Option<User>, AppError> {
-    if let Ok(Some(user_id)) = session.get::<String>("current_user_id").await {
-        let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = ?", user_id)
-            .fetch_optional(database_pool)
-            .await?;
-        Ok(user)
-    } else {
-        Ok(None)
-    }
-}
-
 pub async fn list_history(
     State(database_pool): State<SqlitePool>,
     session: Session,
@@ -116,14 +157,15 @@ pub async fn list_history(
         CompletedWorkoutWithName,
         r#"SELECT
             cw.id,
-            w.name as workout_name,
+            COALESCE(w.name, 'Freestyle') as "workout_name!: String",
             cw.completed_at,
             cw.total_duration_minutes as "total_duration_minutes: i32",
             cw.total_sets as "total_sets: i32",
             cw.total_volume_kg as "total_volume_kg: f32",
-            cw.notes
+            cw.notes,
+            cw.auto_closed as "auto_closed: bool"
         FROM completed_workouts cw
-        JOIN workouts w ON cw.workout_id = w.id
+        LEFT JOIN workouts w ON cw.workout_id = w.id
         WHERE cw.user_id = ?
         ORDER BY cw.completed_at DESC
         LIMIT 50"#,
@@ -132,15 +174,190 @@ pub async fn list_history(
     .fetch_all(&database_pool)
     .await?;
 
+    let exercises = sqlx::query_as!(
+        ExerciseOption,
+        r#"SELECT DISTINCT e.id as "id!", e.name
+        FROM completed_sets cs
+        JOIN completed_workouts cw ON cs.active_workout_id = cw.id
+        JOIN exercises e ON cs.exercise_id = e.id
+        WHERE cw.user_id = ?
+        ORDER BY e.name"#,
+        current_user.id
+    )
+    .fetch_all(&database_pool)
+    .await?;
+
     let template = HistoryListTemplate {
         current_user: Some(current_user.clone()),
         workouts,
+        exercises,
+        unit: current_user.unit(),
         is_dashboard: false,
     };
 
     Ok(Html(template.render()?).into_response())
 }
 
+/// Sätze eines Trainings, nach Übung gruppiert in der Reihenfolge des
+/// jeweils ersten Satzes.
+async fn load_exercises_with_sets(
+    database_pool: &SqlitePool,
+    session_id: &str,
+) -> Result<Vec<ExerciseWithSets>, sqlx::Error> {
+    let sets = sqlx::query!(
+        r#"SELECT
+            cs.exercise_id,
+            e.name as exercise_name,
+            cs.set_number as "set_number: i32",
+            cs.weight as "weight: f32",
+            cs.reps as "reps: i32",
+            cs.notes,
+            cs.rpe as "rpe: f64",
+            cs.set_type
+        FROM completed_sets cs
+        JOIN exercises e ON cs.exercise_id = e.id
+        WHERE cs.active_workout_id = ?
+        ORDER BY cs.completed_at"#,
+        session_id
+    )
+    .fetch_all(database_pool)
+    .await?;
+
+    // Group sets by exercise
+    let mut exercises: Vec<ExerciseWithSets> = Vec::new();
+    for set in sets {
+        let set_detail = SetDetail {
+            set_number: set.set_number,
+            weight: set.weight,
+            reps: set.reps,
+            notes: set.notes,
+            rpe: set.rpe,
+            set_type: set.set_type,
+        };
+
+        if let Some(exercise) = exercises
+            .iter_mut()
+            .find(|e| e.exercise_name == set.exercise_name)
+        {
+            exercise.sets.push(set_detail);
+        } else {
+            exercises.push(ExerciseWithSets {
+                exercise_id: set.exercise_id,
+                exercise_name: set.exercise_name,
+                sets: vec![set_detail],
+                seconds: None,
+            });
+        }
+    }
+
+    Ok(exercises)
+}
+
+/// Plan aus einem freien Training: Übungen in der Reihenfolge ihres ersten
+/// Satzes, so viele Sätze wie Arbeitssätze, das schwerste Gewicht und die
+/// Spanne der Wiederholungen. Aufwärmsätze zählen nicht; gespeichert wird kg.
+pub fn plan_from_session(name: String, exercises: &[ExerciseWithSets]) -> WorkoutPlan {
+    let exercises = exercises
+        .iter()
+        .filter_map(|exercise| {
+            let working: Vec<&SetDetail> =
+                exercise.sets.iter().filter(|s| !s.set_type().is_warmup()).collect();
+            if working.is_empty() {
+                return None;
+            }
+            Some(PlanExercise {
+                name: exercise.exercise_name.clone(),
+                instructions: None,
+                video_url: None,
+                sets: working.len() as i64,
+                weight: working.iter().filter_map(|s| s.weight).map(f64::from).reduce(f64::max),
+                reps_min: working.iter().map(|s| s.reps as i64).min(),
+                reps_max: working.iter().map(|s| s.reps as i64).max(),
+                rest_seconds: None,
+                notes: None,
+                group: None,
+            })
+        })
+        .collect();
+    WorkoutPlan {
+        name,
+        description: None,
+        schedule_type: "manual".to_string(),
+        schedule_day: None,
+        unit: WeightUnit::Kg,
+        exercises,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SaveAsWorkoutForm {
+    pub name: String,
+}
+
+/// Freies Training als neue Vorlage speichern. Das Training hängt danach an
+/// der Vorlage, zählt also für deren Progression und wird nicht doppelt
+/// angeboten.
+pub async fn save_as_workout(
+    Path(session_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Form(form): Form<SaveAsWorkoutForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = get_current_user(&session, &database_pool)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let name = form.name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::BadRequest("Workout name cannot be empty".to_string()));
+    }
+    if name.len() > 100 {
+        return Err(AppError::BadRequest("Workout name must be 100 characters or less".to_string()));
+    }
+
+    let is_freestyle = sqlx::query_scalar!(
+        r#"SELECT workout_id IS NULL as "freestyle!: bool" FROM completed_workouts WHERE id = ? AND user_id = ?"#,
+        session_id,
+        current_user.id
+    )
+    .fetch_optional(&database_pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Training not found".to_string()))?;
+    if !is_freestyle {
+        return Err(AppError::BadRequest("This training already belongs to a workout".to_string()));
+    }
+
+    let exercises = load_exercises_with_sets(&database_pool, &session_id).await?;
+    let plan = plan_from_session(name, &exercises);
+    if plan.exercises.is_empty() {
+        return Err(AppError::BadRequest("This training has no working sets to save".to_string()));
+    }
+
+    // Doppelt abgeschickt: Nur die erste Vorlage bleibt, die zweite rollt
+    // mit dem Drop der Transaktion zurück.
+    let mut tx = database_pool.begin_with("BEGIN IMMEDIATE").await?;
+    let workout = write_plan(&mut tx, &current_user.id, &plan).await?;
+    let linked = sqlx::query!(
+        "UPDATE completed_workouts SET workout_id = ? WHERE id = ? AND user_id = ? AND workout_id IS NULL",
+        workout.id,
+        session_id,
+        current_user.id
+    )
+    .execute(&mut *tx)
+    .await?;
+    if linked.rows_affected() == 0 {
+        return Err(AppError::BadRequest("This training already belongs to a workout".to_string()));
+    }
+    tx.commit().await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "HX-Redirect",
+        HeaderValue::from_str(&format!("/workouts/{}", workout.id))?,
+    );
+    Ok((headers, Html("Workout saved".to_string())).into_response())
+}
+
 pub async fn show_history_detail(
     Path(workout_id): Path<String>,
     State(database_pool): State<SqlitePool>,
@@ -160,14 +377,15 @@ pub async fn show_history_detail(
         CompletedWorkoutWithName,
         r#"SELECT
             cw.id,
-            w.name as workout_name,
+            COALESCE(w.name, 'Freestyle') as "workout_name!: String",
             cw.completed_at,
             cw.total_duration_minutes as "total_duration_minutes: i32",
             cw.total_sets as "total_sets: i32",
             cw.total_volume_kg as "total_volume_kg: f32",
-            cw.notes
+            cw.notes,
+            cw.auto_closed as "auto_closed: bool"
         FROM completed_workouts cw
-        JOIN workouts w ON cw.workout_id = w.id
+        LEFT JOIN workouts w ON cw.workout_id = w.id
         WHERE cw.id = ? AND cw.user_id = ?"#,
         workout_id,
         current_user.id
@@ -184,58 +402,489 @@ pub async fn show_history_detail(
         }
     };
 
-    // Get all sets for this workout grouped by exercise
-    let sets = sqlx::query!(
-        r#"SELECT
-            e.name as exercise_name,
-            cs.set_number as "set_number: i32",
-            cs.weight as "weight: f32",
-            cs.reps as "reps: i32",
-            cs.notes
-        FROM completed_sets cs
-        JOIN exercises e ON cs.exercise_id = e.id
-        WHERE cs.active_workout_id = ?
-        ORDER BY cs.completed_at"#,
+    let mut exercises = load_exercises_with_sets(&database_pool, &workout.id).await?;
+    let session = sqlx::query!(
+        r#"SELECT workout_id IS NULL as "freestyle!: bool", exercise_times FROM completed_workouts WHERE id = ?"#,
         workout.id
     )
-    .fetch_all(&database_pool)
+    .fetch_one(&database_pool)
     .await?;
-
-    // Group sets by exercise
-    let mut exercises: Vec<ExerciseWithSets> = Vec::new();
-    for set in sets {
-        let set_detail = SetDetail {
-            set_number: set.set_number,
-            weight: set.weight,
-            reps: set.reps,
-            notes: set.notes,
-        };
-
-        if let Some(exercise) = exercises
-            .iter_mut()
-            .find(|e| e.exercise_name == set.exercise_name)
-        {
-            exercise.sets.push(set_detail);
-        } else {
-            exercises.push(ExerciseWithSets {
-                exercise_name: set.exercise_name,
-                sets: vec![set_detail],
-            });
-        }
+    let is_freestyle = session.freestyle;
+    let times: Vec<ExerciseTime> = session
+        .exercise_times
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    for exercise in &mut exercises {
+        exercise.seconds = times
+            .iter()
+            .find(|t| t.exercise_id == exercise.exercise_id)
+            .map(|t| t.seconds);
     }
+    let substitutions = load_substitutions(&database_pool, &workout.id).await?;
 
     let template = HistoryDetailTemplate {
         current_user: Some(current_user.clone()),
         workout,
         exercises,
+        substitutions,
+        is_freestyle,
+        unit: current_user.unit(),
         is_dashboard: false,
     };
 
     Ok(Html(template.render()?).into_response())
 }
 
+#[derive(Debug, Deserialize)]
+pub struct HistoryExportQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub exercise: Option<String>,
+}
+
+/// Geprüfte Filter des CSV-Exports. Datumsgrenzen als `YYYY-MM-DD`, beide
+/// einschließlich, verglichen mit dem Datumsteil des UTC-Zeitstempels.
+#[derive(Debug, Default, PartialEq)]
+pub struct ExportFilter {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub exercise_id: Option<String>,
+}
+
+impl ExportFilter {
+    pub fn parse(query: HistoryExportQuery) -> Result<Self, AppError> {
+        // Leere Formularfelder kommen als "" an, nicht als fehlend.
+        fn date(raw: Option<String>, name: &str) -> Result<Option<String>, AppError> {
+            match raw.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+                None => Ok(None),
+                Some(s) => chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .map(|d| Some(d.format("%Y-%m-%d").to_string()))
+                    .map_err(|_| AppError::BadRequest(format!("'{name}' must be a date like 2026-01-31"))),
+            }
+        }
+
+        let filter = ExportFilter {
+            from: date(query.from, "from")?,
+            to: date(query.to, "to")?,
+            exercise_id: query.exercise.filter(|s| !s.trim().is_empty()),
+        };
+        if let (Some(from), Some(to)) = (&filter.from, &filter.to)
+            && from > to
+        {
+            return Err(AppError::BadRequest("'from' must not be after 'to'".to_string()));
+        }
+        Ok(filter)
+    }
+}
+
+/// Ab dieser Größe geht ein Block an den Client — nicht jede Zeile einzeln.
+const CSV_CHUNK_BYTES: usize = 16 * 1024;
+
+/// Puffer zwischen Abfrage und Antwort.
+struct CsvSink {
+    tx: mpsc::Sender<Result<String, sqlx::Error>>,
+    buffer: String,
+}
+
+impl CsvSink {
+    /// `false`, wenn der Client nicht mehr liest — dann abbrechen.
+    async fn push(&mut self, line: String) -> bool {
+        self.buffer.push_str(&line);
+        if self.buffer.len() < CSV_CHUNK_BYTES {
+            return true;
+        }
+        let chunk = std::mem::take(&mut self.buffer);
+        self.tx.send(Ok(chunk)).await.is_ok()
+    }
+}
+
+/// CSV in den Body streamen, ohne die ganze Historie im Speicher zu halten.
+///
+/// Eigener Task mit Kanal: der sqlx-Stream leiht sich Pool und Abfrage und
+/// kann deshalb nicht selbst der Body sein. Bricht die Abfrage ab, endet der
+/// Download mit einem Fehler statt mit einer still gekürzten Datei.
+fn csv_body<F, Fut>(produce: F) -> Body
+where
+    F: FnOnce(CsvSink) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<CsvSink, sqlx::Error>> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(4);
+    let error_tx = tx.clone();
+    tokio::spawn(async move {
+        match produce(CsvSink { tx, buffer: String::new() }).await {
+            Ok(sink) => {
+                if !sink.buffer.is_empty() {
+                    let _ = sink.tx.send(Ok(sink.buffer)).await;
+                }
+            }
+            Err(e) => {
+                let _ = error_tx.send(Err(e)).await;
+            }
+        }
+    });
+    Body::from_stream(stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|item| (item, rx))
+    }))
+}
+
+fn csv_headers(filename: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert("Content-Type", HeaderValue::from_static("text/csv; charset=utf-8"));
+    headers.insert(
+        "Content-Disposition",
+        HeaderValue::from_str(&format!("attachment; filename=\"{filename}\""))
+            .unwrap_or(HeaderValue::from_static("attachment")),
+    );
+    headers
+}
+
+/// Eine Zeile pro Satz — dieselbe Verknüpfung wie `show_history_detail`,
+/// über alle abgeschlossenen Trainings des Nutzers. Gewichte in der Einheit
+/// des Nutzers, die Spaltennamen sagen welche.
+pub fn sets_csv(pool: SqlitePool, user_id: String, filter: ExportFilter, unit: WeightUnit) -> Body {
+    csv_body(move |mut sink| async move {
+        sink.push(csv::row(&[
+            "date".to_string(),
+            "workout".to_string(),
+            "exercise".to_string(),
+            "set".to_string(),
+            format!("weight_{unit}"),
+            "reps".to_string(),
+            "rpe".to_string(),
+            "set_type".to_string(),
+            "notes".to_string(),
+            format!("volume_{unit}"),
+        ]))
+        .await;
+
+        let mut rows = sqlx::query!(
+            r#"SELECT
+                cs.completed_at,
+                COALESCE(w.name, 'Freestyle') as "workout_name!: String",
+                e.name as exercise_name,
+                cs.set_number as "set_number: i32",
+                cs.weight as "weight: f64",
+                cs.reps as "reps: i32",
+                cs.rpe as "rpe: f64",
+                cs.set_type,
+                cs.notes
+            FROM completed_sets cs
+            JOIN completed_workouts cw ON cs.active_workout_id = cw.id
+            LEFT JOIN workouts w ON cw.workout_id = w.id
+            JOIN exercises e ON cs.exercise_id = e.id
+            WHERE cw.user_id = ?
+              AND (? IS NULL OR substr(cs.completed_at, 1, 10) >= ?)
+              AND (? IS NULL OR substr(cs.completed_at, 1, 10) <= ?)
+              AND (? IS NULL OR cs.exercise_id = ?)
+            ORDER BY cs.completed_at, cs.set_number"#,
+            user_id,
+            filter.from,
+            filter.from,
+            filter.to,
+            filter.to,
+            filter.exercise_id,
+            filter.exercise_id
+        )
+        .fetch(&pool);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+            // Aufwärmsätze zählen nicht zum Volumen, wie in `finish_training`.
+            let volume = match SetType::from_db(&row.set_type) {
+                SetType::Warmup => None,
+                _ => row.weight.map(|w| unit.convert_kg(w * row.reps as f64)),
+            };
+            let line = csv::row(&[
+                row.completed_at.get(..10).unwrap_or(&row.completed_at).to_string(),
+                row.workout_name,
+                row.exercise_name,
+                row.set_number.to_string(),
+                csv::number(row.weight.map(|w| unit.convert_kg(w))),
+                row.reps.to_string(),
+                csv::number(row.rpe),
+                row.set_type,
+                row.notes.unwrap_or_default(),
+                csv::number(volume),
+            ]);
+            if !sink.push(line).await {
+                break;
+            }
+        }
+        drop(rows);
+        Ok(sink)
+    })
+}
+
+/// Eine Zeile pro Training aus `completed_workouts`. Der Übungsfilter gilt
+/// hier nicht — die Summen beziehen sich immer auf das ganze Training.
+pub fn sessions_csv(pool: SqlitePool, user_id: String, filter: ExportFilter, unit: WeightUnit) -> Body {
+    csv_body(move |mut sink| async move {
+        sink.push(csv::row(&[
+            "date".to_string(),
+            "workout".to_string(),
+            "duration_minutes".to_string(),
+            "sets".to_string(),
+            format!("volume_{unit}"),
+            "notes".to_string(),
+        ]))
+        .await;
+
+        let mut rows = sqlx::query!(
+            r#"SELECT
+                cw.completed_at,
+                COALESCE(w.name, 'Freestyle') as "workout_name!: String",
+                cw.total_duration_minutes as "total_duration_minutes: i32",
+                cw.total_sets as "total_sets: i32",
+                cw.total_volume_kg as "total_volume_kg: f64",
+                cw.notes
+            FROM completed_workouts cw
+            LEFT JOIN workouts w ON cw.workout_id = w.id
+            WHERE cw.user_id = ?
+              AND (? IS NULL OR substr(cw.completed_at, 1, 10) >= ?)
+              AND (? IS NULL OR substr(cw.completed_at, 1, 10) <= ?)
+            ORDER BY cw.completed_at"#,
+            user_id,
+            filter.from,
+            filter.from,
+            filter.to,
+            filter.to
+        )
+        .fetch(&pool);
+
+        while let Some(row) = rows.next().await {
+            let row = row?;
+            let line = csv::row(&[
+                row.completed_at.get(..10).unwrap_or(&row.completed_at).to_string(),
+                row.workout_name,
+                row.total_duration_minutes.to_string(),
+                row.total_sets.to_string(),
+                csv::number(Some(unit.convert_kg(row.total_volume_kg))),
+                row.notes.unwrap_or_default(),
+            ]);
+            if !sink.push(line).await {
+                break;
+            }
+        }
+        drop(rows);
+        Ok(sink)
+    })
+}
+
+pub async fn export_sets_csv(
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Query(query): Query<HistoryExportQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+    let filter = ExportFilter::parse(query)?;
+
+    Ok((
+        csv_headers("woplanner-sets.csv"),
+        sets_csv(database_pool, current_user.id.clone(), filter, current_user.unit()),
+    ))
+}
+
+pub async fn export_sessions_csv(
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Query(query): Query<HistoryExportQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+    let filter = ExportFilter::parse(query)?;
+
+    Ok((
+        csv_headers("woplanner-sessions.csv"),
+        sessions_csv(database_pool, current_user.id.clone(), filter, current_user.unit()),
+    ))
+}
+
 pub fn router() -> Router<SqlitePool> {
     Router::new()
         .route("/history", get(list_history))
+        .route("/history/export.csv", get(export_sets_csv))
+        .route("/history/sessions.csv", get(export_sessions_csv))
         .route("/history/{id}", get(show_history_detail))
+        .route("/history/{id}/save-as-workout", post(save_as_workout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn seed(pool: &SqlitePool) {
+        sqlx::query!(
+            "INSERT INTO users (id, name, created_at) VALUES ('u-1', 'Anna', '2026-01-01T00:00:00Z'), ('u-2', 'Bert', '2026-01-01T00:00:00Z')"
+        ).execute(pool).await.unwrap();
+        sqlx::query!(
+            "INSERT INTO exercises (id, name, instructions, video_url, created_at) VALUES
+             ('ex-bench', 'Bench Press', 'x', NULL, '2026-01-01T00:00:00Z'),
+             ('ex-pull', 'Pull-up', 'x', NULL, '2026-01-01T00:00:00Z')"
+        ).execute(pool).await.unwrap();
+        sqlx::query!(
+            "INSERT INTO workouts (id, user_id, name, description, is_active, schedule_type, schedule_day, created_at, updated_at) VALUES
+             ('w-1', 'u-1', 'Push, Pull', NULL, 1, 'manual', NULL, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z'),
+             ('w-2', 'u-2', 'Fremd', NULL, 1, 'manual', NULL, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')"
+        ).execute(pool).await.unwrap();
+        sqlx::query!(
+            "INSERT INTO completed_workouts (id, user_id, workout_id, started_at, completed_at, total_duration_minutes, total_sets, total_volume_kg, notes, created_at) VALUES
+             ('s-1', 'u-1', 'w-1', '2026-02-01T18:00:00+00:00', '2026-02-01T18:45:00+00:00', 45, 2, 640, NULL, '2026-02-01T18:45:00+00:00'),
+             ('s-2', 'u-1', 'w-1', '2026-02-08T18:00:00+00:00', '2026-02-08T18:50:00+00:00', 50, 1, 680, 'gut', '2026-02-08T18:50:00+00:00'),
+             ('s-3', 'u-2', 'w-2', '2026-02-08T18:00:00+00:00', '2026-02-08T18:50:00+00:00', 50, 1, 100, NULL, '2026-02-08T18:50:00+00:00')"
+        ).execute(pool).await.unwrap();
+        sqlx::query!(
+            "INSERT INTO completed_sets (id, active_workout_id, exercise_id, set_number, weight, reps, notes, rpe, set_type, completed_at, created_at) VALUES
+             ('c-0', 's-1', 'ex-bench', 1, 40.0, 10, NULL, NULL, 'warmup', '2026-02-01T18:05:00+00:00', '2026-02-01T18:05:00+00:00'),
+             ('c-1', 's-1', 'ex-bench', 1, 80.0, 8, 'schwer, aber sauber', 8.5, 'working', '2026-02-01T18:10:00+00:00', '2026-02-01T18:10:00+00:00'),
+             ('c-2', 's-1', 'ex-pull', 1, NULL, 10, NULL, NULL, 'working', '2026-02-01T18:20:00+00:00', '2026-02-01T18:20:00+00:00'),
+             ('c-3', 's-2', 'ex-bench', 1, 82.5, 8, NULL, NULL, 'amrap', '2026-02-08T18:10:00+00:00', '2026-02-08T18:10:00+00:00'),
+             ('c-4', 's-3', 'ex-bench', 1, 10.0, 10, NULL, NULL, 'working', '2026-02-08T18:10:00+00:00', '2026-02-08T18:10:00+00:00')"
+        ).execute(pool).await.unwrap();
+    }
+
+    async fn text(body: Body) -> String {
+        let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    fn filter(from: &str, to: &str, exercise: &str) -> ExportFilter {
+        ExportFilter::parse(HistoryExportQuery {
+            from: Some(from.into()),
+            to: Some(to.into()),
+            exercise: Some(exercise.into()),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn empty_filter_fields_mean_no_filter() {
+        assert_eq!(filter("", " ", ""), ExportFilter::default());
+    }
+
+    #[test]
+    fn invalid_or_reversed_dates_are_rejected() {
+        let bad = ExportFilter::parse(HistoryExportQuery {
+            from: Some("01.02.2026".into()),
+            to: None,
+            exercise: None,
+        });
+        assert!(matches!(bad, Err(AppError::BadRequest(m)) if m.contains("'from'")));
+
+        let reversed = ExportFilter::parse(HistoryExportQuery {
+            from: Some("2026-03-01".into()),
+            to: Some("2026-02-01".into()),
+            exercise: None,
+        });
+        assert!(matches!(reversed, Err(AppError::BadRequest(_))));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn sets_csv_has_one_row_per_set_of_this_user(pool: SqlitePool) {
+        seed(&pool).await;
+        let csv = text(sets_csv(pool, "u-1".into(), ExportFilter::default(), WeightUnit::Kg)).await;
+        assert_eq!(
+            csv,
+            "date,workout,exercise,set,weight_kg,reps,rpe,set_type,notes,volume_kg\r\n\
+             2026-02-01,\"Push, Pull\",Bench Press,1,40,10,,warmup,,\r\n\
+             2026-02-01,\"Push, Pull\",Bench Press,1,80,8,8.5,working,\"schwer, aber sauber\",640\r\n\
+             2026-02-01,\"Push, Pull\",Pull-up,1,,10,,working,,\r\n\
+             2026-02-08,\"Push, Pull\",Bench Press,1,82.5,8,,amrap,,660\r\n"
+        );
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn sets_csv_applies_date_range_and_exercise(pool: SqlitePool) {
+        seed(&pool).await;
+        let csv = text(sets_csv(pool.clone(), "u-1".into(), filter("2026-02-08", "2026-02-08", ""), WeightUnit::Kg)).await;
+        assert_eq!(csv.lines().count(), 2, "{csv}");
+        assert!(csv.contains("2026-02-08"));
+
+        let csv = text(sets_csv(pool, "u-1".into(), filter("", "", "ex-pull"), WeightUnit::Kg)).await;
+        assert_eq!(csv.lines().count(), 2, "{csv}");
+        assert!(csv.contains("Pull-up"));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn sessions_csv_summarises_completed_workouts(pool: SqlitePool) {
+        seed(&pool).await;
+        let csv = text(sessions_csv(pool, "u-1".into(), filter("2026-02-02", "", ""), WeightUnit::Kg)).await;
+        assert_eq!(
+            csv,
+            "date,workout,duration_minutes,sets,volume_kg,notes\r\n\
+             2026-02-08,\"Push, Pull\",50,1,680,gut\r\n"
+        );
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn csv_follows_the_users_unit(pool: SqlitePool) {
+        seed(&pool).await;
+        let csv = text(sets_csv(pool.clone(), "u-1".into(), filter("2026-02-01", "2026-02-01", "ex-bench"), WeightUnit::Lb)).await;
+        assert_eq!(
+            csv,
+            "date,workout,exercise,set,weight_lb,reps,rpe,set_type,notes,volume_lb\r\n\
+             2026-02-01,\"Push, Pull\",Bench Press,1,88.2,10,,warmup,,\r\n\
+             2026-02-01,\"Push, Pull\",Bench Press,1,176.4,8,8.5,working,\"schwer, aber sauber\",1411\r\n"
+        );
+
+        let csv = text(sessions_csv(pool, "u-1".into(), filter("2026-02-02", "", ""), WeightUnit::Lb)).await;
+        assert!(csv.starts_with("date,workout,duration_minutes,sets,volume_lb,notes\r\n"), "{csv}");
+        assert!(csv.contains(",1499.1,"), "{csv}");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn freestyle_session_becomes_a_plan_without_warm_ups(pool: SqlitePool) {
+        seed(&pool).await;
+        sqlx::query!("UPDATE completed_workouts SET workout_id = NULL WHERE id = 's-1'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let exercises = load_exercises_with_sets(&pool, "s-1").await.unwrap();
+        let plan = plan_from_session("Upper".to_string(), &exercises);
+        assert_eq!(plan.exercises.len(), 2);
+        let bench = &plan.exercises[0];
+        assert_eq!((bench.name.as_str(), bench.sets, bench.weight), ("Bench Press", 1, Some(80.0)));
+        assert_eq!((bench.reps_min, bench.reps_max), (Some(8), Some(8)));
+        assert_eq!(plan.exercises[1].weight, None);
+
+        let mut conn = pool.acquire().await.unwrap();
+        let workout = write_plan(&mut conn, "u-1", &plan).await.unwrap();
+        let linked = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM workout_exercises WHERE workout_id = ?",
+            workout.id
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .unwrap();
+        assert_eq!(linked, 2);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn saving_a_freestyle_session_twice_creates_one_workout(pool: SqlitePool) {
+        seed(&pool).await;
+        sqlx::query!("UPDATE completed_workouts SET workout_id = NULL WHERE id = 's-1'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let save = || async {
+            let session = Session::new(None, std::sync::Arc::new(tower_sessions::MemoryStore::default()), None);
+            session.insert("current_user_id", "u-1").await.unwrap();
+            let form = SaveAsWorkoutForm { name: "Upper".to_string() };
+            save_as_workout(Path("s-1".to_string()), State(pool.clone()), session, Form(form)).await
+        };
+
+        let (first, second) = tokio::join!(save(), save());
+        assert_eq!([first.is_ok(), second.is_ok()].iter().filter(|ok| **ok).count(), 1);
+        assert!(matches!(first.err().or(second.err()), Some(AppError::BadRequest(_))));
+        let workouts = sqlx::query_scalar!("SELECT COUNT(*) FROM workouts WHERE user_id = 'u-1' AND name = 'Upper'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(workouts, 1);
+    }
 }
@@ -0,0 +1,508 @@
+use crate::error::AppError;
+use crate::handlers::get_current_user;
+use crate::handlers::personal_records::recompute_prs;
+use crate::handlers::workouts::{find_exercise_by_name, insert_exercise};
+use crate::history_import::{ImportedHistory, parse};
+use crate::models::Exercise;
+use crate::units::WeightUnit;
+use askama::Template;
+use axum::{
+    Form, Router,
+    extract::{DefaultBodyLimit, State},
+    http::{HeaderMap, HeaderValue},
+    response::{Html, IntoResponse},
+    routing::post,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::{SqliteConnection, SqlitePool};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+/// Wie beim Kontoarchiv: Jahre an Sätzen passen nicht in axums 2 MB.
+const MAX_IMPORT_BYTES: usize = 32 * 1024 * 1024;
+
+/// So viele übersprungene Zeilen zeigt die Vorschau einzeln.
+const SKIPPED_SHOWN: usize = 10;
+
+#[derive(Deserialize)]
+pub struct HistoryImportForm {
+    pub csv: String,
+    pub unit: Option<String>,
+}
+
+impl HistoryImportForm {
+    fn parse(&self) -> Result<ImportedHistory, AppError> {
+        let unit = self
+            .unit
+            .as_deref()
+            .and_then(WeightUnit::parse)
+            .unwrap_or(WeightUnit::Kg);
+        parse(&self.csv, unit).map_err(AppError::BadRequest)
+    }
+}
+
+#[derive(Template)]
+#[template(path = "history/import_preview.html")]
+pub struct ImportPreviewTemplate {
+    pub preview: ImportPreview,
+    /// Wandert unverändert in das Bestätigungsformular.
+    pub csv: String,
+    pub unit: String,
+}
+
+/// Was ein Import schreiben würde, ohne zu schreiben.
+#[derive(Debug, PartialEq)]
+pub struct ImportPreview {
+    pub source: String,
+    pub sessions: usize,
+    pub sets: usize,
+    pub first_date: String,
+    pub last_date: String,
+    pub already_imported: usize,
+    pub matched_exercises: Vec<String>,
+    pub new_exercises: Vec<String>,
+    /// Gleichnamige Pläne, an die die Trainings gehängt werden; sie zählen
+    /// danach für deren Progression.
+    pub matched_workouts: Vec<String>,
+    /// Leere, inaktive Pläne, die für die übrigen Namen entstehen.
+    pub new_workouts: Vec<String>,
+    pub skipped: Vec<String>,
+    pub skipped_total: usize,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct HistoryImportSummary {
+    pub sessions: usize,
+    pub sets: usize,
+    pub exercises_created: usize,
+    pub already_imported: usize,
+}
+
+/// Schlüssel in `imported_ids` — derselbe Tabellenplatz wie beim
+/// Kontoarchiv, eigene Art, damit sich die Quellen nicht mischen.
+const IMPORT_KIND: &str = "csv-session";
+
+async fn already_imported(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+    key: &str,
+) -> Result<bool, AppError> {
+    let found = sqlx::query_scalar!(
+        "SELECT i.local_id FROM imported_ids i
+         JOIN completed_workouts cw ON cw.id = i.local_id
+         WHERE i.user_id = ? AND i.kind = ? AND i.source_id = ?",
+        user_id,
+        IMPORT_KIND,
+        key
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+    Ok(found.is_some())
+}
+
+pub async fn preview_history(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+    history: &ImportedHistory,
+) -> Result<ImportPreview, AppError> {
+    let mut already = 0;
+    let mut names = BTreeSet::new();
+    let mut workout_names = BTreeSet::new();
+    for session in &history.sessions {
+        if already_imported(conn, user_id, &session.key).await? {
+            already += 1;
+        }
+        names.extend(session.sets.iter().map(|s| s.exercise_name.clone()));
+        workout_names.insert(session.workout_name.clone());
+    }
+
+    let mut matched_exercises = Vec::new();
+    let mut new_exercises = Vec::new();
+    for name in names {
+        match find_exercise_by_name(conn, &name).await? {
+            Some(_) => matched_exercises.push(name),
+            None => new_exercises.push(name),
+        }
+    }
+
+    // Abgleich wie in `write_history`: ohne Groß-/Kleinschreibung, jeder
+    // Name nur einmal.
+    let mut matched_workouts = Vec::new();
+    let mut new_workouts = Vec::new();
+    let mut seen = HashSet::new();
+    for name in workout_names {
+        if !seen.insert(name.trim().to_lowercase()) {
+            continue;
+        }
+        let existing = sqlx::query_scalar!(
+            "SELECT id FROM workouts WHERE user_id = ? AND lower(trim(name)) = lower(trim(?)) LIMIT 1",
+            user_id,
+            name
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+        match existing {
+            Some(_) => matched_workouts.push(name),
+            None => new_workouts.push(name),
+        }
+    }
+
+    let dates = history.sessions.iter().map(|s| s.started_at.date());
+    Ok(ImportPreview {
+        source: history.source.label().to_string(),
+        sessions: history.sessions.len(),
+        sets: history.sessions.iter().map(|s| s.sets.len()).sum(),
+        first_date: dates.clone().min().map(|d| d.to_string()).unwrap_or_default(),
+        last_date: dates.max().map(|d| d.to_string()).unwrap_or_default(),
+        already_imported: already,
+        matched_exercises,
+        new_exercises,
+        matched_workouts,
+        new_workouts,
+        skipped: history.skipped.iter().take(SKIPPED_SHOWN).cloned().collect(),
+        skipped_total: history.skipped.len(),
+    })
+}
+
+/// Historie schreiben. Übungen laufen über denselben Namensabgleich wie
+/// `write_plan`; Trainings, die schon einmal importiert wurden, bleiben
+/// aus. Danach werden die Rekorde der berührten Übungen neu gerechnet.
+pub async fn write_history(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+    history: &ImportedHistory,
+) -> Result<HistoryImportSummary, AppError> {
+    let mut summary = HistoryImportSummary::default();
+    let source = history.source.label();
+    let mut exercise_ids: HashMap<String, String> = HashMap::new();
+    let mut workout_ids: HashMap<String, String> = HashMap::new();
+    let mut touched_exercises = HashSet::new();
+
+    for session in &history.sessions {
+        if already_imported(conn, user_id, &session.key).await? {
+            summary.already_imported += 1;
+            continue;
+        }
+
+        // `completed_workouts` braucht einen Plan. Gleichnamige Pläne werden
+        // benutzt, sonst entsteht ein leerer, inaktiver Plan mit dem Namen.
+        let workout_id = match workout_ids.get(&session.workout_name) {
+            Some(id) => id.clone(),
+            None => {
+                let existing = sqlx::query_scalar!(
+                    "SELECT id FROM workouts WHERE user_id = ? AND lower(trim(name)) = lower(trim(?)) LIMIT 1",
+                    user_id,
+                    session.workout_name
+                )
+                .fetch_optional(&mut *conn)
+                .await?;
+                let id = match existing {
+                    Some(id) => id,
+                    None => {
+                        let id = Uuid::new_v4().to_string();
+                        let now = Utc::now().to_rfc3339();
+                        let description = format!("Imported from {source}");
+                        sqlx::query!(
+                            "INSERT INTO workouts (id, user_id, name, description, is_active, schedule_type, schedule_day, created_at, updated_at)
+                             VALUES (?, ?, ?, ?, FALSE, 'manual', NULL, ?, ?)",
+                            id,
+                            user_id,
+                            session.workout_name,
+                            description,
+                            now,
+                            now
+                        )
+                        .execute(&mut *conn)
+                        .await?;
+                        id
+                    }
+                };
+                workout_ids.insert(session.workout_name.clone(), id.clone());
+                id
+            }
+        };
+
+        let session_id = Uuid::new_v4().to_string();
+        let started_at = session.started_at.and_utc().to_rfc3339();
+        let completed_at = session.completed_at.and_utc().to_rfc3339();
+        let duration = session.duration_minutes();
+        let total_sets = session.working_set_count();
+        let volume = session.volume_kg();
+        sqlx::query!(
+            "INSERT INTO completed_workouts (id, user_id, workout_id, started_at, completed_at, total_duration_minutes, total_sets, total_volume_kg, notes, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            session_id,
+            user_id,
+            workout_id,
+            started_at,
+            completed_at,
+            duration,
+            total_sets,
+            volume,
+            session.notes,
+            completed_at
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        for set in &session.sets {
+            let exercise_id = match exercise_ids.get(&set.exercise_name) {
+                Some(id) => id.clone(),
+                None => {
+                    let id = match find_exercise_by_name(conn, &set.exercise_name).await? {
+                        Some(id) => id,
+                        None => {
+                            let exercise = Exercise::new(
+                                set.exercise_name.clone(),
+                                format!("Imported from {source}."),
+                                None,
+                            );
+                            insert_exercise(conn, &exercise).await?;
+                            summary.exercises_created += 1;
+                            exercise.id
+                        }
+                    };
+                    exercise_ids.insert(set.exercise_name.clone(), id.clone());
+                    id
+                }
+            };
+
+            let set_id = Uuid::new_v4().to_string();
+            let set_completed_at = set.completed_at.and_utc().to_rfc3339();
+            let set_type = set.set_type.as_str();
+            sqlx::query!(
+                "INSERT INTO completed_sets (id, active_workout_id, exercise_id, set_number, weight, reps, notes, rpe, set_type, completed_at, created_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                set_id,
+                session_id,
+                exercise_id,
+                set.set_number,
+                set.weight_kg,
+                set.reps,
+                set.notes,
+                set.rpe,
+                set_type,
+                set_completed_at,
+                set_completed_at
+            )
+            .execute(&mut *conn)
+            .await?;
+            touched_exercises.insert(exercise_id);
+            summary.sets += 1;
+        }
+
+        let now = Utc::now().to_rfc3339();
+        sqlx::query!(
+            "INSERT OR REPLACE INTO imported_ids (user_id, kind, source_id, local_id, created_at)
+             VALUES (?, ?, ?, ?, ?)",
+            user_id,
+            IMPORT_KIND,
+            session.key,
+            session_id,
+            now
+        )
+        .execute(&mut *conn)
+        .await?;
+        summary.sessions += 1;
+    }
+
+    for exercise_id in &touched_exercises {
+        recompute_prs(conn, user_id, exercise_id).await?;
+    }
+
+    Ok(summary)
+}
+
+/// Datei lesen und zeigen, was passieren würde. Schreibt nichts.
+pub async fn preview_import(
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Form(form): Form<HistoryImportForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+
+    let history = form.parse()?;
+    let mut conn = database_pool.acquire().await?;
+    let preview = preview_history(&mut conn, &current_user.id, &history).await?;
+
+    let template = ImportPreviewTemplate {
+        preview,
+        unit: form.unit.clone().unwrap_or_else(|| "kg".to_string()),
+        csv: form.csv,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+/// Bestätigter Import: dieselbe Datei noch einmal lesen und in einer
+/// Transaktion schreiben.
+pub async fn import_history(
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Form(form): Form<HistoryImportForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+
+    let history = form.parse()?;
+    let mut tx = database_pool.begin().await?;
+    let summary = write_history(&mut tx, &current_user.id, &history).await?;
+    tx.commit().await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert("HX-Redirect", HeaderValue::from_static("/history"));
+    Ok((
+        headers,
+        Html(format!(
+            "Imported {} sessions with {} sets",
+            summary.sessions, summary.sets
+        )),
+    )
+        .into_response())
+}
+
+pub fn router() -> Router<SqlitePool> {
+    Router::new()
+        .route(
+            "/history/import/preview",
+            post(preview_import).layer(DefaultBodyLimit::max(MAX_IMPORT_BYTES)),
+        )
+        .route(
+            "/history/import",
+            post(import_history).layer(DefaultBodyLimit::max(MAX_IMPORT_BYTES)),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str, unit: WeightUnit) -> ImportedHistory {
+        let path = format!("{}/fixtures/import/{name}", env!("CARGO_MANIFEST_DIR"));
+        parse(&std::fs::read_to_string(&path).unwrap(), unit).unwrap()
+    }
+
+    async fn seed_user(pool: &SqlitePool) -> String {
+        sqlx::query!(
+            "INSERT INTO users (id, name, created_at) VALUES ('u-1', 'Anna', '2026-01-01T00:00:00Z')"
+        ).execute(pool).await.unwrap();
+        "u-1".to_string()
+    }
+
+    async fn write(pool: &SqlitePool, user_id: &str, history: &ImportedHistory) -> HistoryImportSummary {
+        let mut tx = pool.begin().await.unwrap();
+        let summary = write_history(&mut tx, user_id, history).await.unwrap();
+        tx.commit().await.unwrap();
+        summary
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn strong_import_writes_sessions_sets_and_records(pool: SqlitePool) {
+        let user_id = seed_user(&pool).await;
+        sqlx::query!(
+            "INSERT INTO exercises (id, name, instructions, video_url, created_at)
+             VALUES ('ex-bench', 'bench press (barbell)', 'Eigene Anleitung', NULL, '2026-01-01T00:00:00Z')"
+        ).execute(&pool).await.unwrap();
+
+        let summary = write(&pool, &user_id, &fixture("strong.csv", WeightUnit::Kg)).await;
+        assert_eq!(
+            summary,
+            HistoryImportSummary { sessions: 2, sets: 6, exercises_created: 2, already_imported: 0 }
+        );
+
+        let bench_sets = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "c!: i32" FROM completed_sets WHERE exercise_id = 'ex-bench'"#
+        ).fetch_one(&pool).await.unwrap();
+        assert_eq!(bench_sets, 4, "the existing exercise is matched by name");
+
+        let push = sqlx::query!(
+            r#"SELECT cw.started_at, cw.total_duration_minutes as "minutes: i32", cw.total_sets as "sets: i32",
+                      cw.total_volume_kg as "volume: f64", w.name, w.is_active as "is_active: bool"
+               FROM completed_workouts cw JOIN workouts w ON w.id = cw.workout_id
+               ORDER BY cw.started_at LIMIT 1"#
+        ).fetch_one(&pool).await.unwrap();
+        assert_eq!(push.started_at, "2024-03-04T18:02:11+00:00");
+        assert_eq!((push.minutes, push.sets), (65, 4));
+        assert_eq!(push.volume, 60.0 * 8.0 + 62.5 * 8.0 + 62.5 * 7.0);
+        assert_eq!(push.name, "Push Day");
+        assert_eq!(push.is_active, Some(false));
+
+        let max_weight = sqlx::query!(
+            r#"SELECT weight as "weight: f64", achieved_at FROM personal_records
+               WHERE user_id = ? AND exercise_id = 'ex-bench' AND record_type = 'max_weight'"#,
+            user_id
+        ).fetch_one(&pool).await.unwrap();
+        assert_eq!(max_weight.weight, Some(65.0));
+        assert!(max_weight.achieved_at.starts_with("2024-03-06"));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn importing_the_same_file_twice_adds_nothing(pool: SqlitePool) {
+        let user_id = seed_user(&pool).await;
+        let history = fixture("hevy_lbs.csv", WeightUnit::Kg);
+        write(&pool, &user_id, &history).await;
+
+        let mut conn = pool.acquire().await.unwrap();
+        let preview = preview_history(&mut conn, &user_id, &history).await.unwrap();
+        drop(conn);
+        assert_eq!(preview.already_imported, 2);
+
+        let second = write(&pool, &user_id, &history).await;
+        assert_eq!(second, HistoryImportSummary { already_imported: 2, ..Default::default() });
+
+        let sessions = sqlx::query_scalar!(r#"SELECT COUNT(*) as "c!: i32" FROM completed_workouts"#)
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!(sessions, 2);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn preview_lists_matches_and_writes_nothing(pool: SqlitePool) {
+        let user_id = seed_user(&pool).await;
+        sqlx::query!(
+            "INSERT INTO exercises (id, name, instructions, video_url, created_at)
+             VALUES ('ex-pull', 'Pull Up', 'x', NULL, '2026-01-01T00:00:00Z')"
+        ).execute(&pool).await.unwrap();
+
+        let history = fixture("fitnotes.csv", WeightUnit::Kg);
+        let mut conn = pool.acquire().await.unwrap();
+        let preview = preview_history(&mut conn, &user_id, &history).await.unwrap();
+        drop(conn);
+
+        assert_eq!(preview.source, "FitNotes");
+        assert_eq!((preview.sessions, preview.sets), (2, 4));
+        assert_eq!((preview.first_date.as_str(), preview.last_date.as_str()), ("2024-01-05", "2024-01-07"));
+        assert_eq!(preview.matched_exercises, vec!["Pull Up"]);
+        assert_eq!(preview.new_exercises, vec!["Barbell Squat", "Flat Barbell Bench Press"]);
+
+        let sessions = sqlx::query_scalar!(r#"SELECT COUNT(*) as "c!: i32" FROM completed_workouts"#)
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!(sessions, 0);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn preview_names_the_plans_sessions_will_join_or_create(pool: SqlitePool) {
+        let user_id = seed_user(&pool).await;
+        sqlx::query!(
+            "INSERT INTO workouts (id, user_id, name, description, is_active, schedule_type, schedule_day, created_at, updated_at)
+             VALUES ('w-1', 'u-1', 'push day', NULL, 1, 'manual', NULL, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')"
+        ).execute(&pool).await.unwrap();
+
+        let history = fixture("strong.csv", WeightUnit::Kg);
+        let mut conn = pool.acquire().await.unwrap();
+        let preview = preview_history(&mut conn, &user_id, &history).await.unwrap();
+        drop(conn);
+        assert_eq!(preview.matched_workouts, vec!["Push Day"]);
+        assert_eq!(preview.new_workouts, vec!["Pull Day"]);
+
+        write(&pool, &user_id, &history).await;
+        let workouts = sqlx::query_scalar!(r#"SELECT name as "name!" FROM workouts ORDER BY name"#)
+            .fetch_all(&pool).await.unwrap();
+        assert_eq!(workouts, vec!["Pull Day", "push day"]);
+    }
+}
@@ -0,0 +1,272 @@
+//! Live-Ansicht für einen zweiten Bildschirm: Server-Sent Events einer
+//! laufenden Sitzung (siehe [`crate::live_events`]) und eine Seite nur zum
+//! Zuschauen, die sich daraus aktualisiert. Alle drei Routen verlangen die
+//! Anmeldung des Trainierenden; die Sitzungs-ID allein öffnet nichts.
+
+use crate::error::AppError;
+use crate::handlers::get_current_user;
+use crate::handlers::live_training::load_session;
+use crate::live_events;
+use crate::models::{
+    ActiveWorkout, CompletedSetDetail, FREESTYLE_NAME, User, block_display, progress_percent,
+};
+use crate::units::WeightUnit;
+use askama::Template;
+use axum::{
+    Router,
+    extract::{Path, State},
+    http::{HeaderMap, HeaderValue},
+    response::{
+        Html, IntoResponse, Redirect,
+        sse::{Event, KeepAlive, Sse},
+    },
+    routing::get,
+};
+use futures_util::Stream;
+use sqlx::SqlitePool;
+use std::convert::Infallible;
+use tokio::sync::broadcast::error::RecvError;
+use tower_sessions::Session;
+
+/// So viele Sätze zeigt die Zuschaueransicht, neueste zuerst.
+const RECENT_SETS: i64 = 6;
+
+/// Stand der Sitzung für die Zuschaueransicht.
+pub struct WatchStatus {
+    pub active_workout: ActiveWorkout,
+    pub workout_name: String,
+    pub current_exercise: Option<String>,
+    pub block_display: Option<String>,
+    pub progress_percent: f32,
+    pub total_sets: i32,
+    pub recent_sets: Vec<CompletedSetDetail>,
+    pub unit: WeightUnit,
+}
+
+#[derive(Template)]
+#[template(path = "live_training/watch.html")]
+pub struct WatchTemplate {
+    pub status: WatchStatus,
+    pub current_user: Option<User>,
+    pub is_dashboard: bool,
+}
+
+#[derive(Template)]
+#[template(path = "live_training/watch_status.html")]
+pub struct WatchStatusTemplate {
+    pub status: WatchStatus,
+}
+
+/// `None`, wenn `user` keine solche Sitzung (mehr) laufen hat.
+async fn load_watch_status(
+    database_pool: &SqlitePool,
+    active_workout_id: &str,
+    user: &User,
+) -> Result<Option<WatchStatus>, AppError> {
+    let Some(active_workout) = sqlx::query_as!(
+        ActiveWorkout,
+        "SELECT * FROM active_workouts WHERE id = ? AND user_id = ?",
+        active_workout_id,
+        user.id
+    )
+    .fetch_optional(database_pool)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let workout_name = match &active_workout.workout_id {
+        Some(workout_id) => sqlx::query_scalar!("SELECT name FROM workouts WHERE id = ?", workout_id)
+            .fetch_optional(database_pool)
+            .await?
+            .unwrap_or_default(),
+        None => FREESTYLE_NAME.to_string(),
+    };
+    let unit = user.unit();
+
+    let state = load_session(database_pool, active_workout_id, active_workout.workout_id.as_deref()).await?;
+    let recent_sets = sqlx::query_as!(
+        CompletedSetDetail,
+        r#"SELECT
+            cs.id,
+            cs.set_number as "set_number: i32",
+            cs.weight as "weight: f32",
+            cs.reps as "reps: i32",
+            cs.notes,
+            cs.rpe as "rpe: f64",
+            cs.set_type,
+            cs.completed_at,
+            e.name as exercise_name,
+            e.instructions as exercise_instructions
+           FROM completed_sets cs
+           INNER JOIN exercises e ON cs.exercise_id = e.id
+           WHERE cs.active_workout_id = ?
+           ORDER BY cs.completed_at DESC
+           LIMIT ?"#,
+        active_workout_id,
+        RECENT_SETS
+    )
+    .fetch_all(database_pool)
+    .await?;
+
+    Ok(Some(WatchStatus {
+        workout_name,
+        current_exercise: state.current.map(|index| state.exercises[index].exercise_name.clone()),
+        block_display: state.current.and_then(|index| block_display(&state.exercises, index)),
+        progress_percent: progress_percent(&state.exercises, &state.done),
+        total_sets: state.done.iter().sum(),
+        recent_sets,
+        unit,
+        active_workout,
+    }))
+}
+
+/// Zuschaueransicht. Eine beendete Sitzung leitet zu ihrem Ergebnis.
+pub async fn watch_training(
+    Path(active_workout_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(current_user) = get_current_user(&session, &database_pool).await? else {
+        return Err(AppError::Unauthorized);
+    };
+    let Some(status) = load_watch_status(&database_pool, &active_workout_id, &current_user).await? else {
+        let finished = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM completed_workouts WHERE id = ? AND user_id = ?",
+            active_workout_id,
+            current_user.id
+        )
+        .fetch_one(&database_pool)
+        .await?;
+        if finished == 0 {
+            return Err(AppError::NotFound("Active workout not found".to_string()));
+        }
+        return Ok(Redirect::to(&format!("/history/{active_workout_id}")).into_response());
+    };
+
+    let template = WatchTemplate {
+        status,
+        current_user: Some(current_user),
+        is_dashboard: false,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+/// Nur der wechselnde Teil der Zuschaueransicht, nach jedem Ereignis neu.
+pub async fn watch_status(
+    Path(active_workout_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(current_user) = get_current_user(&session, &database_pool).await? else {
+        return Err(AppError::Unauthorized);
+    };
+    let Some(status) = load_watch_status(&database_pool, &active_workout_id, &current_user).await? else {
+        // Inzwischen beendet: die Seite soll zum Ergebnis.
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "HX-Redirect",
+            HeaderValue::from_str(&format!("/live-training/{active_workout_id}/watch"))?,
+        );
+        return Ok((headers, Html("Training finished".to_string())).into_response());
+    };
+    Ok(Html(WatchStatusTemplate { status }.render()?).into_response())
+}
+
+/// Server-Sent Events der Sitzung: `set-completed`, `exercise-changed`,
+/// `rest-started` und zum Schluss `finished`, Daten als JSON. Nach
+/// `finished` endet der Stream.
+pub async fn training_events(
+    Path(active_workout_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let Some(current_user) = get_current_user(&session, &database_pool).await? else {
+        return Err(AppError::Unauthorized);
+    };
+    // Erst zuhören, dann prüfen: Ein `finished` dazwischen ginge sonst
+    // verloren und der Stream bliebe ewig offen.
+    let receiver = live_events::subscribe(&active_workout_id);
+    let running = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM active_workouts WHERE id = ? AND user_id = ?",
+        active_workout_id,
+        current_user.id
+    )
+    .fetch_one(&database_pool)
+    .await?;
+    if running == 0 {
+        return Err(AppError::NotFound("Active workout not found".to_string()));
+    }
+
+    let stream = futures_util::stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    let sse = Event::default().event(event.name).data(event.data.to_string());
+                    return Some((Ok(sse), receiver));
+                }
+                // Verpasstes holt die Ansicht mit dem nächsten Ereignis nach.
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+pub fn router() -> Router<SqlitePool> {
+    Router::new()
+        .route("/live-training/{id}/events", get(training_events))
+        .route("/live-training/{id}/watch", get(watch_training))
+        .route("/live-training/{id}/watch/status", get(watch_status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> Session {
+        Session::new(None, std::sync::Arc::new(tower_sessions::MemoryStore::default()), None)
+    }
+
+    async fn logged_in(user_id: &str) -> Session {
+        let session = session();
+        session.insert("current_user_id", user_id).await.unwrap();
+        session
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn only_the_owner_can_follow_a_session(pool: SqlitePool) {
+        sqlx::query!(
+            "INSERT INTO users (id, name, created_at) VALUES
+             ('u-1', 'Anna', '2026-01-01T00:00:00Z'), ('u-2', 'Ben', '2026-01-01T00:00:00Z')"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query!(
+            "INSERT INTO active_workouts (id, user_id, workout_id, started_at, created_at)
+             VALUES ('a-1', 'u-1', NULL, '2026-03-01T18:00:00Z', '2026-03-01T18:00:00Z')"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let watch = |session: Session| watch_training(Path("a-1".to_string()), State(pool.clone()), session);
+        assert!(matches!(watch(session()).await, Err(AppError::Unauthorized)));
+        assert!(matches!(watch(logged_in("u-2").await).await, Err(AppError::NotFound(_))));
+        assert!(watch(logged_in("u-1").await).await.is_ok());
+
+        let status = |session: Session| watch_status(Path("a-1".to_string()), State(pool.clone()), session);
+        assert!(matches!(status(session()).await, Err(AppError::Unauthorized)));
+        let stranger = status(logged_in("u-2").await).await.unwrap().into_response();
+        assert!(stranger.headers().contains_key("HX-Redirect"));
+        let owner = status(logged_in("u-1").await).await.unwrap().into_response();
+        assert!(!owner.headers().contains_key("HX-Redirect"));
+
+        let events = |session: Session| training_events(Path("a-1".to_string()), State(pool.clone()), session);
+        assert!(matches!(events(session()).await, Err(AppError::Unauthorized)));
+        assert!(matches!(events(logged_in("u-2").await).await, Err(AppError::NotFound(_))));
+        assert!(events(logged_in("u-1").await).await.is_ok());
+    }
+}
@@ -1,10 +1,22 @@
 use crate::error::AppError;
-use crate::handlers::personal_records::check_and_update_prs;
+use crate::handlers::get_current_user;
+use crate::handlers::equipment::load_equipment;
+use crate::handlers::personal_records::{check_and_update_prs, recompute_prs};
+use crate::handlers::progression::{load_target, pending_changes, update_targets};
+use crate::live_events::{self, LiveEvent};
 use crate::models::{
-    ActiveWorkout, ActiveWorkoutView, CompleteSetForm, CompletedSet, CompletedSetDetail,
-    CompletedWorkout, FinishTrainingForm, StartWorkoutForm, User, Workout, WorkoutExerciseDetail,
+    FREESTYLE_NAME,
+    ActiveWorkout, ActiveWorkoutView, AddSessionExerciseForm, CompleteSetForm, CompletedSet, CompletedSetDetail,
+    CompletedWorkout, Exercise, FinishTrainingForm, SessionExerciseForm, SessionSlot, SetType,
+    StartWorkoutForm, SubstituteExerciseForm, Substitution, User, Workout, WorkoutExerciseDetail,
+    block_display, move_block, next_exercise, parse_logged_at, parse_effort, parse_timestamp, progress_percent, rest_after_set,
 };
+use crate::plates::{Equipment, Loading};
+use crate::progression::{Target, WarmupStep, warmup_ramp};
+use crate::units::WeightUnit;
+use crate::webhooks::{self, WebhookEvent};
 use askama::Template;
+use chrono::{DateTime, Utc};
 use axum::{
     Form, Router,
     extract::{Path, State},
@@ -12,7 +24,8 @@ use axum::{
     response::{Html, IntoResponse},
     routing::{get, post},
 };
-use sqlx::SqlitePool;
+use serde_json::json;
+use sqlx::{SqliteConnection, SqlitePool};
 use tower_sessions::Session;
 
 #[derive(Template)]
@@ -23,120 +36,142 @@ pub struct LiveTrainingTemplate {
     pub current_user: Option<User>,
     pub is_dashboard: bool,
     pub pr_notifications: Option<Vec<String>>,
-    pub overload_suggestion: Option<String>,
+    /// Ziel der Progressions-Engine für die aktuelle Übung.
+    pub target: Option<Target>,
+    /// Vorbelegung des Gewichtsfelds: Ziel der Engine, sonst der Plan.
+    pub suggested_weight: Option<f32>,
+    /// Scheiben pro Seite für das vorgeschlagene Gewicht.
+    pub plate_loading: Option<Loading>,
+    /// Noch offene Aufwärmsätze vor dem ersten Arbeitssatz.
+    pub warmup_ramp: Vec<WarmupStep>,
+    /// Im Supersatz oder Zirkel erst nach der Runde Pause, siehe
+    /// `rest_after_set`.
+    pub rest_after_set: bool,
+    pub block_display: Option<String>,
+    /// Übungen der Sitzung in aktueller Reihenfolge, mit Ersetzungen.
+    pub session_plan: Vec<SessionSlot>,
+    /// Auswahl fürs Ersetzen und spontane Hinzufügen von Übungen.
+    pub available_exercises: Vec<Exercise>,
+    pub unit: WeightUnit,
 }
 
-async fn get_current_user(session: &Session, database_pool: &SqlitePool) -> Result<Option<User>, AppError> {
-    if let Ok(Some(user_id)) = session.get::<String>("current_user_id").await {
-        let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = ?", user_id)
-            .fetch_optional(database_pool)
-            .await?;
-        Ok(user)
-    } else {
-        Ok(None)
-    }
+/// Plan in die Sitzung kopieren. Mehrfach aufrufbar: bestehende Zeilen
+/// bleiben, wie sie sind; was während der Sitzung im Plan dazukam, landet
+/// hinten. Sitzungen von vor der Tabelle holen so ihre Zeilen nach. Freies
+/// Training hat keinen Plan und kopiert nichts.
+async fn snapshot_plan(
+    conn: &mut SqliteConnection,
+    session_id: &str,
+    workout_id: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT OR IGNORE INTO session_exercises (session_id, position, planned_exercise_id, exercise_id, sort_order)
+         SELECT ?, position, exercise_id, exercise_id,
+                position + COALESCE((SELECT MAX(sort_order) FROM session_exercises WHERE session_id = ?), 0)
+         FROM workout_exercises WHERE workout_id = ?",
+        session_id,
+        session_id,
+        workout_id
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
 }
 
-async fn determine_current_exercise(
-    database_pool: &SqlitePool,
-    active_workout_id: &str,
-    workout_id: &str,
-) -> Result<Option<WorkoutExerciseDetail>, AppError> {
-    let workout_exercise = sqlx::query_as!(
+/// Übungen der Sitzung in ihrer aktuellen Reihenfolge. Vorgaben kommen aus
+/// dem Plan plus Zusatzsätzen, Name und Anleitung von der tatsächlich
+/// trainierten Übung. Spontane Übungen (negative Position) haben nur ihre
+/// Zusatzsätze.
+async fn session_exercises(
+    conn: &mut SqliteConnection,
+    session_id: &str,
+    workout_id: Option<&str>,
+) -> Result<Vec<WorkoutExerciseDetail>, sqlx::Error> {
+    snapshot_plan(conn, session_id, workout_id).await?;
+    sqlx::query_as!(
         WorkoutExerciseDetail,
         r#"SELECT
-            we.position as "position: i32",
-            we.target_sets as "target_sets: i32",
+            se.position as "position!: i32",
+            COALESCE(we.target_sets, 0) + se.extra_sets as "target_sets!: i32",
             we.target_weight as "target_weight: f32",
+            we.target_reps_min as "target_reps_min: i32",
+            we.target_reps_max as "target_reps_max: i32",
+            we.rest_seconds as "rest_seconds: i32",
             we.notes,
+            COALESCE(we.progression, 'double') as "progression!: String",
+            we.group_label,
             e.id as exercise_id,
             e.name as exercise_name,
             e.instructions as exercise_instructions,
             e.video_url as exercise_video_url
-           FROM workout_exercises we
-           INNER JOIN exercises e ON we.exercise_id = e.id
-           WHERE we.workout_id = ?
-           ORDER BY we.position ASC"#,
-        workout_id
+           FROM session_exercises se
+           LEFT JOIN workout_exercises we ON we.workout_id = ? AND we.position = se.position
+           INNER JOIN exercises e ON se.exercise_id = e.id
+           WHERE se.session_id = ? AND (se.position < 0 OR we.id IS NOT NULL)
+           ORDER BY se.sort_order ASC, se.position ASC"#,
+        workout_id,
+        session_id
     )
-    .fetch_all(database_pool)
-    .await?;
+    .fetch_all(&mut *conn)
+    .await
+}
 
-    for exercise in workout_exercise {
-        let completed_sets_count = sqlx::query_scalar!(
-            "SELECT COUNT(*) FROM completed_sets WHERE active_workout_id = ? AND exercise_id = ?",
-            active_workout_id,
-            exercise.exercise_id
-        )
-        .fetch_one(database_pool)
-        .await
-        .unwrap_or(0) as i32;
+/// Ersetzte Übungen einer Sitzung, laufend oder abgeschlossen.
+pub async fn load_substitutions(
+    pool: &SqlitePool,
+    session_id: &str,
+) -> Result<Vec<Substitution>, sqlx::Error> {
+    sqlx::query_as!(
+        Substitution,
+        r#"SELECT se.position as "position: i32", planned.name as planned_name, used.name as exercise_name
+           FROM session_exercises se
+           INNER JOIN exercises planned ON planned.id = se.planned_exercise_id
+           INNER JOIN exercises used ON used.id = se.exercise_id
+           WHERE se.session_id = ? AND se.exercise_id != se.planned_exercise_id
+           ORDER BY se.position"#,
+        session_id
+    )
+    .fetch_all(pool)
+    .await
+}
 
-        if completed_sets_count < exercise.target_sets {
-            return Ok(Some(exercise));
-        }
-    }
-    Ok(None)
+/// Stand der Sitzung: Übungen in Reihenfolge, erledigte Arbeitssätze je
+/// Übung und die aktuelle Übung.
+pub(crate) struct SessionState {
+    pub exercises: Vec<WorkoutExerciseDetail>,
+    pub done: Vec<i32>,
+    pub current: Option<usize>,
 }
 
-async fn calculate_progress_percent(
+pub(crate) async fn load_session(
     database_pool: &SqlitePool,
     active_workout_id: &str,
-    workout_id: &str,
-) -> Result<f32, AppError> {
-    let total_planned_sets = sqlx::query_scalar!(
-        "SELECT COALESCE(SUM(target_sets), 0) FROM workout_exercises WHERE workout_id = ?",
-        workout_id
-    )
-    .fetch_one(database_pool)
-    .await? as f32;
+    workout_id: Option<&str>,
+) -> Result<SessionState, AppError> {
+    let mut conn = database_pool.acquire().await?;
+    let exercises = session_exercises(&mut conn, active_workout_id, workout_id).await?;
+    drop(conn);
 
-    if total_planned_sets == 0.0 {
-        return Ok(0.0);
-    }
-
-    let completed_sets_count = sqlx::query_scalar!(
-        "SELECT COUNT(*) FROM completed_sets WHERE active_workout_id = ?",
+    let counts = sqlx::query!(
+        r#"SELECT exercise_id, COUNT(*) as "sets!: i32" FROM completed_sets
+           WHERE active_workout_id = ? AND set_type != 'warmup'
+           GROUP BY exercise_id"#,
         active_workout_id
     )
-    .fetch_one(database_pool)
-    .await? as f32;
-
-    Ok((completed_sets_count / total_planned_sets * 100.0).min(100.0))
-}
-
-/// Generate progressive overload suggestion based on completed set
-fn generate_overload_suggestion(completed_set: &CompletedSet, _current_weight: Option<f32>) -> Option<String> {
-    // Only provide suggestions for weighted exercises
-    let weight = match completed_set.weight {
-        Some(w) => w,
-        None => return None, // No suggestions for bodyweight exercises
-    };
-
-    if completed_set.reps > 12 {
-        // User is doing too many reps - suggest increasing weight
-        let suggested_weight = weight + 2.5; // Standard increment of 2.5kg
-        Some(format!(
-            "💪 Du schaffst mehr als 12 Wiederholungen! Versuche es beim nächsten Set mit {}kg für optimales Muskelwachstum.",
-            suggested_weight
-        ))
-    } else if completed_set.reps < 6 {
-        // User is struggling - suggest decreasing weight
-        let suggested_weight = (weight - 2.5).max(0.0); // Don't go below 0
-        if suggested_weight > 0.0 {
-            Some(format!(
-                "⚠️ Weniger als 6 Wiederholungen können auf zu hohes Gewicht hindeuten. Versuche es mit {}kg für bessere Form und Kontrolle.",
-                suggested_weight
-            ))
-        } else {
-            Some(
-                "⚠️ Weniger als 6 Wiederholungen - versuche mit weniger Gewicht oder Bodyweight zu trainieren für bessere Form.".to_string()
-            )
-        }
-    } else {
-        // Reps are in the optimal range (6-12), no suggestion needed
-        None
-    }
+    .fetch_all(database_pool)
+    .await?;
+    let done: Vec<i32> = exercises
+        .iter()
+        .map(|exercise| {
+            counts
+                .iter()
+                .find(|c| c.exercise_id == exercise.exercise_id)
+                .map_or(0, |c| c.sets)
+        })
+        .collect();
+
+    let current = next_exercise(&exercises, &done);
+    Ok(SessionState { exercises, done, current })
 }
 
 pub async fn start_training(
@@ -158,33 +193,47 @@ pub async fn start_training(
         }
     };
 
-    // Verify workout exists
-    sqlx::query_as!(
-        Workout,
-        "SELECT id, user_id, name, description, is_active, schedule_type as \"schedule_type!: String\", schedule_day as \"schedule_day: i32\", created_at, updated_at FROM workouts WHERE id = ?",
-        form.workout_id
-    ).fetch_optional(&database_pool).await?
-        .ok_or_else(|| AppError::NotFound(format!("Workout '{}' not found", form.workout_id)))?;
+    let (active, started) =
+        begin_session(&database_pool, &current_user.id, Some(form.workout_id)).await?;
+    if !started {
+        return back_to_training(&active.id, "Redirecting to active training");
+    }
+    back_to_training(&active.id, "Training started!")
+}
+
+/// Laufende Sitzung des Nutzers oder eine neue — nach Plan oder frei
+/// (`workout_id` `None`). Das `bool` sagt, ob sie eben erst begonnen hat.
+pub(crate) async fn begin_session(
+    database_pool: &SqlitePool,
+    user_id: &str,
+    workout_id: Option<String>,
+) -> Result<(ActiveWorkout, bool), AppError> {
+    if let Some(workout_id) = workout_id.as_deref() {
+        let owned = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM workouts WHERE id = ? AND user_id = ?",
+            workout_id,
+            user_id
+        )
+        .fetch_one(database_pool)
+        .await?;
+        if owned == 0 {
+            return Err(AppError::NotFound(format!("Workout '{workout_id}' not found")));
+        }
+    }
 
     let existing_active = sqlx::query_as!(
         ActiveWorkout,
         "SELECT * from active_workouts WHERE user_id = ? LIMIT 1",
-        current_user.id
+        user_id
     )
-    .fetch_optional(&database_pool)
+    .fetch_optional(database_pool)
     .await?;
-
     if let Some(active) = existing_active {
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            "HX-Redirect",
-            HeaderValue::from_str(&format!("/live-training/{}", active.id))?,
-        );
-        return Ok((headers, Html("Redirecting to active training".to_string())).into_response());
+        return Ok((active, false));
     }
 
-    let new_active = ActiveWorkout::new(current_user.id, form.workout_id);
-
+    let new_active = ActiveWorkout::new(user_id.to_string(), workout_id);
+    let mut tx = database_pool.begin().await?;
     sqlx::query!(
         "INSERT INTO active_workouts (id, user_id, workout_id, started_at, created_at) VALUES (?, ?, ?, ?, ?)",
         new_active.id,
@@ -192,14 +241,31 @@ pub async fn start_training(
         new_active.workout_id,
         new_active.started_at,
         new_active.created_at
-    ).execute(&database_pool).await?;
+    ).execute(&mut *tx).await?;
+    snapshot_plan(&mut tx, &new_active.id, new_active.workout_id.as_deref()).await?;
+    tx.commit().await?;
+    Ok((new_active, true))
+}
 
-    let mut headers = HeaderMap::new();
-    headers.insert(
-        "HX-Redirect",
-        HeaderValue::from_str(&format!("/live-training/{}", new_active.id))?,
-    );
-    Ok((headers, Html("Training started!".to_string())).into_response())
+/// Training ohne Vorlage: startet leer, Übungen kommen unterwegs dazu.
+pub async fn start_freestyle(
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => {
+            let mut headers = HeaderMap::new();
+            headers.insert("HX-Redirect", HeaderValue::from_static("/users"));
+            return Ok((headers, Html("Not logged in".to_string())).into_response());
+        }
+    };
+
+    let (active, started) = begin_session(&database_pool, &current_user.id, None).await?;
+    if !started {
+        return back_to_training(&active.id, "Redirecting to active training");
+    }
+    back_to_training(&active.id, "Training started!")
 }
 
 pub async fn show_live_training(
@@ -207,45 +273,65 @@ pub async fn show_live_training(
     State(database_pool): State<SqlitePool>,
     session: Session,
 ) -> Result<impl IntoResponse, AppError> {
-    let current_user = get_current_user(&session, &database_pool).await?;
-
-    let active_workout = sqlx::query_as!(
-        ActiveWorkout,
-        "SELECT * FROM active_workouts WHERE id = ?",
-        active_workout_id
-    )
-    .fetch_optional(&database_pool)
-    .await?;
-
-    let active_workout = match active_workout {
-        Some(workout) => workout,
-        None => return Err(AppError::NotFound("Active workout not found".to_string())),
+    let Some(current_user) = get_current_user(&session, &database_pool).await? else {
+        return Err(AppError::Unauthorized);
     };
+    let active_workout = find_active_workout(&database_pool, &active_workout_id, &current_user.id).await?;
+    let unit = current_user.unit();
+    let equipment = load_equipment(&database_pool, &current_user).await?;
 
-    let workout = sqlx::query_as!(
-        Workout,
-        r#"SELECT
-            id, user_id, name, description, is_active, schedule_type as "schedule_type!: String",
-            schedule_day as "schedule_day: i32", created_at, updated_at FROM workouts WHERE id = ?"#,
-        active_workout.workout_id
-    ).fetch_one(&database_pool).await?;
+    // Freies Training hat keinen Plan und damit keinen Namen.
+    let workout_name = match &active_workout.workout_id {
+        Some(workout_id) => {
+            sqlx::query_as!(
+                Workout,
+                r#"SELECT
+                    id, user_id, name, description, is_active, schedule_type as "schedule_type!: String",
+                    schedule_day as "schedule_day: i32", created_at, updated_at FROM workouts WHERE id = ?"#,
+                workout_id
+            ).fetch_one(&database_pool).await?.name
+        }
+        None => FREESTYLE_NAME.to_string(),
+    };
 
-    let current_exercise = determine_current_exercise(
+    let state = load_session(
         &database_pool,
         &active_workout_id,
-        &active_workout.workout_id,
+        active_workout.workout_id.as_deref(),
     )
     .await?;
+    let rest_after_set = state
+        .current
+        .is_some_and(|index| rest_after_set(&state.exercises, &state.done, index));
+    let block_display = state.current.and_then(|index| block_display(&state.exercises, index));
+    let substitutions = load_substitutions(&database_pool, &active_workout_id).await?;
+    let session_plan: Vec<SessionSlot> = state
+        .exercises
+        .iter()
+        .zip(&state.done)
+        .enumerate()
+        .map(|(index, (exercise, done))| SessionSlot {
+            position: exercise.position,
+            name: exercise.exercise_name.clone(),
+            done: *done,
+            target_sets: exercise.target_sets,
+            planned_name: substitutions
+                .iter()
+                .find(|s| s.position == exercise.position)
+                .map(|s| s.planned_name.clone()),
+            is_ad_hoc: exercise.position < 0,
+            is_current: state.current == Some(index),
+        })
+        .collect();
+    let current_exercise = state.current.map(|index| state.exercises[index].clone());
+    let available_exercises = sqlx::query_as!(Exercise, "SELECT id, name, instructions, video_url, created_at FROM exercises ORDER BY name")
+        .fetch_all(&database_pool)
+        .await?;
 
-    let progress_percent = calculate_progress_percent(
-        &database_pool,
-        &active_workout_id,
-        &active_workout.workout_id,
-    )
-    .await?;
+    let progress_percent = progress_percent(&state.exercises, &state.done);
 
     let total_sets_completed = sqlx::query_scalar!(
-        "SELECT COUNT(*) FROM completed_sets WHERE active_workout_id = ?",
+        "SELECT COUNT(*) FROM completed_sets WHERE active_workout_id = ? AND set_type != 'warmup'",
         active_workout_id
     )
     .fetch_one(&database_pool)
@@ -261,6 +347,8 @@ pub async fn show_live_training(
                 cs.weight as "weight: f32",
                 cs.reps as "reps: i32",
                 cs.notes,
+                cs.rpe as "rpe: f64",
+                cs.set_type,
                 cs.completed_at,
                 e.name as exercise_name,
                 e.instructions as exercise_instructions
@@ -277,9 +365,37 @@ pub async fn show_live_training(
         Vec::new()
     };
 
+    let target = match (&active_workout.workout_id, &current_exercise) {
+        (Some(workout_id), Some(exercise)) => {
+            load_target(&database_pool, workout_id, exercise.position, &exercise.exercise_id).await?
+        }
+        _ => None,
+    };
+    let suggested_weight = match &target {
+        Some(target) => target.weight_kg.map(|kg| kg as f32),
+        None => current_exercise.as_ref().and_then(|e| e.target_weight),
+    };
+    let plate_loading = suggested_weight.and_then(|kg| equipment.barbell(kg as f64));
+
+    // Rampe nur an der Stange und nur bis zum ersten Arbeitssatz; schon
+    // geloggte Aufwärmsätze haken die ersten Stufen ab.
+    let warmup_ramp = match (suggested_weight, &plate_loading) {
+        (Some(kg), Some(_))
+            if current_user.warmup_ramps
+                && current_exercise_sets.iter().all(|s| s.set_type().is_warmup()) =>
+        {
+            let empty_bar_kg = equipment.unit.to_kg(equipment.empty_bar());
+            warmup_ramp(kg as f64, empty_bar_kg, |kg| equipment.nearest_loadable_kg(kg))
+                .into_iter()
+                .skip(current_exercise_sets.len())
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+
     let active_workout_view = ActiveWorkoutView {
         active_workout,
-        workout_name: workout.name,
+        workout_name,
         total_sets_completed,
         current_exercise,
         progress_percent,
@@ -291,30 +407,100 @@ pub async fn show_live_training(
         let _ = session.remove::<Vec<String>>("pr_notifications").await;
     }
 
-    // Retrieve and clear overload suggestion from session
-    let overload_suggestion: Option<String> = session.get("overload_suggestion").await.ok().flatten();
-    if overload_suggestion.is_some() {
-        let _ = session.remove::<String>("overload_suggestion").await;
-    }
-
     let template = LiveTrainingTemplate {
         active_workout_view,
         current_exercise_sets,
-        current_user,
+        current_user: Some(current_user),
         is_dashboard: false,
         pr_notifications,
-        overload_suggestion,
+        target,
+        suggested_weight,
+        plate_loading,
+        warmup_ramp,
+        rest_after_set,
+        block_display,
+        session_plan,
+        available_exercises,
+        unit,
     };
 
     Ok(Html(template.render()?).into_response())
 }
 
+/// Zeile der Sitzung, zu der ein neuer Satz von `exercise_id` gehört: die
+/// erste in Sitzungsreihenfolge, deren Arbeitssätze noch nicht voll sind,
+/// sonst die letzte. Steht die Übung nur einmal in der Sitzung, ist es die.
+async fn set_position(
+    conn: &mut SqliteConnection,
+    active_workout: &ActiveWorkout,
+    exercise_id: &str,
+) -> Result<Option<i32>, sqlx::Error> {
+    let entries: Vec<_> = session_exercises(conn, &active_workout.id, active_workout.workout_id.as_deref())
+        .await?
+        .into_iter()
+        .filter(|exercise| exercise.exercise_id == exercise_id)
+        .collect();
+    let counts = sqlx::query!(
+        r#"SELECT position as "position: i32", COUNT(*) as "sets!: i32" FROM completed_sets
+           WHERE active_workout_id = ? AND exercise_id = ? AND set_type != 'warmup'
+           GROUP BY position"#,
+        active_workout.id,
+        exercise_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    let done = |position: i32| {
+        counts
+            .iter()
+            .find(|c| c.position == Some(position))
+            .map_or(0, |c| c.sets)
+    };
+    Ok(entries
+        .iter()
+        .find(|exercise| done(exercise.position) < exercise.target_sets)
+        .or(entries.last())
+        .map(|exercise| exercise.position))
+}
+
+/// Ergebnis von [`log_set`].
+pub(crate) enum LoggedSet {
+    /// Neu gespeichert, mit den dabei erreichten Rekorden (Anzeigetexte).
+    Logged { set: Box<CompletedSet>, records: Vec<String> },
+    /// Derselbe `client_request_id` war schon da; nichts geändert.
+    Duplicate,
+}
+
 pub async fn complete_set(
     Path(active_workout_id): Path<String>,
     State(database_pool): State<SqlitePool>,
     session: Session,
     Form(form): Form<CompleteSetForm>,
 ) -> Result<impl IntoResponse, AppError> {
+    let Some(current_user) = get_current_user(&session, &database_pool).await? else {
+        return Err(AppError::Unauthorized);
+    };
+    find_active_workout(&database_pool, &active_workout_id, &current_user.id).await?;
+    match log_set(&database_pool, &active_workout_id, form, current_user.unit()).await? {
+        LoggedSet::Duplicate => back_to_training(&active_workout_id, "Set already logged"),
+        LoggedSet::Logged { records, .. } => {
+            if !records.is_empty() {
+                // Store PR notifications in session for display
+                let _ = session.insert("pr_notifications", records).await;
+            }
+            back_to_training(&active_workout_id, "Set completed")
+        }
+    }
+}
+
+/// Satz speichern — gemeinsam für das Formular und die JSON-API. Das
+/// Gewicht kommt in `input_unit` (Formular: Einheit des Nutzers, API: kg)
+/// und wird in kg gespeichert.
+pub(crate) async fn log_set(
+    database_pool: &SqlitePool,
+    active_workout_id: &str,
+    form: CompleteSetForm,
+    input_unit: WeightUnit,
+) -> Result<LoggedSet, AppError> {
     // Validate input
     if form.exercise_id.is_empty() {
         return Err(AppError::BadRequest("Exercise ID cannot be empty".to_string()));
@@ -322,9 +508,35 @@ pub async fn complete_set(
     if form.reps < 1 || form.reps > 100 {
         return Err(AppError::BadRequest("Reps must be between 1 and 100".to_string()));
     }
-    if let Some(weight) = form.weight {
-        if weight < 0.0 || weight > 10000.0 {
-            return Err(AppError::BadRequest("Weight must be between 0 and 10000 kg".to_string()));
+    let rpe = parse_effort(
+        form.effort.as_deref().unwrap_or(""),
+        form.effort_scale.as_deref().unwrap_or(""),
+    )
+    .map_err(AppError::BadRequest)?;
+    let logged_at = parse_logged_at(form.logged_at.as_deref(), Utc::now()).map_err(AppError::BadRequest)?;
+
+    // Nachgereichter Satz, der schon angekommen ist: nichts tun, aber wie
+    // beim ersten Mal antworten, damit der Client ihn aus der Queue nimmt.
+    let client_request_id = form.client_request_id.as_deref().map(str::trim).filter(|id| !id.is_empty());
+    if client_request_id.is_some_and(|id| id.len() > 100) {
+        return Err(AppError::BadRequest("Client request ID is too long".to_string()));
+    }
+
+    // Prüfen, Nummer vergeben, Einfügen und Rekorde in einem Zug. IMMEDIATE
+    // nimmt die Schreibsperre gleich zu Beginn: Ein zweiter Request wartet,
+    // statt dieselbe Satznummer zu lesen oder beim späteren Schreiben mit
+    // SQLITE_BUSY abzubrechen.
+    let mut tx = database_pool.begin_with("BEGIN IMMEDIATE").await?;
+    if let Some(client_request_id) = client_request_id {
+        let known = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM completed_sets WHERE active_workout_id = ? AND client_request_id = ?",
+            active_workout_id,
+            client_request_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if known > 0 {
+            return Ok(LoggedSet::Duplicate);
         }
     }
 
@@ -334,7 +546,7 @@ pub async fn complete_set(
         "SELECT * FROM active_workouts WHERE id = ?",
         active_workout_id
     )
-    .fetch_optional(&database_pool)
+    .fetch_optional(&mut *tx)
     .await?;
 
     let active_workout = match active_workout {
@@ -342,31 +554,73 @@ pub async fn complete_set(
         None => return Err(AppError::NotFound("Active workout not found".to_string())),
     };
 
+    // Nur Übungen der Sitzung; die API nimmt sonst jede ID an.
+    snapshot_plan(&mut tx, active_workout_id, active_workout.workout_id.as_deref()).await?;
+    let in_session = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM session_exercises WHERE session_id = ? AND exercise_id = ?",
+        active_workout_id,
+        form.exercise_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    if in_session == 0 {
+        return Err(AppError::BadRequest("Exercise is not part of this session".to_string()));
+    }
+
+    // Ein Satz beendet die Pause.
+    resume_session(&mut tx, &active_workout).await?;
+
+    // Gespeichert wird kg; die Rekordtexte stehen in der Einheit des Nutzers.
+    let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = ?", active_workout.user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+    let unit = user.unit();
+    let weight = match form.weight {
+        Some(w) if !(0.0..=10000.0).contains(&w) => {
+            return Err(AppError::BadRequest(format!("Weight must be between 0 and 10000 {input_unit}")));
+        }
+        Some(w) => Some(input_unit.to_kg(w as f64) as f32),
+        None => None,
+    };
+
+    let set_type = match form.set_type.as_deref().map(str::trim) {
+        None | Some("") => SetType::default(),
+        Some(raw) => SetType::parse(raw)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown set type '{raw}'")))?,
+    };
+
+    // Aufwärmsätze werden getrennt gezählt: W1, W2, dann Satz 1.
+    let is_warmup = set_type.is_warmup();
     let next_set_number = sqlx::query_scalar!(
         "SELECT COALESCE(MAX(set_number), 0) +1
          FROM completed_sets
-         WHERE active_workout_id = ? AND exercise_id = ?",
+         WHERE active_workout_id = ? AND exercise_id = ? AND (set_type = 'warmup') = ?",
         active_workout_id,
-        form.exercise_id
+        form.exercise_id,
+        is_warmup
     )
-    .fetch_one(&database_pool)
-    .await
-    .unwrap_or(1) as i32;
+    .fetch_one(&mut *tx)
+    .await? as i32;
+
+    let position = set_position(&mut tx, &active_workout, &form.exercise_id).await?;
 
     let mut completed_set = CompletedSet::new(
-        active_workout_id.clone(),
+        active_workout_id.to_string(),
         form.exercise_id.clone(),
         next_set_number,
-        form.weight,
+        weight,
         form.reps,
     );
     // Leere Eingabe ist keine Notiz — sonst landet "" statt NULL in der DB
     // und das Template rendert ein einsames Paar Anführungszeichen.
     completed_set.notes = form.notes.filter(|n| !n.trim().is_empty());
+    completed_set.rpe = rpe;
+    completed_set.set_type = set_type.as_str().to_string();
+    completed_set.completed_at = logged_at.to_rfc3339();
 
-    sqlx::query!(
-        "INSERT INTO completed_sets (id, active_workout_id, exercise_id, set_number, weight, reps, notes, completed_at, created_at)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    let inserted = sqlx::query!(
+        "INSERT INTO completed_sets (id, active_workout_id, exercise_id, set_number, weight, reps, notes, rpe, set_type, completed_at, created_at, client_request_id, position)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         completed_set.id,
         completed_set.active_workout_id,
         completed_set.exercise_id,
@@ -374,83 +628,147 @@ pub async fn complete_set(
         completed_set.weight,
         completed_set.reps,
         completed_set.notes,
+        completed_set.rpe,
+        completed_set.set_type,
         completed_set.completed_at,
-        completed_set.created_at
-    ).execute(&database_pool).await?;
+        completed_set.created_at,
+        client_request_id,
+        position
+    ).execute(&mut *tx).await;
+    // Zweite Absicherung neben der Prüfung oben: der Index lässt je
+    // Schlüssel nur einen Satz zu.
+    match inserted {
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Ok(LoggedSet::Duplicate);
+        }
+        other => {
+            other?;
+        }
+    }
 
-    // Check for new personal records
-    if let Ok(prs) = check_and_update_prs(
-        &database_pool,
+    webhooks::enqueue(
+        &mut tx,
+        &active_workout.user_id,
+        WebhookEvent::SetCompleted,
+        json!({"session_id": active_workout.id, "workout_id": active_workout.workout_id, "set": completed_set}),
+    )
+    .await?;
+
+    // Rekorde im selben Zug: scheitert die Prüfung, gibt es auch den Satz
+    // nicht, und zwei gleichzeitige Sätze sehen den Rekord des anderen.
+    let prs = check_and_update_prs(
+        &mut tx,
         &active_workout.user_id,
         &form.exercise_id,
         &completed_set,
+        unit,
     )
-    .await
-    {
-        if !prs.is_empty() {
-            // Store PR notifications in session for display
-            let _ = session.insert("pr_notifications", prs).await;
-        }
-    }
+    .await?;
+    tx.commit().await?;
+    webhooks::wake();
+    announce_set(database_pool, &active_workout, &completed_set, &prs).await;
 
-    // Generate progressive overload suggestion
-    if let Some(suggestion) = generate_overload_suggestion(&completed_set, form.weight) {
-        let _ = session.insert("overload_suggestion", suggestion).await;
-    }
+    Ok(LoggedSet::Logged { set: Box::new(completed_set), records: prs })
+}
 
-    let mut headers = HeaderMap::new();
-    headers.insert(
-        "HX-Redirect",
-        HeaderValue::from_str(&format!("/live-training/{}", active_workout_id))?,
-    );
-    Ok((headers, Html("Set completed".to_string())).into_response())
+/// Sätze einer Sitzung in zeitlicher Reihenfolge, laufend oder beendet.
+pub(crate) async fn session_sets(
+    conn: &mut SqliteConnection,
+    session_id: &str,
+) -> Result<Vec<CompletedSet>, sqlx::Error> {
+    sqlx::query_as!(
+        CompletedSet,
+        r#"SELECT id, active_workout_id, exercise_id, set_number as "set_number: i32",
+                  weight as "weight: f32", reps as "reps: i32", notes, rpe, set_type, completed_at, created_at
+           FROM completed_sets WHERE active_workout_id = ? ORDER BY completed_at, set_number"#,
+        session_id
+    )
+    .fetch_all(conn)
+    .await
 }
 
-pub async fn finish_training(
-    Path(active_workout_id): Path<String>,
-    State(database_pool): State<SqlitePool>,
-    Form(form): Form<FinishTrainingForm>,
-) -> Result<impl IntoResponse, AppError> {
-    let active_workout = sqlx::query_as!(
-        ActiveWorkout,
-        "SELECT * FROM active_workouts WHERE id = ?",
-        active_workout_id
+/// Beendete Pausen einer laufenden Sitzung als Anfang und Ende; siehe
+/// [`CompletedWorkout::new`].
+async fn session_pauses(
+    conn: &mut SqliteConnection,
+    session_id: &str,
+) -> Result<Vec<(DateTime<Utc>, DateTime<Utc>)>, sqlx::Error> {
+    let rows = sqlx::query!(
+        "SELECT paused_at, resumed_at FROM session_pauses WHERE session_id = ?",
+        session_id
     )
-    .fetch_optional(&database_pool)
+    .fetch_all(conn)
     .await?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| Some((parse_timestamp(&row.paused_at)?, parse_timestamp(&row.resumed_at)?)))
+        .collect())
+}
 
-    let active_workout = match active_workout {
-        Some(workout) => workout,
-        None => return Err(AppError::NotFound("Active workout not found".to_string())),
-    };
+/// Training abschließen: nach `completed_workouts` übertragen, die laufende
+/// Sitzung entfernen und die Ziele fortschreiben — in einer Transaktion,
+/// damit ein Absturz dazwischen keine Sitzung hinterlässt, die zugleich läuft
+/// und abgeschlossen ist. Gemeinsam für „Beenden" und den Hintergrundjob.
+///
+/// Die Sitzung wird in der Transaktion neu gelesen, nur für `user_id`.
+/// `None`, wenn sie nicht (mehr) läuft, etwa weil ein zweiter Klick auf
+/// „Beenden" schneller war.
+pub(crate) async fn close_session(
+    database_pool: &SqlitePool,
+    active_workout_id: &str,
+    user_id: &str,
+    notes: Option<String>,
+    ended_at: DateTime<Utc>,
+    auto_closed: bool,
+) -> Result<Option<CompletedWorkout>, AppError> {
+    let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = ?", user_id)
+        .fetch_one(database_pool)
+        .await?;
+    let equipment = load_equipment(database_pool, &user).await?;
 
-    let total_sets = sqlx::query_scalar!(
-        "SELECT COUNT(*) FROM completed_sets WHERE active_workout_id = ?",
-        active_workout_id
+    let mut tx = database_pool.begin_with("BEGIN IMMEDIATE").await?;
+    let Some(active_workout) = sqlx::query_as!(
+        ActiveWorkout,
+        "SELECT * FROM active_workouts WHERE id = ? AND user_id = ?",
+        active_workout_id,
+        user_id
     )
-    .fetch_one(&database_pool)
-    .await
-    .unwrap_or(0) as i32;
+    .fetch_optional(&mut *tx)
+    .await?
+    else {
+        return Ok(None);
+    };
 
-    let total_volume_kg = sqlx::query_scalar!(
-        r#"SELECT COALESCE(SUM(weight * reps), 0.0) as "total_volume!: f32"
-            FROM completed_sets
-            WHERE active_workout_id = ? AND weight IS NOT NULL"#,
-        active_workout_id
-    )
-    .fetch_one(&database_pool)
-    .await
-    .unwrap_or(0.0);
+    let sets = session_sets(&mut tx, active_workout_id).await?;
+    if sets.is_empty() {
+        return Err(AppError::BadRequest(
+            "Log at least one set before finishing, or discard the session".to_string(),
+        ));
+    }
 
-    // Leere Eingabe ist keine Notiz — siehe complete_set weiter oben.
-    let notes = form.notes.filter(|n| !n.trim().is_empty());
-    let completed_workout =
-        CompletedWorkout::new(active_workout, total_sets, total_volume_kg, notes);
+    let pauses = session_pauses(&mut tx, active_workout_id).await?;
+    let mut completed_workout = CompletedWorkout::new(active_workout, &sets, &pauses, notes, ended_at);
+    completed_workout.auto_closed = auto_closed;
+    store_completed(&mut tx, &completed_workout, &user, &equipment).await?;
+    tx.commit().await?;
+    webhooks::wake();
+    live_events::publish(active_workout_id, LiveEvent::finished(false));
+
+    Ok(Some(completed_workout))
+}
 
+/// Der Teil von `close_session` innerhalb der Transaktion: abgeschlossenes
+/// Training schreiben, laufende Sitzung löschen, Ziele und Webhook.
+async fn store_completed(
+    tx: &mut SqliteConnection,
+    completed_workout: &CompletedWorkout,
+    user: &User,
+    equipment: &Equipment,
+) -> Result<(), AppError> {
     sqlx::query!(
         r#"INSERT INTO completed_workouts
-            (id, user_id, workout_id, started_at, completed_at, total_duration_minutes, total_sets, total_volume_kg, notes, created_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+            (id, user_id, workout_id, started_at, completed_at, total_duration_minutes, total_sets, total_volume_kg, notes, created_at, auto_closed, exercise_times)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
         completed_workout.id,
         completed_workout.user_id,
         completed_workout.workout_id,
@@ -460,18 +778,109 @@ pub async fn finish_training(
         completed_workout.total_sets,
         completed_workout.total_volume_kg,
         completed_workout.notes,
-        completed_workout.created_at
-    ).execute(&database_pool).await?;
+        completed_workout.created_at,
+        completed_workout.auto_closed,
+        completed_workout.exercise_times
+    ).execute(&mut *tx).await?;
 
     sqlx::query!(
         "DELETE FROM active_workouts WHERE id = ?",
-        active_workout_id
+        completed_workout.id
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!("DELETE FROM session_pauses WHERE session_id = ?", completed_workout.id)
+        .execute(&mut *tx)
+        .await?;
+
+    // Ziele für das nächste Mal aus der jetzt abgeschlossenen Historie.
+    // Freies Training hat keinen Plan und damit keine Ziele.
+    if let Some(workout_id) = completed_workout.workout_id.as_deref() {
+        update_targets(
+            &mut *tx,
+            user,
+            equipment,
+            workout_id,
+            &completed_workout.id,
+        )
+        .await?;
+    }
+    webhooks::enqueue(
+        &mut *tx,
+        &user.id,
+        WebhookEvent::WorkoutFinished,
+        json!({"workout": completed_workout}),
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn finish_training(
+    Path(active_workout_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Form(form): Form<FinishTrainingForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(current_user) = get_current_user(&session, &database_pool).await? else {
+        let mut headers = HeaderMap::new();
+        headers.insert("HX-Redirect", HeaderValue::from_static("/users"));
+        return Ok((headers, Html("Not logged in".to_string())).into_response());
+    };
+
+    // Leere Eingabe ist keine Notiz — siehe complete_set weiter oben.
+    let notes = form.notes.filter(|n| !n.trim().is_empty());
+    let closed = close_session(
+        &database_pool,
+        &active_workout_id,
+        &current_user.id,
+        notes,
+        Utc::now(),
+        false,
     )
-    .execute(&database_pool)
     .await?;
+    let Some(completed_workout) = closed else {
+        // Schon beendet (Doppelklick, wiederholter Request): zum Ergebnis
+        // statt eines Fehlers. Fremde Sitzungen bleiben unsichtbar.
+        let finished = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM completed_workouts WHERE id = ? AND user_id = ?",
+            active_workout_id,
+            current_user.id
+        )
+        .fetch_one(&database_pool)
+        .await?;
+        if finished == 0 {
+            return Err(AppError::NotFound("Active workout not found".to_string()));
+        }
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "HX-Redirect",
+            HeaderValue::from_str(&format!("/history/{}", active_workout_id))?,
+        );
+        return Ok((headers, Html("Training already completed".to_string())).into_response());
+    };
+
+    // Freies Training: das Angebot, daraus einen Plan zu machen, steht auf
+    // der Detailseite.
+    let Some(workout_id) = completed_workout.workout_id.as_deref() else {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "HX-Redirect",
+            HeaderValue::from_str(&format!("/history/{}", completed_workout.id))?,
+        );
+        return Ok((headers, Html("Training completed successfully!".to_string())).into_response());
+    };
 
+    // Gibt es Vorschläge für den Plan, erst zur Bestätigung.
+    let changes = pending_changes(&database_pool, workout_id, &completed_workout.id).await?;
     let mut headers = HeaderMap::new();
-    headers.insert("HX-Redirect", HeaderValue::from_static("/dashboard"));
+    if changes.is_empty() {
+        headers.insert("HX-Redirect", HeaderValue::from_static("/dashboard"));
+    } else {
+        headers.insert(
+            "HX-Redirect",
+            HeaderValue::from_str(&format!("/history/{}/targets", completed_workout.id))?,
+        );
+    }
 
     Ok((
         headers,
@@ -479,10 +888,1195 @@ pub async fn finish_training(
     ).into_response())
 }
 
-pub fn router() -> Router<SqlitePool> {
-    Router::new()
-        .route("/start-training", post(start_training))
-        .route("/live-training/{id}", get(show_live_training))
-        .route("/live-training/{id}/complete-set", post(complete_set))
-        .route("/live-training/{id}/finish", post(finish_training))
+/// Pause zu Ende: ihre Dauer wandert in `paused_seconds`. Nur wenn die
+/// Pause noch dieselbe ist — wer zu zweit fortsetzt, soll das Intervall
+/// nicht doppelt eintragen.
+async fn resume_session(
+    conn: &mut SqliteConnection,
+    active_workout: &ActiveWorkout,
+) -> Result<(), AppError> {
+    if !active_workout.is_paused() {
+        return Ok(());
+    }
+    let now = Utc::now();
+    let paused_seconds = active_workout.paused_seconds + active_workout.current_pause_seconds(now);
+    let resumed = sqlx::query!(
+        "UPDATE active_workouts SET paused_at = NULL, paused_seconds = ? WHERE id = ? AND paused_at = ?",
+        paused_seconds,
+        active_workout.id,
+        active_workout.paused_at
+    )
+    .execute(&mut *conn)
+    .await?;
+    if resumed.rows_affected() != 1 {
+        return Ok(());
+    }
+    // Das Intervall selbst, damit die Dauer von Satz zu Satz es abziehen kann.
+    let resumed_at = now.to_rfc3339();
+    sqlx::query!(
+        "INSERT INTO session_pauses (session_id, paused_at, resumed_at) VALUES (?, ?, ?)",
+        active_workout.id,
+        active_workout.paused_at,
+        resumed_at
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+pub async fn pause_training(
+    Path(active_workout_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(current_user) = get_current_user(&session, &database_pool).await? else {
+        return Err(AppError::Unauthorized);
+    };
+    find_active_workout(&database_pool, &active_workout_id, &current_user.id).await?;
+    // Schon pausiert: Die laufende Pause behält ihren Anfang.
+    let now = Utc::now().to_rfc3339();
+    sqlx::query!(
+        "UPDATE active_workouts SET paused_at = ? WHERE id = ? AND paused_at IS NULL",
+        now,
+        active_workout_id
+    )
+    .execute(&database_pool)
+    .await?;
+    back_to_training(&active_workout_id, "Training paused")
+}
+
+pub async fn resume_training(
+    Path(active_workout_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(current_user) = get_current_user(&session, &database_pool).await? else {
+        return Err(AppError::Unauthorized);
+    };
+    find_active_workout(&database_pool, &active_workout_id, &current_user.id).await?;
+    // Wie beim Loggen: erst die Sperre, dann den Stand der Pause lesen.
+    let mut tx = database_pool.begin_with("BEGIN IMMEDIATE").await?;
+    let active_workout = sqlx::query_as!(
+        ActiveWorkout,
+        "SELECT * FROM active_workouts WHERE id = ?",
+        active_workout_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Active workout not found".to_string()))?;
+    resume_session(&mut tx, &active_workout).await?;
+    tx.commit().await?;
+    back_to_training(&active_workout_id, "Training resumed")
+}
+
+/// Sitzung verwerfen, als hätte es sie nie gegeben: Sätze, Sitzungsplan und
+/// die Sitzung selbst. Rekorde der betroffenen Übungen werden aus den
+/// übrigen Sätzen neu aufgebaut.
+async fn discard_session(
+    database_pool: &SqlitePool,
+    active_workout: &ActiveWorkout,
+) -> Result<(), AppError> {
+    // IMMEDIATE wie beim Loggen: Ein Satz, der zwischen Lesen und Löschen
+    // ankommt, fehlte sonst bei den neu berechneten Rekorden.
+    let mut tx = database_pool.begin_with("BEGIN IMMEDIATE").await?;
+    let exercise_ids = sqlx::query_scalar!(
+        "SELECT DISTINCT exercise_id FROM completed_sets WHERE active_workout_id = ?",
+        active_workout.id
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    sqlx::query!("DELETE FROM completed_sets WHERE active_workout_id = ?", active_workout.id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query!("DELETE FROM session_exercises WHERE session_id = ?", active_workout.id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query!("DELETE FROM session_pauses WHERE session_id = ?", active_workout.id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query!("DELETE FROM active_workouts WHERE id = ?", active_workout.id)
+        .execute(&mut *tx)
+        .await?;
+    for exercise_id in &exercise_ids {
+        recompute_prs(&mut tx, &active_workout.user_id, exercise_id).await?;
+    }
+    tx.commit().await?;
+    live_events::publish(&active_workout.id, LiveEvent::finished(true));
+    Ok(())
+}
+
+pub async fn discard_training(
+    Path(active_workout_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(current_user) = get_current_user(&session, &database_pool).await? else {
+        return Err(AppError::Unauthorized);
+    };
+    let active_workout = find_active_workout(&database_pool, &active_workout_id, &current_user.id).await?;
+    discard_session(&database_pool, &active_workout).await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert("HX-Redirect", HeaderValue::from_static("/dashboard"));
+    Ok((headers, Html("Training discarded".to_string())).into_response())
+}
+
+/// Sitzungen, in denen seit `limit` nichts passiert ist, schließen: mit
+/// Sätzen als abgeschlossenes Training, das am letzten Satz endet und als
+/// automatisch geschlossen markiert ist; ohne Sätze wird verworfen.
+/// Liefert die Zahl der geschlossenen Sitzungen.
+///
+/// Scheitert eine Sitzung, wird das protokolliert und mit der nächsten
+/// weitergemacht — sonst bliebe alles dahinter für immer offen.
+pub async fn close_stale_sessions(
+    database_pool: &SqlitePool,
+    limit: chrono::Duration,
+    now: DateTime<Utc>,
+) -> Result<usize, AppError> {
+    let sessions = sqlx::query_as!(ActiveWorkout, "SELECT * FROM active_workouts ORDER BY started_at")
+        .fetch_all(database_pool)
+        .await?;
+
+    let mut closed = 0;
+    for active_workout in sessions {
+        match close_if_stale(database_pool, &active_workout, limit, now).await {
+            Ok(true) => closed += 1,
+            Ok(false) => {}
+            Err(e) => tracing::error!("auto-closing session {} failed: {:?}", active_workout.id, e),
+        }
+    }
+    Ok(closed)
+}
+
+/// Eine Sitzung schließen oder verwerfen, falls sie still steht. Prüfung
+/// und Schreiben in einer `BEGIN IMMEDIATE`-Transaktion: ein Satz, der
+/// dazwischen geloggt wird, macht die Sitzung wieder lebendig, statt mit
+/// ihr gelöscht zu werden.
+async fn close_if_stale(
+    database_pool: &SqlitePool,
+    active_workout: &ActiveWorkout,
+    limit: chrono::Duration,
+    now: DateTime<Utc>,
+) -> Result<bool, AppError> {
+    let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = ?", active_workout.user_id)
+        .fetch_one(database_pool)
+        .await?;
+    let equipment = load_equipment(database_pool, &user).await?;
+
+    let mut tx = database_pool.begin_with("BEGIN IMMEDIATE").await?;
+    // Inzwischen vom Nutzer beendet oder verworfen: nichts mehr zu tun.
+    let Some(active_workout) = sqlx::query_as!(
+        ActiveWorkout,
+        "SELECT * FROM active_workouts WHERE id = ?",
+        active_workout.id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    else {
+        return Ok(false);
+    };
+
+    let sets = session_sets(&mut tx, &active_workout.id).await?;
+    let last_set = sets.iter().filter_map(|set| parse_timestamp(&set.completed_at)).max();
+    let Some(last_activity) = last_set.or_else(|| parse_timestamp(&active_workout.started_at)) else {
+        return Ok(false);
+    };
+    if now.signed_duration_since(last_activity) < limit {
+        return Ok(false);
+    }
+
+    let session_id = active_workout.id.clone();
+    match last_set {
+        Some(ended_at) => {
+            let pauses = session_pauses(&mut tx, &session_id).await?;
+            let mut completed_workout = CompletedWorkout::new(active_workout, &sets, &pauses, None, ended_at);
+            completed_workout.auto_closed = true;
+            store_completed(&mut tx, &completed_workout, &user, &equipment).await?;
+            tx.commit().await?;
+            webhooks::wake();
+            live_events::publish(&session_id, LiveEvent::finished(false));
+        }
+        // Ohne Sätze gibt es auch keine Rekorde neu zu rechnen.
+        None => {
+            sqlx::query!("DELETE FROM session_exercises WHERE session_id = ?", session_id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query!("DELETE FROM session_pauses WHERE session_id = ?", session_id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query!("DELETE FROM active_workouts WHERE id = ?", session_id)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+            live_events::publish(&session_id, LiveEvent::finished(true));
+        }
+    }
+    Ok(true)
+}
+
+/// Hintergrundjob für `close_stale_sessions`, alle zehn Minuten.
+pub async fn auto_close_job(database_pool: SqlitePool, limit: chrono::Duration) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(10 * 60));
+    loop {
+        interval.tick().await;
+        match close_stale_sessions(&database_pool, limit, Utc::now()).await {
+            Ok(0) => {}
+            Ok(closed) => tracing::info!("auto-closed {} stale training sessions", closed),
+            Err(e) => tracing::error!("auto-closing stale sessions failed: {:?}", e),
+        }
+    }
+}
+
+/// Pause, wenn der Plan keine nennt — wie im Template.
+const DEFAULT_REST_SECONDS: i32 = 90;
+
+fn current_exercise_event(state: &SessionState) -> LiveEvent {
+    LiveEvent::exercise_changed(state.current.map(|index| {
+        let exercise = &state.exercises[index];
+        (exercise.position, exercise.exercise_id.as_str(), exercise.exercise_name.as_str())
+    }))
+}
+
+/// Neuer Satz an die Zuschauer, dazu Pause und Übungswechsel, wie sie die
+/// Seite des Loggenden zeigt. Scheitert das Nachladen, fehlen nur diese
+/// Folgeereignisse — der Satz ist längst gespeichert.
+async fn announce_set(
+    database_pool: &SqlitePool,
+    active_workout: &ActiveWorkout,
+    completed_set: &CompletedSet,
+    records: &[String],
+) {
+    if !live_events::is_watched(&active_workout.id) {
+        return;
+    }
+    live_events::publish(&active_workout.id, LiveEvent::set_completed(json!(completed_set), records));
+
+    let state = match load_session(database_pool, &active_workout.id, active_workout.workout_id.as_deref()).await {
+        Ok(state) => state,
+        Err(e) => {
+            tracing::warn!("live events for session {} incomplete: {:?}", active_workout.id, e);
+            return;
+        }
+    };
+    let Some(index) = state.exercises.iter().position(|e| e.exercise_id == completed_set.exercise_id) else {
+        return;
+    };
+    // Die Seite entscheidet vor dem Satz; Aufwärmsätze zählen nicht mit.
+    let mut before = state.done.clone();
+    if !completed_set.set_type().is_warmup() {
+        before[index] = (before[index] - 1).max(0);
+    }
+    if rest_after_set(&state.exercises, &before, index) {
+        let seconds = state.exercises[index].rest_seconds.unwrap_or(DEFAULT_REST_SECONDS);
+        live_events::publish(&active_workout.id, LiveEvent::rest_started(seconds));
+    }
+    if state.current != Some(index) {
+        live_events::publish(&active_workout.id, current_exercise_event(&state));
+    }
+}
+
+/// Aktuelle Übung an die Zuschauer, nach Umsortieren und Ersetzen.
+async fn announce_current(database_pool: &SqlitePool, active_workout: &ActiveWorkout) {
+    if !live_events::is_watched(&active_workout.id) {
+        return;
+    }
+    match load_session(database_pool, &active_workout.id, active_workout.workout_id.as_deref()).await {
+        Ok(state) => live_events::publish(&active_workout.id, current_exercise_event(&state)),
+        Err(e) => tracing::warn!("live events for session {} incomplete: {:?}", active_workout.id, e),
+    }
+}
+
+/// Laufende Sitzung von `user_id`. Fremde Sitzungen gibt es nicht — die ID
+/// steht auch in der Zuschauer-URL und ist kein Zugang; auch dort zählt nur
+/// die Anmeldung des Besitzers.
+async fn find_active_workout(
+    database_pool: &SqlitePool,
+    active_workout_id: &str,
+    user_id: &str,
+) -> Result<ActiveWorkout, AppError> {
+    sqlx::query_as!(
+        ActiveWorkout,
+        "SELECT * FROM active_workouts WHERE id = ? AND user_id = ?",
+        active_workout_id,
+        user_id
+    )
+    .fetch_optional(database_pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Active workout not found".to_string()))
+}
+
+fn back_to_training(active_workout_id: &str, message: &str) -> Result<axum::response::Response, AppError> {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "HX-Redirect",
+        HeaderValue::from_str(&format!("/live-training/{}", active_workout_id))?,
+    );
+    Ok((headers, Html(message.to_string())).into_response())
+}
+
+/// Block an `position` nach vorn oder ans Ende der Sitzung schieben und
+/// die Reihenfolge neu durchnummerieren.
+async fn move_session_block(
+    database_pool: &SqlitePool,
+    active_workout_id: &str,
+    user_id: &str,
+    position: i32,
+    to_front: bool,
+) -> Result<(), AppError> {
+    let active_workout = find_active_workout(database_pool, active_workout_id, user_id).await?;
+    let mut tx = database_pool.begin_with("BEGIN IMMEDIATE").await?;
+    reorder_session(&mut tx, &active_workout, position, to_front).await?;
+    tx.commit().await?;
+    announce_current(database_pool, &active_workout).await;
+    Ok(())
+}
+
+/// [`move_session_block`] in einer Transaktion des Aufrufers, damit eine
+/// Änderung und das Verschieben zusammen sichtbar werden.
+async fn reorder_session(
+    conn: &mut SqliteConnection,
+    active_workout: &ActiveWorkout,
+    position: i32,
+    to_front: bool,
+) -> Result<(), AppError> {
+    let exercises = session_exercises(conn, &active_workout.id, active_workout.workout_id.as_deref()).await?;
+    let index = exercises
+        .iter()
+        .position(|e| e.position == position)
+        .ok_or_else(|| AppError::NotFound("Exercise is not part of this session".to_string()))?;
+
+    for (sort_order, position) in move_block(&exercises, index, to_front).into_iter().enumerate() {
+        let sort_order = sort_order as i32;
+        sqlx::query!(
+            "UPDATE session_exercises SET sort_order = ? WHERE session_id = ? AND position = ?",
+            sort_order,
+            active_workout.id,
+            position
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+pub async fn skip_exercise(
+    Path(active_workout_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Form(form): Form<SessionExerciseForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(current_user) = get_current_user(&session, &database_pool).await? else {
+        return Err(AppError::Unauthorized);
+    };
+    move_session_block(&database_pool, &active_workout_id, &current_user.id, form.position, false).await?;
+    back_to_training(&active_workout_id, "Exercise skipped for now")
+}
+
+pub async fn jump_to_exercise(
+    Path(active_workout_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Form(form): Form<SessionExerciseForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(current_user) = get_current_user(&session, &database_pool).await? else {
+        return Err(AppError::Unauthorized);
+    };
+    move_session_block(&database_pool, &active_workout_id, &current_user.id, form.position, true).await?;
+    back_to_training(&active_workout_id, "Jumped to exercise")
+}
+
+/// Einen Satz über die Vorgabe hinaus: Die Übung wird wieder aktuell.
+pub async fn add_extra_set(
+    Path(active_workout_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Form(form): Form<SessionExerciseForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(current_user) = get_current_user(&session, &database_pool).await? else {
+        return Err(AppError::Unauthorized);
+    };
+    // Erst den Besitzer prüfen, dann ändern.
+    let active_workout = find_active_workout(&database_pool, &active_workout_id, &current_user.id).await?;
+    // Zusatzsatz und neue Reihenfolge in einem Zug, wie beim Loggen.
+    let mut tx = database_pool.begin_with("BEGIN IMMEDIATE").await?;
+    let updated = sqlx::query!(
+        "UPDATE session_exercises SET extra_sets = extra_sets + 1 WHERE session_id = ? AND position = ?",
+        active_workout_id,
+        form.position
+    )
+    .execute(&mut *tx)
+    .await?;
+    if updated.rows_affected() == 0 {
+        return Err(AppError::NotFound("Exercise is not part of this session".to_string()));
+    }
+    reorder_session(&mut tx, &active_workout, form.position, true).await?;
+    tx.commit().await?;
+    announce_current(&database_pool, &active_workout).await;
+    back_to_training(&active_workout_id, "Extra set added")
+}
+
+/// Übung aus der Bibliothek spontan in die Sitzung nehmen, mit einem Satz.
+/// Ist sie schon dabei, gibt es dort einen Satz mehr.
+pub async fn add_session_exercise(
+    Path(active_workout_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Form(form): Form<AddSessionExerciseForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(current_user) = get_current_user(&session, &database_pool).await? else {
+        return Err(AppError::Unauthorized);
+    };
+    let active_workout = find_active_workout(&database_pool, &active_workout_id, &current_user.id).await?;
+    sqlx::query_scalar!("SELECT id FROM exercises WHERE id = ?", form.exercise_id)
+        .fetch_optional(&database_pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Exercise '{}' not found", form.exercise_id)))?;
+
+    let mut tx = database_pool.begin_with("BEGIN IMMEDIATE").await?;
+    snapshot_plan(&mut tx, &active_workout_id, active_workout.workout_id.as_deref()).await?;
+    let existing = sqlx::query_scalar!(
+        r#"SELECT position as "position!: i32" FROM session_exercises WHERE session_id = ? AND exercise_id = ?"#,
+        active_workout_id,
+        form.exercise_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+    let position = match existing {
+        Some(position) => {
+            sqlx::query!(
+                "UPDATE session_exercises SET extra_sets = extra_sets + 1 WHERE session_id = ? AND position = ?",
+                active_workout_id,
+                position
+            )
+            .execute(&mut *tx)
+            .await?;
+            position
+        }
+        None => {
+            let position = sqlx::query_scalar!(
+                r#"SELECT MIN(COALESCE(MIN(position), 0), 0) - 1 as "position!: i32"
+                   FROM session_exercises WHERE session_id = ?"#,
+                active_workout_id
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            sqlx::query!(
+                "INSERT INTO session_exercises (session_id, position, planned_exercise_id, exercise_id, sort_order, extra_sets)
+                 VALUES (?, ?, ?, ?, (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM session_exercises WHERE session_id = ?), 1)",
+                active_workout_id,
+                position,
+                form.exercise_id,
+                form.exercise_id,
+                active_workout_id
+            )
+            .execute(&mut *tx)
+            .await?;
+            position
+        }
+    };
+    reorder_session(&mut tx, &active_workout, position, true).await?;
+    tx.commit().await?;
+    announce_current(&database_pool, &active_workout).await;
+    back_to_training(&active_workout_id, "Exercise added")
+}
+
+/// Übung nur für diese Sitzung ersetzen. Geht nur, solange für sie noch
+/// nichts geloggt ist — sonst hingen Sätze an der falschen Übung.
+pub async fn substitute_exercise(
+    Path(active_workout_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Form(form): Form<SubstituteExerciseForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(current_user) = get_current_user(&session, &database_pool).await? else {
+        return Err(AppError::Unauthorized);
+    };
+    let active_workout = find_active_workout(&database_pool, &active_workout_id, &current_user.id).await?;
+    let mut tx = database_pool.begin_with("BEGIN IMMEDIATE").await?;
+    snapshot_plan(&mut tx, &active_workout_id, active_workout.workout_id.as_deref()).await?;
+
+    let slot = sqlx::query!(
+        "SELECT planned_exercise_id, exercise_id FROM session_exercises WHERE session_id = ? AND position = ?",
+        active_workout_id,
+        form.position
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Exercise is not part of this session".to_string()))?;
+
+    let exercise_id = form
+        .exercise_id
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| slot.planned_exercise_id.clone());
+    if exercise_id == slot.exercise_id {
+        return back_to_training(&active_workout_id, "Nothing to change");
+    }
+
+    let logged = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM completed_sets WHERE active_workout_id = ? AND exercise_id = ?",
+        active_workout_id,
+        slot.exercise_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    if logged > 0 {
+        return Err(AppError::BadRequest(
+            "Sets are already logged for this exercise".to_string(),
+        ));
+    }
+
+    sqlx::query_scalar!("SELECT id FROM exercises WHERE id = ?", exercise_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Exercise '{}' not found", exercise_id)))?;
+    let in_session = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM session_exercises WHERE session_id = ? AND exercise_id = ?",
+        active_workout_id,
+        exercise_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    if in_session > 0 {
+        return Err(AppError::BadRequest(
+            "Exercise is already part of this session".to_string(),
+        ));
+    }
+
+    sqlx::query!(
+        "UPDATE session_exercises SET exercise_id = ? WHERE session_id = ? AND position = ?",
+        exercise_id,
+        active_workout_id,
+        form.position
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    announce_current(&database_pool, &active_workout).await;
+
+    back_to_training(&active_workout_id, "Exercise substituted")
+}
+
+pub fn router() -> Router<SqlitePool> {
+    Router::new()
+        .route("/start-training", post(start_training))
+        .route("/start-freestyle", post(start_freestyle))
+        .route("/live-training/{id}", get(show_live_training))
+        .route("/live-training/{id}/complete-set", post(complete_set))
+        .route("/live-training/{id}/skip", post(skip_exercise))
+        .route("/live-training/{id}/jump", post(jump_to_exercise))
+        .route("/live-training/{id}/substitute", post(substitute_exercise))
+        .route("/live-training/{id}/extra-set", post(add_extra_set))
+        .route("/live-training/{id}/add-exercise", post(add_session_exercise))
+        .route("/live-training/{id}/finish", post(finish_training))
+        .route("/live-training/{id}/pause", post(pause_training))
+        .route("/live-training/{id}/resume", post(resume_training))
+        .route("/live-training/{id}/discard", post(discard_training))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ExerciseTime;
+
+    async fn seed(pool: &SqlitePool) {
+        sqlx::query!(
+            "INSERT INTO users (id, name, created_at) VALUES ('u-1', 'Anna', '2026-01-01T00:00:00Z')"
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        for (id, name) in [("ex-squat", "Squat"), ("ex-bench", "Bench"), ("ex-press", "Leg Press")] {
+            sqlx::query!(
+                "INSERT INTO exercises (id, name, instructions, video_url, created_at)
+                 VALUES (?, ?, '', NULL, '2026-01-01T00:00:00Z')",
+                id,
+                name
+            )
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query!(
+            "INSERT INTO workouts (id, user_id, name, description, is_active, schedule_type, schedule_day, created_at, updated_at)
+             VALUES ('w-1', 'u-1', 'Full Body', NULL, 1, 'manual', NULL, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')"
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        for (id, exercise_id, position) in [("we-1", "ex-squat", 1), ("we-2", "ex-bench", 2)] {
+            sqlx::query!(
+                "INSERT INTO workout_exercises (id, workout_id, exercise_id, position, target_sets, created_at)
+                 VALUES (?, 'w-1', ?, ?, 3, '2026-01-01T00:00:00Z')",
+                id,
+                exercise_id,
+                position
+            )
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query!(
+            "INSERT INTO active_workouts (id, user_id, workout_id, started_at, created_at)
+             VALUES ('a-1', 'u-1', 'w-1', '2026-03-01T18:00:00Z', '2026-03-01T18:00:00Z')"
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn current_name(state: &SessionState) -> Option<&str> {
+        state.current.map(|index| state.exercises[index].exercise_name.as_str())
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn skip_and_jump_reorder_only_the_session(pool: SqlitePool) {
+        seed(&pool).await;
+        let state = load_session(&pool, "a-1", Some("w-1")).await.unwrap();
+        assert_eq!(current_name(&state), Some("Squat"));
+
+        move_session_block(&pool, "a-1", "u-1", 1, false).await.unwrap();
+        let state = load_session(&pool, "a-1", Some("w-1")).await.unwrap();
+        assert_eq!(current_name(&state), Some("Bench"));
+
+        move_session_block(&pool, "a-1", "u-1", 1, true).await.unwrap();
+        let state = load_session(&pool, "a-1", Some("w-1")).await.unwrap();
+        assert_eq!(current_name(&state), Some("Squat"));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn substitution_is_recorded_without_touching_the_plan(pool: SqlitePool) {
+        seed(&pool).await;
+        let form = SubstituteExerciseForm { position: 1, exercise_id: Some("ex-press".to_string()) };
+        assert!(substitute_exercise(Path("a-1".to_string()), State(pool.clone()), logged_in("u-1").await, Form(form)).await.is_ok());
+
+        let state = load_session(&pool, "a-1", Some("w-1")).await.unwrap();
+        assert_eq!(current_name(&state), Some("Leg Press"));
+        let substitutions = load_substitutions(&pool, "a-1").await.unwrap();
+        assert_eq!(substitutions.len(), 1);
+        assert_eq!(substitutions[0].planned_name, "Squat");
+        let planned = sqlx::query_scalar!("SELECT exercise_id FROM workout_exercises WHERE id = 'we-1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(planned, "ex-squat");
+
+        // Bench ist schon Teil der Sitzung.
+        let form = SubstituteExerciseForm { position: 1, exercise_id: Some("ex-bench".to_string()) };
+        assert!(substitute_exercise(Path("a-1".to_string()), State(pool.clone()), logged_in("u-1").await, Form(form)).await.is_err());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn only_the_owner_changes_the_session_plan(pool: SqlitePool) {
+        seed(&pool).await;
+        sqlx::query!("INSERT INTO users (id, name, created_at) VALUES ('u-2', 'Ben', '2026-01-01T00:00:00Z')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let skip = |session: Session| {
+            skip_exercise(Path("a-1".to_string()), State(pool.clone()), session, Form(SessionExerciseForm { position: 1 }))
+        };
+        assert!(matches!(skip(session()).await, Err(AppError::Unauthorized)));
+        assert!(matches!(skip(logged_in("u-2").await).await, Err(AppError::NotFound(_))));
+        let form = SubstituteExerciseForm { position: 1, exercise_id: Some("ex-press".to_string()) };
+        let substituted =
+            substitute_exercise(Path("a-1".to_string()), State(pool.clone()), logged_in("u-2").await, Form(form)).await;
+        assert!(matches!(substituted, Err(AppError::NotFound(_))));
+        let shown = |session: Session| show_live_training(Path("a-1".to_string()), State(pool.clone()), session);
+        assert!(matches!(shown(session()).await, Err(AppError::Unauthorized)));
+        assert!(matches!(shown(logged_in("u-2").await).await, Err(AppError::NotFound(_))));
+
+        let state = load_session(&pool, "a-1", Some("w-1")).await.unwrap();
+        assert_eq!(current_name(&state), Some("Squat"));
+        assert!(load_substitutions(&pool, "a-1").await.unwrap().is_empty());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn extra_sets_and_ad_hoc_exercises_reopen_a_finished_session(pool: SqlitePool) {
+        seed(&pool).await;
+        for (index, exercise_id) in ["ex-squat", "ex-bench"].iter().enumerate() {
+            for number in 1..=3 {
+                let id = format!("cs-{index}-{number}");
+                sqlx::query!(
+                    "INSERT INTO completed_sets (id, active_workout_id, exercise_id, set_number, weight, reps, completed_at, created_at)
+                     VALUES (?, 'a-1', ?, ?, 100.0, 5, '2026-03-01T18:10:00Z', '2026-03-01T18:10:00Z')",
+                    id,
+                    exercise_id,
+                    number
+                )
+                .execute(&pool)
+                .await
+                .unwrap();
+            }
+        }
+        let state = load_session(&pool, "a-1", Some("w-1")).await.unwrap();
+        assert_eq!(current_name(&state), None);
+        assert_eq!(progress_percent(&state.exercises, &state.done), 100.0);
+
+        let form = SessionExerciseForm { position: 1 };
+        assert!(add_extra_set(Path("a-1".to_string()), State(pool.clone()), logged_in("u-1").await, Form(form)).await.is_ok());
+        let state = load_session(&pool, "a-1", Some("w-1")).await.unwrap();
+        assert_eq!(current_name(&state), Some("Squat"));
+        assert_eq!(state.exercises[state.current.unwrap()].target_sets, 4);
+
+        let form = AddSessionExerciseForm { exercise_id: "ex-press".to_string() };
+        assert!(add_session_exercise(Path("a-1".to_string()), State(pool.clone()), logged_in("u-1").await, Form(form)).await.is_ok());
+        let state = load_session(&pool, "a-1", Some("w-1")).await.unwrap();
+        assert_eq!(current_name(&state), Some("Leg Press"));
+        assert_eq!(state.exercises.len(), 3);
+        assert_eq!(progress_percent(&state.exercises, &state.done), 75.0);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn other_users_cannot_add_sets_or_exercises(pool: SqlitePool) {
+        seed(&pool).await;
+        sqlx::query!("INSERT INTO users (id, name, created_at) VALUES ('u-2', 'Ben', '2026-01-01T00:00:00Z')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        snapshot_plan(&mut conn, "a-1", Some("w-1")).await.unwrap();
+        drop(conn);
+
+        let form = SessionExerciseForm { position: 1 };
+        let extra = add_extra_set(Path("a-1".to_string()), State(pool.clone()), logged_in("u-2").await, Form(form)).await;
+        assert!(matches!(extra, Err(AppError::NotFound(_))));
+        let form = AddSessionExerciseForm { exercise_id: "ex-press".to_string() };
+        let added =
+            add_session_exercise(Path("a-1".to_string()), State(pool.clone()), logged_in("u-2").await, Form(form)).await;
+        assert!(matches!(added, Err(AppError::NotFound(_))));
+        let form = AddSessionExerciseForm { exercise_id: "ex-press".to_string() };
+        let added = add_session_exercise(Path("a-1".to_string()), State(pool.clone()), session(), Form(form)).await;
+        assert!(matches!(added, Err(AppError::Unauthorized)));
+
+        let state = load_session(&pool, "a-1", Some("w-1")).await.unwrap();
+        assert_eq!(state.exercises.len(), 2);
+        assert_eq!(state.exercises.iter().map(|e| e.target_sets).collect::<Vec<_>>(), vec![3, 3]);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn stale_sessions_are_closed_at_their_last_set_or_discarded(pool: SqlitePool) {
+        seed(&pool).await;
+        sqlx::query!(
+            "INSERT INTO completed_sets (id, active_workout_id, exercise_id, set_number, weight, reps, completed_at, created_at)
+             VALUES ('cs-1', 'a-1', 'ex-squat', 1, 100.0, 5, '2026-03-01T18:40:00+00:00', '2026-03-01T18:40:00+00:00')"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query!(
+            "INSERT INTO active_workouts (id, user_id, workout_id, started_at, created_at)
+             VALUES ('a-2', 'u-1', NULL, '2026-03-02T18:00:00+00:00', '2026-03-02T18:00:00+00:00')"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let now = parse_timestamp("2026-03-02T20:00:00+00:00").unwrap();
+        let closed = close_stale_sessions(&pool, chrono::Duration::hours(12), now).await.unwrap();
+        assert_eq!(closed, 1, "a-2 is only two hours old");
+
+        let session = sqlx::query!(
+            r#"SELECT completed_at, total_duration_minutes as "minutes: i32", auto_closed as "auto_closed: bool"
+               FROM completed_workouts WHERE id = 'a-1'"#
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(session.completed_at, "2026-03-01T18:40:00+00:00");
+        assert_eq!(session.minutes, 40);
+        assert!(session.auto_closed);
+
+        let now = parse_timestamp("2026-03-03T08:00:00+00:00").unwrap();
+        assert_eq!(close_stale_sessions(&pool, chrono::Duration::hours(12), now).await.unwrap(), 1);
+        let left = sqlx::query_scalar!("SELECT COUNT(*) FROM active_workouts").fetch_one(&pool).await.unwrap();
+        let sessions = sqlx::query_scalar!("SELECT COUNT(*) FROM completed_workouts").fetch_one(&pool).await.unwrap();
+        assert_eq!((left, sessions), (0, 1), "the empty session is discarded, not kept");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn one_failing_session_does_not_stop_the_sweep(pool: SqlitePool) {
+        seed(&pool).await;
+        sqlx::query!(
+            "INSERT INTO completed_sets (id, active_workout_id, exercise_id, set_number, weight, reps, completed_at, created_at)
+             VALUES ('cs-1', 'a-1', 'ex-squat', 1, 100.0, 5, '2026-03-01T18:40:00+00:00', '2026-03-01T18:40:00+00:00')"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        // Älter als a-1 und damit zuerst dran: gehört einem Nutzer, den es
+        // nicht mehr gibt, und scheitert beim Schließen.
+        let mut conn = pool.acquire().await.unwrap();
+        sqlx::query("PRAGMA foreign_keys = OFF").execute(&mut *conn).await.unwrap();
+        sqlx::query!(
+            "INSERT INTO active_workouts (id, user_id, workout_id, started_at, created_at)
+             VALUES ('a-ghost', 'u-gone', NULL, '2026-02-01T18:00:00+00:00', '2026-02-01T18:00:00+00:00')"
+        )
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+        sqlx::query("PRAGMA foreign_keys = ON").execute(&mut *conn).await.unwrap();
+        drop(conn);
+        sqlx::query!(
+            "INSERT INTO completed_sets (id, active_workout_id, exercise_id, set_number, weight, reps, completed_at, created_at)
+             VALUES ('cs-ghost', 'a-ghost', 'ex-squat', 1, 60.0, 5, '2026-02-01T18:10:00+00:00', '2026-02-01T18:10:00+00:00')"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let now = parse_timestamp("2026-03-03T08:00:00+00:00").unwrap();
+        assert_eq!(close_stale_sessions(&pool, chrono::Duration::hours(12), now).await.unwrap(), 1);
+        let closed = sqlx::query_scalar!("SELECT COUNT(*) FROM completed_workouts WHERE id = 'a-1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(closed, 1, "a-1 is closed although a-ghost failed before it");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn discarding_removes_sets_and_their_records(pool: SqlitePool) {
+        seed(&pool).await;
+        sqlx::query!(
+            "INSERT INTO completed_sets (id, active_workout_id, exercise_id, set_number, weight, reps, completed_at, created_at)
+             VALUES ('cs-1', 'a-1', 'ex-squat', 1, 100.0, 5, '2026-03-01T18:40:00+00:00', '2026-03-01T18:40:00+00:00')"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        recompute_prs(&mut conn, "u-1", "ex-squat").await.unwrap();
+        drop(conn);
+
+        assert!(discard_training(Path("a-1".to_string()), State(pool.clone()), logged_in("u-1").await).await.is_ok());
+
+        let sets = sqlx::query_scalar!("SELECT COUNT(*) FROM completed_sets").fetch_one(&pool).await.unwrap();
+        let records = sqlx::query_scalar!("SELECT COUNT(*) FROM personal_records").fetch_one(&pool).await.unwrap();
+        let active = sqlx::query_scalar!("SELECT COUNT(*) FROM active_workouts").fetch_one(&pool).await.unwrap();
+        assert_eq!((sets, records, active), (0, 0, 0));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn other_users_cannot_pause_resume_or_discard(pool: SqlitePool) {
+        seed(&pool).await;
+        sqlx::query!("INSERT INTO users (id, name, created_at) VALUES ('u-2', 'Ben', '2026-01-01T00:00:00Z')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query!(
+            "INSERT INTO completed_sets (id, active_workout_id, exercise_id, set_number, weight, reps, completed_at, created_at)
+             VALUES ('cs-1', 'a-1', 'ex-squat', 1, 100.0, 5, '2026-03-01T18:40:00+00:00', '2026-03-01T18:40:00+00:00')"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let id = || Path("a-1".to_string());
+
+        let discarded = discard_training(id(), State(pool.clone()), logged_in("u-2").await).await;
+        assert!(matches!(discarded, Err(AppError::NotFound(_))));
+        let discarded = discard_training(id(), State(pool.clone()), session()).await;
+        assert!(matches!(discarded, Err(AppError::Unauthorized)));
+        let paused = pause_training(id(), State(pool.clone()), logged_in("u-2").await).await;
+        assert!(matches!(paused, Err(AppError::NotFound(_))));
+        let resumed = resume_training(id(), State(pool.clone()), logged_in("u-2").await).await;
+        assert!(matches!(resumed, Err(AppError::NotFound(_))));
+
+        let sets = sqlx::query_scalar!("SELECT COUNT(*) FROM completed_sets").fetch_one(&pool).await.unwrap();
+        let session = find_active_workout(&pool, "a-1", "u-1").await.unwrap();
+        assert_eq!(sets, 1);
+        assert!(!session.is_paused());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn a_pause_ends_once_when_resumed_twice_or_by_a_set(pool: SqlitePool) {
+        seed(&pool).await;
+        let id = || Path("a-1".to_string());
+        let pause = || async { pause_training(id(), State(pool.clone()), logged_in("u-1").await).await.is_ok() };
+        let resume = || async { resume_training(id(), State(pool.clone()), logged_in("u-1").await).await.is_ok() };
+
+        assert!(pause().await);
+        let started = find_active_workout(&pool, "a-1", "u-1").await.unwrap().paused_at;
+        assert!(pause().await);
+        assert_eq!(find_active_workout(&pool, "a-1", "u-1").await.unwrap().paused_at, started);
+        let (first, second) = tokio::join!(resume(), resume());
+        assert!(first && second);
+
+        assert!(pause().await);
+        let (resumed, logged) =
+            tokio::join!(resume(), log_set(&pool, "a-1", squat_set("req-1"), WeightUnit::Kg));
+        assert!(resumed && logged.is_ok());
+
+        let pauses = sqlx::query_scalar!("SELECT COUNT(*) FROM session_pauses WHERE session_id = 'a-1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(pauses, 2);
+        assert!(!find_active_workout(&pool, "a-1", "u-1").await.unwrap().is_paused());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn finishing_measures_time_between_sets(pool: SqlitePool) {
+        seed(&pool).await;
+        for (id, exercise_id, at) in [
+            ("cs-1", "ex-squat", "2026-03-01T18:10:00+00:00"),
+            ("cs-2", "ex-squat", "2026-03-01T18:14:00+00:00"),
+            ("cs-3", "ex-bench", "2026-03-01T18:20:00+00:00"),
+        ] {
+            sqlx::query!(
+                "INSERT INTO completed_sets (id, active_workout_id, exercise_id, set_number, weight, reps, completed_at, created_at)
+                 VALUES (?, 'a-1', ?, 1, 100.0, 5, ?, ?)",
+                id,
+                exercise_id,
+                at,
+                at
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        let ended_at = parse_timestamp("2026-03-01T19:30:00+00:00").unwrap();
+        let completed = close_session(&pool, "a-1", "u-1", None, ended_at, false).await.unwrap().unwrap();
+
+        assert_eq!(completed.total_duration_minutes, 10, "first to last set, not start to finish");
+        let times: Vec<ExerciseTime> =
+            serde_json::from_str(completed.exercise_times.as_deref().unwrap()).unwrap();
+        assert_eq!(times.iter().map(|t| t.seconds).collect::<Vec<_>>(), vec![240, 360]);
+    }
+
+    fn session() -> Session {
+        Session::new(None, std::sync::Arc::new(tower_sessions::MemoryStore::default()), None)
+    }
+
+    async fn logged_in(user_id: &str) -> Session {
+        let session = session();
+        session.insert("current_user_id", user_id).await.unwrap();
+        session
+    }
+
+    fn squat_set(client_request_id: &str) -> CompleteSetForm {
+        CompleteSetForm {
+            exercise_id: "ex-squat".to_string(),
+            weight: Some(100.0),
+            reps: 5,
+            notes: None,
+            effort: None,
+            effort_scale: None,
+            set_type: None,
+            client_request_id: Some(client_request_id.to_string()),
+            logged_at: Some("2026-03-01T18:10:00Z".to_string()),
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn replayed_sets_are_logged_once_at_their_original_time(pool: SqlitePool) {
+        seed(&pool).await;
+        for _ in 0..2 {
+            let session = logged_in("u-1").await;
+            let response =
+                complete_set(Path("a-1".to_string()), State(pool.clone()), session, Form(squat_set("req-1"))).await;
+            assert!(response.is_ok());
+        }
+
+        let logged = sqlx::query_scalar!("SELECT completed_at FROM completed_sets WHERE active_workout_id = 'a-1'")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(logged, vec!["2026-03-01T18:10:00+00:00".to_string()]);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn sets_fill_the_first_entry_of_an_exercise_before_the_next(pool: SqlitePool) {
+        seed(&pool).await;
+        sqlx::query!(
+            "INSERT INTO workout_exercises (id, workout_id, exercise_id, position, target_sets, created_at)
+             VALUES ('we-3', 'w-1', 'ex-squat', 3, 2, '2026-01-01T00:00:00Z')"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        for number in 1..=6 {
+            log_set(&pool, "a-1", squat_set(&format!("req-{number}")), WeightUnit::Kg).await.unwrap();
+        }
+
+        let positions = sqlx::query_scalar!(
+            r#"SELECT position as "position: i32" FROM completed_sets WHERE active_workout_id = 'a-1' ORDER BY set_number"#
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        // Drei für die erste Zeile, zwei für die zweite, der Rest ist extra.
+        assert_eq!(positions, vec![Some(1), Some(1), Some(1), Some(3), Some(3), Some(3)]);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn parallel_submissions_get_distinct_numbers_or_collapse(pool: SqlitePool) {
+        seed(&pool).await;
+        let submit = |key: &'static str| {
+            let pool = pool.clone();
+            async move {
+                let session = logged_in("u-1").await;
+                complete_set(Path("a-1".to_string()), State(pool), session, Form(squat_set(key))).await
+            }
+        };
+
+        // Zwei verschiedene Sätze gleichzeitig: beide landen, fortlaufend nummeriert.
+        let (first, second) = tokio::join!(submit("req-1"), submit("req-2"));
+        assert!(first.is_ok() && second.is_ok());
+        // Doppeltipp: derselbe Schlüssel zweimal gleichzeitig, ein Satz.
+        let (first, second) = tokio::join!(submit("req-3"), submit("req-3"));
+        assert!(first.is_ok() && second.is_ok());
+
+        let numbers = sqlx::query_scalar!(
+            "SELECT set_number FROM completed_sets WHERE active_workout_id = 'a-1' ORDER BY set_number"
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(numbers, vec![1, 2, 3]);
+        let records = sqlx::query_scalar!("SELECT COUNT(*) FROM personal_records WHERE exercise_id = 'ex-squat'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(records, 3, "one row per record type, not one per set");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn finishing_happens_once_for_the_owner_of_a_non_empty_session(pool: SqlitePool) {
+        seed(&pool).await;
+        sqlx::query!("INSERT INTO users (id, name, created_at) VALUES ('u-2', 'Ben', '2026-01-01T00:00:00Z')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let finish = |session: Session| {
+            finish_training(
+                Path("a-1".to_string()),
+                State(pool.clone()),
+                session,
+                Form(FinishTrainingForm { notes: None }),
+            )
+        };
+        let counts = || async {
+            let active = sqlx::query_scalar!("SELECT COUNT(*) FROM active_workouts WHERE id = 'a-1'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            let completed = sqlx::query_scalar!("SELECT COUNT(*) FROM completed_workouts WHERE id = 'a-1'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            (active, completed)
+        };
+
+        assert!(matches!(
+            finish(logged_in("u-1").await).await,
+            Err(AppError::BadRequest(_))
+        ));
+        complete_set(Path("a-1".to_string()), State(pool.clone()), logged_in("u-1").await, Form(squat_set("req-1")))
+            .await
+            .unwrap();
+        assert!(matches!(finish(logged_in("u-2").await).await, Err(AppError::NotFound(_))));
+        assert_eq!(counts().await, (1, 0));
+
+        let (first, second) = tokio::join!(finish(logged_in("u-1").await), finish(logged_in("u-1").await));
+        assert!(first.is_ok() && second.is_ok());
+        assert_eq!(counts().await, (0, 1));
+        let again = finish(logged_in("u-1").await).await.unwrap().into_response();
+        assert_eq!(again.headers()["HX-Redirect"], "/history/a-1");
+    }
+
+    fn drain(receiver: &mut tokio::sync::broadcast::Receiver<LiveEvent>) -> Vec<LiveEvent> {
+        std::iter::from_fn(|| receiver.try_recv().ok()).collect()
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn watchers_follow_sets_rest_and_exercise_changes_until_the_finish(pool: SqlitePool) {
+        seed(&pool).await;
+        // Eigene ID: Der Kanal ist prozessweit, `a-1` nutzen parallele Tests.
+        sqlx::query!(
+            "INSERT INTO active_workouts (id, user_id, workout_id, started_at, created_at)
+             VALUES ('a-watch', 'u-1', 'w-1', '2026-03-01T18:00:00Z', '2026-03-01T18:00:00Z')"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let mut events = live_events::subscribe("a-watch");
+
+        for key in ["req-1", "req-2", "req-3"] {
+            log_set(&pool, "a-watch", squat_set(key), WeightUnit::Kg).await.unwrap();
+        }
+        let received = drain(&mut events);
+        let names: Vec<&str> = received.iter().map(|e| e.name).collect();
+        assert_eq!(
+            names,
+            [
+                "set-completed", "rest-started",
+                "set-completed", "rest-started",
+                "set-completed", "rest-started", "exercise-changed",
+            ]
+        );
+        assert_eq!(received[0].data["set"]["reps"], 5);
+        assert_eq!(received[1].data["seconds"], DEFAULT_REST_SECONDS);
+        assert_eq!(received[6].data["exercise_name"], "Bench");
+
+        // Wiederholter Satz: nichts Neues.
+        log_set(&pool, "a-watch", squat_set("req-3"), WeightUnit::Kg).await.unwrap();
+        assert!(drain(&mut events).is_empty());
+
+        move_session_block(&pool, "a-watch", "u-1", 1, true).await.unwrap();
+        assert_eq!(drain(&mut events), [LiveEvent::exercise_changed(Some((2, "ex-bench", "Bench")))]);
+
+        close_session(&pool, "a-watch", "u-1", None, Utc::now(), false).await.unwrap();
+        assert_eq!(events.recv().await.unwrap(), LiveEvent::finished(false));
+        assert!(events.recv().await.is_err());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn training_events_are_queued_with_what_they_report(pool: SqlitePool) {
+        seed(&pool).await;
+        sqlx::query!(
+            "INSERT INTO webhooks (id, user_id, url, secret, events, created_at)
+             VALUES ('wh-1', 'u-1', 'http://127.0.0.1:9/hook', 'whsec_test', 'set.completed,pr.achieved,workout.finished', '2026-01-01T00:00:00Z')"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let queued = || async {
+            sqlx::query!(r#"SELECT event, COUNT(*) as "count: i64" FROM webhook_deliveries GROUP BY event ORDER BY event"#)
+                .fetch_all(&pool)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|row| (row.event, row.count))
+                .collect::<Vec<_>>()
+        };
+
+        // Erster Satz: drei Rekorde. Die Wiederholung meldet nichts.
+        log_set(&pool, "a-1", squat_set("req-1"), WeightUnit::Kg).await.unwrap();
+        log_set(&pool, "a-1", squat_set("req-1"), WeightUnit::Kg).await.unwrap();
+        assert_eq!(queued().await, [("pr.achieved".to_string(), 3), ("set.completed".to_string(), 1)]);
+
+        // Gescheitertes Loggen hinterlässt keine Zustellung.
+        let mut invalid = squat_set("req-2");
+        invalid.set_type = Some("bogus".to_string());
+        assert!(log_set(&pool, "a-1", invalid, WeightUnit::Kg).await.is_err());
+
+        close_session(&pool, "a-1", "u-1", None, Utc::now(), false).await.unwrap();
+        close_session(&pool, "a-1", "u-1", None, Utc::now(), false).await.unwrap();
+        assert_eq!(
+            queued().await,
+            [
+                ("pr.achieved".to_string(), 3),
+                ("set.completed".to_string(), 1),
+                ("workout.finished".to_string(), 1)
+            ]
+        );
+        let payload = sqlx::query_scalar!("SELECT payload FROM webhook_deliveries WHERE event = 'workout.finished'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(payload["data"]["workout"]["id"], "a-1");
+        assert_eq!(payload["data"]["workout"]["total_sets"], 1);
+    }
 }
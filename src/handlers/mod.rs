@@ -1,6 +1,35 @@
+pub mod account;
+pub mod api;
+pub mod calendar;
+pub mod equipment;
 pub mod exercise;
 pub mod history;
+pub mod history_import;
+pub mod live_stream;
 pub mod live_training;
+pub mod openapi;
 pub mod personal_records;
+pub mod progression;
+pub mod pwa;
+pub mod tokens;
 pub mod users;
+pub mod webhooks;
 pub mod workouts;
+
+use crate::error::AppError;
+use crate::models::User;
+use sqlx::SqlitePool;
+use tower_sessions::Session;
+
+/// Angemeldeter Nutzer der Browser-Sitzung, `None` ohne Anmeldung. Die
+/// JSON-API meldet sich stattdessen mit Token an, siehe `api`.
+pub async fn get_current_user(session: &Session, database_pool: &SqlitePool) -> Result<Option<User>, AppError> {
+    if let Ok(Some(user_id)) = session.get::<String>("current_user_id").await {
+        let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = ?", user_id)
+            .fetch_optional(database_pool)
+            .await?;
+        Ok(user)
+    } else {
+        Ok(None)
+    }
+}
@@ -1,17 +1,27 @@
 use crate::models::CompletedSet;
+use crate::units::WeightUnit;
+use crate::webhooks::{self, WebhookEvent};
 use chrono::Utc;
-use sqlx::SqlitePool;
+use serde_json::json;
+use sqlx::SqliteConnection;
 use uuid::Uuid;
 
-/// Check and update personal records after a set is completed
+/// Check and update personal records after a set is completed. `unit` only
+/// affects the messages, records are stored in kg.
 pub async fn check_and_update_prs(
-    pool: &SqlitePool,
+    conn: &mut SqliteConnection,
     user_id: &str,
     exercise_id: &str,
     completed_set: &CompletedSet,
+    unit: WeightUnit,
 ) -> Result<Vec<String>, sqlx::Error> {
     let mut achieved_prs = Vec::new();
 
+    // Aufwärmsätze sind nie Rekorde.
+    if completed_set.set_type().is_warmup() {
+        return Ok(achieved_prs);
+    }
+
     // Check max weight PR (only for weighted exercises)
     if let Some(weight) = completed_set.weight {
         let current_max = sqlx::query_scalar::<_, Option<f64>>(
@@ -20,7 +30,7 @@ pub async fn check_and_update_prs(
         )
         .bind(user_id)
         .bind(exercise_id)
-        .fetch_optional(pool)
+        .fetch_optional(&mut *conn)
         .await?
         .flatten();
 
@@ -45,10 +55,11 @@ pub async fn check_and_update_prs(
             .bind(&completed_set.id)
             .bind(&now)
             .bind(&now)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
 
-            achieved_prs.push(format!("Max Weight: {}kg", weight));
+            achieved_prs.push(format!("Max Weight: {}", unit.format(weight)));
+            queue_record(conn, user_id, "max_weight", completed_set, &achieved_prs).await?;
         }
     }
 
@@ -59,7 +70,7 @@ pub async fn check_and_update_prs(
     )
     .bind(user_id)
     .bind(exercise_id)
-    .fetch_optional(pool)
+    .fetch_optional(&mut *conn)
     .await?
     .flatten();
 
@@ -85,10 +96,11 @@ pub async fn check_and_update_prs(
         .bind(&completed_set.id)
         .bind(&now)
         .bind(&now)
-        .execute(pool)
+        .execute(&mut *conn)
         .await?;
 
         achieved_prs.push(format!("Max Reps: {}", completed_set.reps));
+        queue_record(conn, user_id, "max_reps", completed_set, &achieved_prs).await?;
     }
 
     // Check max volume PR (only for weighted exercises)
@@ -101,7 +113,7 @@ pub async fn check_and_update_prs(
         )
         .bind(user_id)
         .bind(exercise_id)
-        .fetch_optional(pool)
+        .fetch_optional(&mut *conn)
         .await?
         .flatten();
 
@@ -124,12 +136,120 @@ pub async fn check_and_update_prs(
             .bind(&completed_set.id)
             .bind(&now)
             .bind(&now)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
 
-            achieved_prs.push(format!("Max Volume: {:.1}kg", volume));
+            achieved_prs.push(format!("Max Volume: {}", unit.format(volume as f32)));
+            queue_record(conn, user_id, "max_volume", completed_set, &achieved_prs).await?;
         }
     }
 
     Ok(achieved_prs)
 }
+
+/// `pr.achieved` für die Webhooks des Nutzers, mit dem Anzeigetext des
+/// eben erreichten Rekords. Gewichte in kg.
+async fn queue_record(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+    record_type: &str,
+    completed_set: &CompletedSet,
+    achieved_prs: &[String],
+) -> Result<(), sqlx::Error> {
+    let data = json!({
+        "record_type": record_type,
+        "exercise_id": completed_set.exercise_id,
+        "weight": completed_set.weight,
+        "reps": completed_set.reps,
+        "completed_set_id": completed_set.id,
+        "session_id": completed_set.active_workout_id,
+        "label": achieved_prs.last(),
+    });
+    webhooks::enqueue(conn, user_id, WebhookEvent::PrAchieved, data).await?;
+    Ok(())
+}
+
+/// Rekorde einer Übung aus allen Sätzen des Nutzers neu aufbauen — nach
+/// einem Import, wenn Sätze nicht in zeitlicher Reihenfolge ankommen.
+///
+/// Gleiche Regel wie `check_and_update_prs`: nur wer den bisherigen Wert
+/// übertrifft, wird Rekord, bei Gleichstand bleibt der frühere Satz;
+/// Aufwärmsätze zählen nicht. `achieved_at` ist der Zeitpunkt des Satzes, nicht der Neuberechnung.
+pub async fn recompute_prs(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+    exercise_id: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "DELETE FROM personal_records WHERE user_id = ? AND exercise_id = ?",
+        user_id,
+        exercise_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let sets = sqlx::query!(
+        r#"SELECT cs.id as "id!", cs.weight as "weight: f64", cs.reps as "reps: i64", cs.completed_at
+           FROM completed_sets cs
+           LEFT JOIN active_workouts aw ON cs.active_workout_id = aw.id
+           LEFT JOIN completed_workouts cw ON cs.active_workout_id = cw.id
+           WHERE cs.exercise_id = ? AND (aw.user_id = ? OR cw.user_id = ?)
+             AND cs.set_type != 'warmup'
+           ORDER BY cs.completed_at, cs.set_number"#,
+        exercise_id,
+        user_id,
+        user_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut max_weight: Option<usize> = None;
+    let mut max_reps: Option<usize> = None;
+    let mut max_volume: Option<usize> = None;
+    let volume = |i: usize| sets[i].weight.map(|w| w * sets[i].reps as f64);
+
+    for (i, set) in sets.iter().enumerate() {
+        if let Some(weight) = set.weight {
+            if max_weight.is_none_or(|m| weight > sets[m].weight.unwrap_or(0.0)) {
+                max_weight = Some(i);
+            }
+            if max_volume.is_none_or(|m| volume(i) > volume(m)) {
+                max_volume = Some(i);
+            }
+        }
+        if max_reps.is_none_or(|m| set.reps > sets[m].reps) {
+            max_reps = Some(i);
+        }
+    }
+
+    let now = Utc::now().to_rfc3339();
+    for (record_type, best) in [
+        ("max_weight", max_weight),
+        ("max_reps", max_reps),
+        ("max_volume", max_volume),
+    ] {
+        let Some(i) = best else { continue };
+        let set = &sets[i];
+        let pr_id = Uuid::new_v4().to_string();
+        let volume_kg = volume(i);
+        sqlx::query!(
+            "INSERT INTO personal_records
+             (id, user_id, exercise_id, record_type, weight, reps, volume_kg, completed_set_id, achieved_at, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            pr_id,
+            user_id,
+            exercise_id,
+            record_type,
+            set.weight,
+            set.reps,
+            volume_kg,
+            set.id,
+            set.completed_at,
+            now
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
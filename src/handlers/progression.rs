@@ -0,0 +1,856 @@
+use crate::error::AppError;
+use crate::handlers::get_current_user;
+use crate::models::{User, WorkoutExerciseDetail};
+use crate::plates::Equipment;
+use crate::progression::{
+    Decision, HISTORY_SESSIONS, LoggedSet, PlanChange, Prescription, SessionLog, Strategy, Target,
+    next_target, plan_change,
+};
+use crate::units::WeightUnit;
+use askama::Template;
+use axum::{
+    Router,
+    extract::{Path, State},
+    http::{HeaderMap, HeaderValue},
+    response::{Html, IntoResponse},
+    routing::{get, post},
+};
+use chrono::Utc;
+use sqlx::{SqliteConnection, SqlitePool};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+#[derive(Template)]
+#[template(path = "progression/review.html")]
+pub struct TargetReviewTemplate {
+    pub current_user: Option<User>,
+    pub completed_workout_id: String,
+    pub workout_id: String,
+    pub workout_name: String,
+    pub changes: Vec<ProposedChange>,
+    pub unit: WeightUnit,
+    pub is_dashboard: bool,
+}
+
+/// Vorgeschlagene Änderung an einer Übung im Plan, mit dem Stand davor.
+#[derive(Debug, Clone)]
+pub struct ProposedChange {
+    /// Zeile im Plan; dieselbe Übung kann mehrfach darin stehen.
+    pub workout_exercise_id: String,
+    pub exercise_id: String,
+    pub exercise_name: String,
+    pub old_weight_kg: Option<f64>,
+    pub old_reps: Option<(i32, i32)>,
+    pub change: PlanChange,
+    pub target: Target,
+}
+
+impl ProposedChange {
+    fn new_reps(&self) -> Option<(i32, i32)> {
+        self.change.reps.or(self.old_reps)
+    }
+
+    pub fn old_display(&self, unit: WeightUnit) -> String {
+        plan_display(self.old_weight_kg, self.old_reps, unit)
+    }
+
+    pub fn new_display(&self, unit: WeightUnit) -> String {
+        plan_display(self.change.weight_kg, self.new_reps(), unit)
+    }
+}
+
+/// Eintrag aus `target_changes` für den Verlauf auf der Planseite.
+#[derive(Debug, Clone)]
+pub struct TargetChangeEntry {
+    pub exercise_name: String,
+    pub decision: String,
+    pub old_weight: Option<f64>,
+    pub new_weight: Option<f64>,
+    pub old_reps_min: Option<i32>,
+    pub old_reps_max: Option<i32>,
+    pub new_reps_min: Option<i32>,
+    pub new_reps_max: Option<i32>,
+    /// `applied` oder `rejected`.
+    pub status: String,
+    pub created_at: String,
+}
+
+impl TargetChangeEntry {
+    pub fn date_display(&self) -> String {
+        chrono::DateTime::parse_from_rfc3339(&self.created_at)
+            .map(|dt| dt.format("%d.%m.%Y").to_string())
+            .unwrap_or_else(|_| self.created_at.clone())
+    }
+
+    pub fn is_deload(&self) -> bool {
+        self.decision == Decision::Deload.as_str()
+    }
+
+    pub fn is_rejected(&self) -> bool {
+        self.status == "rejected"
+    }
+
+    pub fn old_display(&self, unit: WeightUnit) -> String {
+        plan_display(self.old_weight, range(self.old_reps_min, self.old_reps_max), unit)
+    }
+
+    pub fn new_display(&self, unit: WeightUnit) -> String {
+        plan_display(self.new_weight, range(self.new_reps_min, self.new_reps_max), unit)
+    }
+}
+
+fn range(min: Option<i32>, max: Option<i32>) -> Option<(i32, i32)> {
+    match (min, max) {
+        (Some(min), Some(max)) => Some((min, max)),
+        (Some(reps), None) | (None, Some(reps)) => Some((reps, reps)),
+        (None, None) => None,
+    }
+}
+
+/// `80kg × 8–12`, `Bodyweight × 10`, `80kg`.
+fn plan_display(weight_kg: Option<f64>, reps: Option<(i32, i32)>, unit: WeightUnit) -> String {
+    let weight = match weight_kg {
+        Some(kg) => unit.format(kg as f32),
+        None => "Bodyweight".to_string(),
+    };
+    match reps {
+        Some((min, max)) if min != max => format!("{weight} × {min}–{max}"),
+        Some((reps, _)) => format!("{weight} × {reps}"),
+        None => weight,
+    }
+}
+
+/// Die letzten `HISTORY_SESSIONS` Trainings dieser Zeile im Plan, neueste
+/// zuerst. Nur Trainings nach diesem Plan und nur Sätze an dieser Position:
+/// Steht die Übung zweimal im Plan oder in zwei Plänen, zählt jede Zeile
+/// für sich. Sätze ohne Position gehören zur ersten Zeile mit der Übung.
+pub async fn load_history(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+    workout_id: &str,
+    position: i32,
+    exercise_id: &str,
+) -> Result<Vec<SessionLog>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"WITH entry_sets AS (
+               SELECT cs.active_workout_id as session_id, cw.completed_at, cs.set_number, cs.weight, cs.reps, cs.rpe
+               FROM completed_sets cs
+               INNER JOIN completed_workouts cw ON cw.id = cs.active_workout_id
+               WHERE cw.user_id = ? AND cw.workout_id = ? AND cs.exercise_id = ? AND cs.set_type != 'warmup'
+                 AND COALESCE(cs.position, (
+                     SELECT MIN(first.position) FROM workout_exercises first
+                     WHERE first.workout_id = cw.workout_id AND first.exercise_id = cs.exercise_id)) = ?)
+           SELECT session_id as "session_id!", weight as "weight: f64", reps as "reps!: i32", rpe as "rpe: f64"
+           FROM entry_sets
+           WHERE session_id IN (
+               SELECT session_id FROM entry_sets
+               GROUP BY session_id
+               ORDER BY MAX(completed_at) DESC
+               LIMIT ?)
+           ORDER BY completed_at DESC, set_number ASC"#,
+        user_id,
+        workout_id,
+        exercise_id,
+        position,
+        HISTORY_SESSIONS
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut history: Vec<SessionLog> = Vec::new();
+    let mut current: Option<String> = None;
+    for row in rows {
+        if current.as_deref() != Some(row.session_id.as_str()) {
+            history.push(SessionLog::default());
+            current = Some(row.session_id);
+        }
+        if let Some(log) = history.last_mut() {
+            log.sets.push(LoggedSet { weight_kg: row.weight, reps: row.reps, rpe: row.rpe });
+        }
+    }
+    Ok(history)
+}
+
+/// Ziele der Zeilen im Plan neu berechnen, die in diesem Training
+/// Arbeitssätze haben. Läuft nach jedem abgeschlossenen Training; übersprungene
+/// oder ersetzte Übungen behalten ihr Ziel und damit auch eine schon
+/// abgelehnte Änderung.
+pub async fn update_targets(
+    conn: &mut SqliteConnection,
+    user: &User,
+    equipment: &Equipment,
+    workout_id: &str,
+    completed_workout_id: &str,
+) -> Result<(), sqlx::Error> {
+    let exercises = sqlx::query!(
+        r#"SELECT
+            we.id as "id!",
+            we.position as "position: i32",
+            we.target_sets as "target_sets: i32",
+            we.target_weight as "target_weight: f32",
+            we.target_reps_min as "target_reps_min: i32",
+            we.target_reps_max as "target_reps_max: i32",
+            we.rest_seconds as "rest_seconds: i32",
+            we.notes,
+            we.progression,
+            we.group_label,
+            e.id as exercise_id,
+            e.name as exercise_name,
+            e.instructions as exercise_instructions,
+            e.video_url as exercise_video_url
+           FROM workout_exercises we
+           INNER JOIN exercises e ON we.exercise_id = e.id
+           WHERE we.workout_id = ?
+           ORDER BY we.position ASC"#,
+        workout_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let unit = user.unit();
+    let increment_kg = unit.to_kg(unit.increment());
+    let now = Utc::now().to_rfc3339();
+    for row in exercises {
+        let exercise = WorkoutExerciseDetail {
+            position: row.position,
+            target_sets: row.target_sets,
+            target_weight: row.target_weight,
+            target_reps_min: row.target_reps_min,
+            target_reps_max: row.target_reps_max,
+            rest_seconds: row.rest_seconds,
+            notes: row.notes,
+            progression: row.progression,
+            group_label: row.group_label,
+            exercise_id: row.exercise_id,
+            exercise_name: row.exercise_name,
+            exercise_instructions: row.exercise_instructions,
+            exercise_video_url: row.exercise_video_url,
+        };
+        let trained = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM completed_sets cs
+             WHERE cs.active_workout_id = ? AND cs.exercise_id = ? AND cs.set_type != 'warmup'
+               AND COALESCE(cs.position, (
+                   SELECT MIN(first.position) FROM workout_exercises first
+                   WHERE first.workout_id = ? AND first.exercise_id = cs.exercise_id)) = ?",
+            completed_workout_id,
+            exercise.exercise_id,
+            workout_id,
+            exercise.position
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+        if trained == 0 {
+            continue;
+        }
+        let history = load_history(conn, &user.id, workout_id, exercise.position, &exercise.exercise_id).await?;
+        let target = next_target(
+            exercise.strategy(),
+            &exercise.prescription(),
+            &history,
+            increment_kg,
+            |kg| equipment.nearest_loadable_kg(kg),
+        );
+
+        let strategy = target.strategy.as_str();
+        let decision = target.decision.as_str();
+        sqlx::query!(
+            "INSERT INTO progression_targets
+                (workout_exercise_id, workout_id, exercise_id, strategy, decision, target_weight, target_sets,
+                 target_reps_min, target_reps_max, basis_weight, top_of_range, completed_workout_id, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(workout_exercise_id) DO UPDATE SET
+                exercise_id = excluded.exercise_id,
+                strategy = excluded.strategy,
+                decision = excluded.decision,
+                target_weight = excluded.target_weight,
+                target_sets = excluded.target_sets,
+                target_reps_min = excluded.target_reps_min,
+                target_reps_max = excluded.target_reps_max,
+                basis_weight = excluded.basis_weight,
+                top_of_range = excluded.top_of_range,
+                completed_workout_id = excluded.completed_workout_id,
+                created_at = excluded.created_at",
+            row.id,
+            workout_id,
+            exercise.exercise_id,
+            strategy,
+            decision,
+            target.weight_kg,
+            target.sets,
+            target.reps_min,
+            target.reps_max,
+            target.basis_kg,
+            target.top_of_range,
+            completed_workout_id,
+            now
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Gespeichertes Ziel für die Übung an `position` im Plan, `None` vor dem
+/// ersten abgeschlossenen Training. Ist dort gerade eine andere Übung
+/// eingesetzt, gilt das Ziel nicht für sie.
+pub async fn load_target(
+    pool: &SqlitePool,
+    workout_id: &str,
+    position: i32,
+    exercise_id: &str,
+) -> Result<Option<Target>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"SELECT pt.strategy, pt.decision, pt.target_weight as "target_weight: f64",
+                  pt.target_sets as "target_sets: i32", pt.target_reps_min as "target_reps_min: i32",
+                  pt.target_reps_max as "target_reps_max: i32", pt.basis_weight as "basis_weight: f64",
+                  pt.top_of_range as "top_of_range: bool"
+           FROM progression_targets pt
+           INNER JOIN workout_exercises we ON we.id = pt.workout_exercise_id
+           WHERE we.workout_id = ? AND we.position = ? AND pt.exercise_id = ?"#,
+        workout_id,
+        position,
+        exercise_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| Target {
+        strategy: Strategy::from_db(&row.strategy),
+        decision: Decision::parse(&row.decision).unwrap_or(Decision::Start),
+        weight_kg: row.target_weight,
+        sets: row.target_sets,
+        reps_min: row.target_reps_min,
+        reps_max: row.target_reps_max,
+        basis_kg: row.basis_weight,
+        top_of_range: row.top_of_range,
+    }))
+}
+
+/// Offene Vorschläge aus den Zielen, die nach diesem Training berechnet
+/// wurden. Was schon im Plan steht oder abgelehnt wurde, ist kein Vorschlag
+/// mehr — so lässt sich die Seite gefahrlos erneut öffnen.
+pub async fn pending_changes(
+    pool: &SqlitePool,
+    workout_id: &str,
+    completed_workout_id: &str,
+) -> Result<Vec<ProposedChange>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"SELECT pt.workout_exercise_id, pt.exercise_id, e.name as exercise_name, pt.strategy, pt.decision,
+                  pt.target_weight as "target_weight: f64", pt.target_sets as "target_sets: i32",
+                  pt.target_reps_min as "target_reps_min: i32", pt.target_reps_max as "target_reps_max: i32",
+                  pt.basis_weight as "basis_weight: f64", pt.top_of_range as "top_of_range: bool",
+                  we.target_sets as "plan_sets: i32", we.target_weight as "plan_weight: f64",
+                  we.target_reps_min as "plan_reps_min: i32", we.target_reps_max as "plan_reps_max: i32"
+           FROM progression_targets pt
+           INNER JOIN workout_exercises we ON we.id = pt.workout_exercise_id
+           INNER JOIN exercises e ON e.id = pt.exercise_id
+           WHERE pt.workout_id = ? AND pt.completed_workout_id = ?
+             AND NOT EXISTS (
+                 SELECT 1 FROM target_changes tc
+                 WHERE tc.workout_exercise_id = pt.workout_exercise_id
+                   AND tc.completed_workout_id = pt.completed_workout_id)
+           ORDER BY we.position ASC"#,
+        workout_id,
+        completed_workout_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let target = Target {
+                strategy: Strategy::from_db(&row.strategy),
+                decision: Decision::parse(&row.decision).unwrap_or(Decision::Start),
+                weight_kg: row.target_weight,
+                sets: row.target_sets,
+                reps_min: row.target_reps_min,
+                reps_max: row.target_reps_max,
+                basis_kg: row.basis_weight,
+                top_of_range: row.top_of_range,
+            };
+            let plan = Prescription::new(row.plan_sets, row.plan_reps_min, row.plan_reps_max, row.plan_weight);
+            let change = plan_change(&plan, &target)?;
+            Some(ProposedChange {
+                workout_exercise_id: row.workout_exercise_id,
+                exercise_id: row.exercise_id,
+                exercise_name: row.exercise_name,
+                old_weight_kg: row.plan_weight,
+                old_reps: range(row.plan_reps_min, row.plan_reps_max),
+                change,
+                target,
+            })
+        })
+        .collect())
+}
+
+/// Vorschläge in den Plan schreiben und jeden einzeln protokollieren.
+pub async fn apply_changes(
+    conn: &mut SqliteConnection,
+    workout_id: &str,
+    completed_workout_id: &str,
+    changes: &[ProposedChange],
+) -> Result<(), sqlx::Error> {
+    for proposed in changes {
+        let (new_min, new_max) = split_range(proposed.new_reps());
+        sqlx::query!(
+            "UPDATE workout_exercises SET target_weight = ?, target_reps_min = ?, target_reps_max = ?
+             WHERE id = ? AND workout_id = ?",
+            proposed.change.weight_kg,
+            new_min,
+            new_max,
+            proposed.workout_exercise_id,
+            workout_id
+        )
+        .execute(&mut *conn)
+        .await?;
+        log_change(conn, workout_id, completed_workout_id, proposed, "applied").await?;
+    }
+    Ok(())
+}
+
+/// Vorschläge ablehnen: der Plan bleibt, protokolliert wird trotzdem, damit
+/// der Verlauf zeigt, was vorgeschlagen war.
+pub async fn reject_changes(
+    conn: &mut SqliteConnection,
+    workout_id: &str,
+    completed_workout_id: &str,
+    changes: &[ProposedChange],
+) -> Result<(), sqlx::Error> {
+    for proposed in changes {
+        log_change(conn, workout_id, completed_workout_id, proposed, "rejected").await?;
+    }
+    Ok(())
+}
+
+fn split_range(reps: Option<(i32, i32)>) -> (Option<i32>, Option<i32>) {
+    match reps {
+        Some((min, max)) => (Some(min), Some(max)),
+        None => (None, None),
+    }
+}
+
+async fn log_change(
+    conn: &mut SqliteConnection,
+    workout_id: &str,
+    completed_workout_id: &str,
+    proposed: &ProposedChange,
+    status: &str,
+) -> Result<(), sqlx::Error> {
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    let decision = proposed.target.decision.as_str();
+    let (old_min, old_max) = split_range(proposed.old_reps);
+    let (new_min, new_max) = split_range(proposed.new_reps());
+    sqlx::query!(
+        "INSERT INTO target_changes
+            (id, workout_id, workout_exercise_id, exercise_id, completed_workout_id, decision, old_weight,
+             new_weight, old_reps_min, old_reps_max, new_reps_min, new_reps_max, status, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        id,
+        workout_id,
+        proposed.workout_exercise_id,
+        proposed.exercise_id,
+        completed_workout_id,
+        decision,
+        proposed.old_weight_kg,
+        proposed.change.weight_kg,
+        old_min,
+        old_max,
+        new_min,
+        new_max,
+        status,
+        now
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// Letzte Zieländerungen eines Plans, neueste zuerst.
+pub async fn target_history(pool: &SqlitePool, workout_id: &str) -> Result<Vec<TargetChangeEntry>, sqlx::Error> {
+    sqlx::query_as!(
+        TargetChangeEntry,
+        r#"SELECT e.name as exercise_name, tc.decision,
+                  tc.old_weight as "old_weight: f64", tc.new_weight as "new_weight: f64",
+                  tc.old_reps_min as "old_reps_min: i32", tc.old_reps_max as "old_reps_max: i32",
+                  tc.new_reps_min as "new_reps_min: i32", tc.new_reps_max as "new_reps_max: i32",
+                  tc.status, tc.created_at
+           FROM target_changes tc
+           INNER JOIN exercises e ON e.id = tc.exercise_id
+           WHERE tc.workout_id = ?
+           ORDER BY tc.created_at DESC
+           LIMIT 20"#,
+        workout_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Training des Nutzers mit Plan-ID und -Name. Fremde Trainings gibt es nicht.
+async fn find_session(
+    pool: &SqlitePool,
+    user_id: &str,
+    completed_workout_id: &str,
+) -> Result<(String, String), AppError> {
+    let row = sqlx::query!(
+        r#"SELECT cw.workout_id as "workout_id!", w.name as workout_name
+         FROM completed_workouts cw
+         JOIN workouts w ON w.id = cw.workout_id
+         WHERE cw.id = ? AND cw.user_id = ?"#,
+        completed_workout_id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Training not found".to_string()))?;
+    Ok((row.workout_id, row.workout_name))
+}
+
+pub async fn review_targets(
+    Path(completed_workout_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+    let (workout_id, workout_name) =
+        find_session(&database_pool, &current_user.id, &completed_workout_id).await?;
+    let changes = pending_changes(&database_pool, &workout_id, &completed_workout_id).await?;
+
+    let template = TargetReviewTemplate {
+        completed_workout_id,
+        workout_id,
+        workout_name,
+        changes,
+        unit: current_user.unit(),
+        current_user: Some(current_user),
+        is_dashboard: false,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+pub async fn confirm_targets(
+    Path(completed_workout_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+    let (workout_id, _) = find_session(&database_pool, &current_user.id, &completed_workout_id).await?;
+    let changes = pending_changes(&database_pool, &workout_id, &completed_workout_id).await?;
+
+    let mut tx = database_pool.begin().await?;
+    apply_changes(&mut tx, &workout_id, &completed_workout_id, &changes).await?;
+    tx.commit().await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert("HX-Redirect", HeaderValue::from_str(&format!("/workouts/{workout_id}"))?);
+    Ok((headers, Html(format!("Updated {} targets", changes.len()))).into_response())
+}
+
+/// Vorschläge verwerfen; der Plan behält seine Ziele.
+pub async fn reject_targets(
+    Path(completed_workout_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+    let (workout_id, _) = find_session(&database_pool, &current_user.id, &completed_workout_id).await?;
+    let changes = pending_changes(&database_pool, &workout_id, &completed_workout_id).await?;
+
+    let mut tx = database_pool.begin().await?;
+    reject_changes(&mut tx, &workout_id, &completed_workout_id, &changes).await?;
+    tx.commit().await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert("HX-Redirect", HeaderValue::from_static("/dashboard"));
+    Ok((headers, Html(format!("Kept {} current targets", changes.len()))).into_response())
+}
+
+pub fn router() -> Router<SqlitePool> {
+    Router::new()
+        .route("/history/{id}/targets", get(review_targets).post(confirm_targets))
+        .route("/history/{id}/targets/reject", post(reject_targets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn seed(pool: &SqlitePool) -> User {
+        sqlx::query!(
+            "INSERT INTO users (id, name, created_at) VALUES ('u-1', 'Anna', '2026-01-01T00:00:00Z')"
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query!(
+            "INSERT INTO exercises (id, name, instructions, video_url, created_at)
+             VALUES ('ex-squat', 'Squat', 'Runter, hoch.', NULL, '2026-01-01T00:00:00Z')"
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query!(
+            "INSERT INTO workouts (id, user_id, name, description, is_active, schedule_type, schedule_day, created_at, updated_at)
+             VALUES ('w-1', 'u-1', 'Legs', NULL, 1, 'manual', NULL, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')"
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query!(
+            "INSERT INTO workout_exercises (id, workout_id, exercise_id, position, target_sets, target_weight, target_reps_min, target_reps_max, progression, created_at)
+             VALUES ('we-1', 'w-1', 'ex-squat', 1, 3, 100.0, 5, 5, 'linear', '2026-01-01T00:00:00Z')"
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query_as!(User, "SELECT * FROM users WHERE id = 'u-1'")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn log_session(pool: &SqlitePool, id: &str, day: &str, weight: f64, reps: &[i32]) {
+        let at = format!("{day}T18:00:00Z");
+        sqlx::query!(
+            "INSERT INTO completed_workouts (id, user_id, workout_id, started_at, completed_at, total_duration_minutes, total_sets, total_volume_kg, notes, created_at)
+             VALUES (?, 'u-1', 'w-1', ?, ?, 45, 3, 0, NULL, ?)",
+            id, at, at, at
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        for (number, reps) in reps.iter().enumerate() {
+            let set_id = format!("{id}-{number}");
+            let number = number as i32 + 1;
+            sqlx::query!(
+                "INSERT INTO completed_sets (id, active_workout_id, exercise_id, set_number, weight, reps, notes, completed_at, created_at)
+                 VALUES (?, ?, 'ex-squat', ?, ?, ?, NULL, ?, ?)",
+                set_id, id, number, weight, reps, at, at
+            )
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn history_is_grouped_per_session_newest_first(pool: SqlitePool) {
+        seed(&pool).await;
+        log_session(&pool, "s-1", "2026-03-01", 95.0, &[5, 5, 5]).await;
+        log_session(&pool, "s-2", "2026-03-03", 100.0, &[5, 4, 3]).await;
+
+        let history = load_history(&mut pool.acquire().await.unwrap(), "u-1", "w-1", 1, "ex-squat").await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].sets.iter().map(|s| s.reps).collect::<Vec<_>>(), vec![5, 4, 3]);
+        assert_eq!(history[1].sets[0].weight_kg, Some(95.0));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn warm_ups_alone_do_not_make_a_session(pool: SqlitePool) {
+        seed(&pool).await;
+        log_session(&pool, "s-1", "2026-03-01", 100.0, &[5, 5, 5]).await;
+        log_session(&pool, "s-2", "2026-03-03", 60.0, &[5]).await;
+        sqlx::query!("UPDATE completed_sets SET set_type = 'warmup' WHERE active_workout_id = 's-2'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let history = load_history(&mut pool.acquire().await.unwrap(), "u-1", "w-1", 1, "ex-squat").await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].sets[0].weight_kg, Some(100.0));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn finished_training_stores_the_next_target(pool: SqlitePool) {
+        let user = seed(&pool).await;
+        log_session(&pool, "s-1", "2026-03-01", 100.0, &[5, 5, 5]).await;
+
+        let equipment = Equipment::default_for(user.unit());
+        update_targets(&mut pool.acquire().await.unwrap(), &user, &equipment, "w-1", "s-1").await.unwrap();
+
+        let target = load_target(&pool, "w-1", 1, "ex-squat").await.unwrap().unwrap();
+        assert_eq!(target.strategy, Strategy::Linear);
+        assert_eq!(target.decision, Decision::Increase);
+        assert_eq!(target.weight_kg, Some(102.5));
+        assert_eq!(target.basis_kg, Some(100.0));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn confirmed_changes_update_the_plan_and_are_logged(pool: SqlitePool) {
+        let user = seed(&pool).await;
+        log_session(&pool, "s-1", "2026-03-01", 100.0, &[5, 5, 5]).await;
+        let equipment = Equipment::default_for(user.unit());
+        update_targets(&mut pool.acquire().await.unwrap(), &user, &equipment, "w-1", "s-1").await.unwrap();
+
+        let changes = pending_changes(&pool, "w-1", "s-1").await.unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].old_display(WeightUnit::Kg), "100kg × 5");
+        assert_eq!(changes[0].new_display(WeightUnit::Kg), "102.5kg × 5");
+
+        let mut conn = pool.acquire().await.unwrap();
+        apply_changes(&mut conn, "w-1", "s-1", &changes).await.unwrap();
+        drop(conn);
+
+        let planned = sqlx::query_scalar!(
+            r#"SELECT target_weight as "target_weight: f64" FROM workout_exercises WHERE id = 'we-1'"#
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(planned, Some(102.5));
+        // Schon übernommen: kein Vorschlag mehr.
+        assert!(pending_changes(&pool, "w-1", "s-1").await.unwrap().is_empty());
+
+        let history = target_history(&pool, "w-1").await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert!(!history[0].is_rejected());
+        assert_eq!(history[0].old_display(WeightUnit::Kg), "100kg × 5");
+        assert_eq!(history[0].new_display(WeightUnit::Kg), "102.5kg × 5");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn rejected_changes_keep_the_plan_and_are_logged(pool: SqlitePool) {
+        let user = seed(&pool).await;
+        log_session(&pool, "s-1", "2026-03-01", 100.0, &[5, 5, 5]).await;
+        let equipment = Equipment::default_for(user.unit());
+        update_targets(&mut pool.acquire().await.unwrap(), &user, &equipment, "w-1", "s-1").await.unwrap();
+
+        let changes = pending_changes(&pool, "w-1", "s-1").await.unwrap();
+        assert_eq!(changes.len(), 1);
+        let mut conn = pool.acquire().await.unwrap();
+        reject_changes(&mut conn, "w-1", "s-1", &changes).await.unwrap();
+        drop(conn);
+
+        let planned = sqlx::query_scalar!(
+            r#"SELECT target_weight as "target_weight: f64" FROM workout_exercises WHERE id = 'we-1'"#
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(planned, Some(100.0));
+        // Entschieden: kein offener Vorschlag mehr.
+        assert!(pending_changes(&pool, "w-1", "s-1").await.unwrap().is_empty());
+
+        let history = target_history(&pool, "w-1").await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert!(history[0].is_rejected());
+        assert_eq!(history[0].new_display(WeightUnit::Kg), "102.5kg × 5");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn the_same_exercise_twice_keeps_two_targets(pool: SqlitePool) {
+        let user = seed(&pool).await;
+        // Zweiter Eintrag: leichte Kniebeugen mit eigenem Ziel.
+        sqlx::query!(
+            "INSERT INTO workout_exercises (id, workout_id, exercise_id, position, target_sets, target_weight, target_reps_min, target_reps_max, progression, created_at)
+             VALUES ('we-2', 'w-1', 'ex-squat', 2, 3, 60.0, 8, 10, 'double', '2026-01-01T00:00:00Z')"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        log_session(&pool, "s-1", "2026-03-01", 100.0, &[5, 5, 5]).await;
+        sqlx::query!("UPDATE completed_sets SET position = 1").execute(&pool).await.unwrap();
+        for number in 1..=3 {
+            let set_id = format!("light-{number}");
+            sqlx::query!(
+                "INSERT INTO completed_sets (id, active_workout_id, exercise_id, set_number, weight, reps, notes, completed_at, created_at, position)
+                 VALUES (?, 's-1', 'ex-squat', ?, 60.0, 8, NULL, '2026-03-01T18:30:00Z', '2026-03-01T18:30:00Z', 2)",
+                set_id,
+                number
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        let equipment = Equipment::default_for(user.unit());
+        update_targets(&mut pool.acquire().await.unwrap(), &user, &equipment, "w-1", "s-1").await.unwrap();
+
+        let heavy = load_target(&pool, "w-1", 1, "ex-squat").await.unwrap().unwrap();
+        let light = load_target(&pool, "w-1", 2, "ex-squat").await.unwrap().unwrap();
+        assert_eq!(heavy.strategy, Strategy::Linear);
+        assert_eq!((heavy.weight_kg, heavy.basis_kg), (Some(102.5), Some(100.0)));
+        assert_eq!(light.strategy, Strategy::Double);
+        assert_eq!(light.basis_kg, Some(60.0), "judged on its own sets, not the heavy ones");
+        assert_eq!(light.weight_kg, Some(60.0));
+
+        // Nur die schwere Zeile ist am oberen Ende; die leichte steht bei 8 von 8–10.
+        let changes = pending_changes(&pool, "w-1", "s-1").await.unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].workout_exercise_id, "we-1");
+        assert_eq!(changes[0].change.weight_kg, Some(102.5));
+        let mut conn = pool.acquire().await.unwrap();
+        apply_changes(&mut conn, "w-1", "s-1", &changes).await.unwrap();
+        drop(conn);
+
+        let planned = sqlx::query!(
+            r#"SELECT id as "id!", target_weight as "target_weight: f64" FROM workout_exercises ORDER BY position"#
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(planned[0].target_weight, Some(102.5));
+        assert_eq!(planned[1].target_weight, Some(60.0), "the other entry keeps its target");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn another_plan_with_the_same_exercise_has_its_own_history(pool: SqlitePool) {
+        seed(&pool).await;
+        sqlx::query!(
+            "INSERT INTO workouts (id, user_id, name, description, is_active, schedule_type, schedule_day, created_at, updated_at)
+             VALUES ('w-2', 'u-1', 'Legs B', NULL, 1, 'manual', NULL, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        log_session(&pool, "s-1", "2026-03-01", 100.0, &[5, 5, 5]).await;
+        log_session(&pool, "s-2", "2026-03-03", 140.0, &[3, 3, 2]).await;
+        sqlx::query!("UPDATE completed_workouts SET workout_id = 'w-2' WHERE id = 's-2'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let history = load_history(&mut pool.acquire().await.unwrap(), "u-1", "w-1", 1, "ex-squat").await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].sets[0].weight_kg, Some(100.0));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn untrained_entries_keep_their_target_and_rejection(pool: SqlitePool) {
+        let user = seed(&pool).await;
+        log_session(&pool, "s-1", "2026-03-01", 100.0, &[5, 5, 5]).await;
+        let equipment = Equipment::default_for(user.unit());
+        update_targets(&mut pool.acquire().await.unwrap(), &user, &equipment, "w-1", "s-1").await.unwrap();
+        let changes = pending_changes(&pool, "w-1", "s-1").await.unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        reject_changes(&mut conn, "w-1", "s-1", &changes).await.unwrap();
+        drop(conn);
+
+        // Nächstes Training ohne Kniebeugen.
+        log_session(&pool, "s-2", "2026-03-03", 100.0, &[]).await;
+        update_targets(&mut pool.acquire().await.unwrap(), &user, &equipment, "w-1", "s-2").await.unwrap();
+        assert!(pending_changes(&pool, "w-1", "s-2").await.unwrap().is_empty());
+        assert!(pending_changes(&pool, "w-1", "s-1").await.unwrap().is_empty());
+        let target = load_target(&pool, "w-1", 1, "ex-squat").await.unwrap().unwrap();
+        assert_eq!(target.weight_kg, Some(102.5));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn holding_the_weight_proposes_nothing(pool: SqlitePool) {
+        let user = seed(&pool).await;
+        log_session(&pool, "s-1", "2026-03-01", 100.0, &[5, 5, 4]).await;
+        let equipment = Equipment::default_for(user.unit());
+        update_targets(&mut pool.acquire().await.unwrap(), &user, &equipment, "w-1", "s-1").await.unwrap();
+
+        assert!(pending_changes(&pool, "w-1", "s-1").await.unwrap().is_empty());
+    }
+}
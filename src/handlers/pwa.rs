@@ -0,0 +1,27 @@
+use axum::{
+    Router,
+    http::{HeaderMap, HeaderValue},
+    response::IntoResponse,
+    routing::get,
+};
+use sqlx::SqlitePool;
+
+/// Im Binary, damit Worker und Server nie auseinanderlaufen.
+const SERVICE_WORKER: &str = include_str!("../../static/js/sw.js");
+
+/// Service Worker unter /sw.js statt /static/js/sw.js: Ein Worker steuert
+/// nur Seiten unterhalb seines eigenen Pfads.
+pub async fn service_worker() -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "Content-Type",
+        HeaderValue::from_static("text/javascript; charset=utf-8"),
+    );
+    // Der Browser prüft sonst erst nach einem Tag auf eine neue Fassung.
+    headers.insert("Cache-Control", HeaderValue::from_static("no-cache"));
+    (headers, SERVICE_WORKER)
+}
+
+pub fn router() -> Router<SqlitePool> {
+    Router::new().route("/sw.js", get(service_worker))
+}
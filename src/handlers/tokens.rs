@@ -0,0 +1,140 @@
+use crate::api_token::{self, TokenScope};
+use crate::error::AppError;
+use crate::handlers::get_current_user;
+use crate::models::ApiToken;
+use askama::Template;
+use axum::{
+    Form, Router,
+    extract::{Path, State},
+    response::{Html, IntoResponse},
+    routing::post,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use tower_sessions::Session;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTokenForm {
+    pub name: String,
+    /// `read` oder `read_write`.
+    pub scope: String,
+}
+
+/// Tokenliste auf der Kontoseite. `new_token` steht nur in der Antwort
+/// auf das Anlegen, danach gibt es das Token nirgends mehr im Klartext.
+#[derive(Template)]
+#[template(path = "users/token_list_partial.html")]
+pub struct TokenListPartialTemplate {
+    pub tokens: Vec<ApiToken>,
+    pub new_token: Option<String>,
+}
+
+/// Legt ein Token an und gibt es einmalig im Klartext zurück. Gespeichert
+/// wird nur der Hash.
+pub async fn create_token(
+    pool: &SqlitePool,
+    user_id: &str,
+    name: &str,
+    scope: TokenScope,
+) -> Result<(ApiToken, String), AppError> {
+    let name = name.trim().to_string();
+    if name.is_empty() || name.chars().count() > 100 {
+        return Err(AppError::BadRequest("Token name must be 1 to 100 characters".to_string()));
+    }
+
+    let token = api_token::generate();
+    let token_hash = api_token::hash(&token);
+    let created = ApiToken {
+        id: Uuid::new_v4().to_string(),
+        user_id: user_id.to_string(),
+        name,
+        scope: scope.as_str().to_string(),
+        created_at: Utc::now().to_rfc3339(),
+        last_used_at: None,
+    };
+    sqlx::query!(
+        "INSERT INTO api_tokens (id, user_id, name, token_hash, scope, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+        created.id,
+        created.user_id,
+        created.name,
+        token_hash,
+        created.scope,
+        created.created_at
+    )
+    .execute(pool)
+    .await?;
+    tracing::info!("API token '{}' ({}) created for user {}", created.name, created.scope, user_id);
+
+    Ok((created, token))
+}
+
+/// Tokens des Nutzers, neueste zuerst.
+pub async fn list_tokens(pool: &SqlitePool, user_id: &str) -> Result<Vec<ApiToken>, sqlx::Error> {
+    sqlx::query_as!(
+        ApiToken,
+        "SELECT id, user_id, name, scope, created_at, last_used_at
+         FROM api_tokens WHERE user_id = ? ORDER BY created_at DESC",
+        user_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Löscht das Token; `false`, wenn es dem Nutzer nicht (mehr) gehört.
+pub async fn revoke_token(pool: &SqlitePool, user_id: &str, token_id: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!("DELETE FROM api_tokens WHERE id = ? AND user_id = ?", token_id, user_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn create(
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Form(form): Form<CreateTokenForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+
+    let scope = TokenScope::parse(&form.scope)
+        .ok_or_else(|| AppError::BadRequest(format!("Unknown token scope '{}'", form.scope)))?;
+    let (_, token) = create_token(&database_pool, &current_user.id, &form.name, scope).await?;
+
+    let template = TokenListPartialTemplate {
+        tokens: list_tokens(&database_pool, &current_user.id).await?,
+        new_token: Some(token),
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+pub async fn revoke(
+    Path(token_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+
+    if !revoke_token(&database_pool, &current_user.id, &token_id).await? {
+        return Err(AppError::NotFound(format!("Token {token_id}")));
+    }
+    tracing::info!("API token {} revoked by user {}", token_id, current_user.id);
+
+    let template = TokenListPartialTemplate {
+        tokens: list_tokens(&database_pool, &current_user.id).await?,
+        new_token: None,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+pub fn router() -> Router<SqlitePool> {
+    Router::new()
+        .route("/users/tokens", post(create))
+        .route("/users/tokens/{id}/revoke", post(revoke))
+}
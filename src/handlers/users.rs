@@ -1,6 +1,13 @@
 use crate::error::AppError;
+use crate::handlers::get_current_user;
+use crate::handlers::calendar::load_calendar_feed;
+use crate::handlers::equipment::load_equipment;
 use crate::handlers::history::CompletedWorkoutWithName;
-use crate::models::{ActiveWorkout, User};
+use crate::handlers::tokens::list_tokens;
+use crate::handlers::webhooks::{WebhookEntry, list_webhooks};
+use crate::models::{ActiveWorkout, ApiToken, CalendarFeed, User};
+use crate::plates::Equipment;
+use crate::units::WeightUnit;
 use askama::Template;
 use axum::{
     Form, Router,
@@ -18,11 +25,32 @@ pub struct CreateUserForm {
     pub name: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct WeightUnitForm {
+    pub weight_unit: String,
+}
+
+/// `on` oder `off`.
+#[derive(Debug, Deserialize)]
+pub struct WarmupRampsForm {
+    pub warmup_ramps: String,
+}
+
 #[derive(Template)]
 #[template(path = "users/list.html")]
 pub struct UserListTemplate {
     pub users: Vec<User>,
     pub current_user: Option<User>,
+    /// Für das Formular zum Scheibenrechner, nur mit angemeldetem Nutzer.
+    pub equipment: Option<Equipment>,
+    /// API-Tokens des angemeldeten Nutzers.
+    pub tokens: Vec<ApiToken>,
+    /// Nur im Partial nach dem Anlegen gesetzt.
+    pub new_token: Option<String>,
+    pub webhooks: Vec<WebhookEntry>,
+    pub new_secret: Option<String>,
+    /// Kalender-Link, falls angelegt.
+    pub calendar: Option<CalendarFeed>,
     pub is_dashboard: bool,
 }
 
@@ -40,6 +68,7 @@ pub struct DashboardTemplate {
     pub recent_workouts: Vec<CompletedWorkoutWithName>,
     pub active_workout: Option<ActiveWorkout>,
     pub stats: DashboardStats,
+    pub unit: WeightUnit,
     pub is_dashboard: bool,
 }
 
@@ -51,17 +80,6 @@ pub struct DashboardStats {
     pub total_volume_kg: f32,
 }
 
-async fn get_current_user(session: &Session, database_pool: &SqlitePool) -> Result<Option<User>, AppError> {
-    if let Ok(Some(user_id)) = session.get::<String>("current_user_id").await {
-        let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = ?", user_id)
-            .fetch_optional(database_pool)
-            .await?;
-        Ok(user)
-    } else {
-        Ok(None)
-    }
-}
-
 pub async fn list_users(
     State(database_pool): State<SqlitePool>,
     session: Session,
@@ -71,10 +89,28 @@ pub async fn list_users(
         .await?;
 
     let current_user = get_current_user(&session, &database_pool).await?;
+    let equipment = match &current_user {
+        Some(user) => Some(load_equipment(&database_pool, user).await?),
+        None => None,
+    };
+    let (tokens, webhooks, calendar) = match &current_user {
+        Some(user) => (
+            list_tokens(&database_pool, &user.id).await?,
+            list_webhooks(&database_pool, &user.id).await?,
+            load_calendar_feed(&database_pool, &user.id).await?,
+        ),
+        None => (Vec::new(), Vec::new(), None),
+    };
 
     let template = UserListTemplate {
         users,
         current_user,
+        equipment,
+        tokens,
+        new_token: None,
+        webhooks,
+        new_secret: None,
+        calendar,
         is_dashboard: false,
     };
     Ok(Html(template.render()?).into_response())
@@ -178,14 +214,15 @@ pub async fn dashboard(
         CompletedWorkoutWithName,
         r#"SELECT
             cw.id as "id!",
-            w.name as "workout_name!",
+            COALESCE(w.name, 'Freestyle') as "workout_name!: String",
             cw.completed_at as "completed_at!",
             cw.total_duration_minutes as "total_duration_minutes: i32",
             cw.total_sets as "total_sets: i32",
             cw.total_volume_kg as "total_volume_kg: f32",
-            cw.notes
+            cw.notes,
+            cw.auto_closed as "auto_closed: bool"
             FROM completed_workouts cw
-            JOIN workouts w ON w.id = cw.workout_id
+            LEFT JOIN workouts w ON w.id = cw.workout_id
             WHERE cw.user_id = ?
             ORDER BY cw.completed_at DESC LIMIT 3"#,
         user.id
@@ -242,6 +279,7 @@ pub async fn dashboard(
     };
 
     let template = DashboardTemplate {
+        unit: user.unit(),
         user: user.clone(),
         current_user: Some(user),
         recent_workouts,
@@ -252,6 +290,64 @@ pub async fn dashboard(
     Ok(Html(template.render()?).into_response())
 }
 
+/// Einheit für Eingabe und Anzeige. Gespeicherte Gewichte bleiben kg, es
+/// wird nichts umgeschrieben.
+pub async fn update_weight_unit(
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Form(form): Form<WeightUnitForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+    let unit = WeightUnit::parse(&form.weight_unit)
+        .ok_or_else(|| AppError::BadRequest(format!("Unknown weight unit '{}'", form.weight_unit)))?;
+    let stored = unit.as_str();
+
+    sqlx::query!(
+        "UPDATE users SET weight_unit = ? WHERE id = ?",
+        stored,
+        current_user.id
+    )
+    .execute(&database_pool)
+    .await?;
+
+    Ok(Html(format!("Weights are now shown in {unit}.")).into_response())
+}
+
+/// Aufwärmrampe im Training ein- oder ausschalten.
+pub async fn update_warmup_ramps(
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Form(form): Form<WarmupRampsForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+    let enabled = match form.warmup_ramps.as_str() {
+        "on" => true,
+        "off" => false,
+        other => return Err(AppError::BadRequest(format!("Unknown warm-up setting '{other}'"))),
+    };
+
+    sqlx::query!(
+        "UPDATE users SET warmup_ramps = ? WHERE id = ?",
+        enabled,
+        current_user.id
+    )
+    .execute(&database_pool)
+    .await?;
+
+    let message = if enabled {
+        "Warm-up ramps are suggested before the first working set."
+    } else {
+        "Warm-up ramps are off."
+    };
+    Ok(Html(message.to_string()).into_response())
+}
+
 pub async fn logout(session: Session) -> Result<impl IntoResponse, AppError> {
     session.flush().await?;
     let mut headers = HeaderMap::new();
@@ -267,6 +363,8 @@ pub fn router() -> Router<SqlitePool> {
         .route("/users", get(list_users))
         .route("/users", post(create_user))
         .route("/users/{id}/select", post(select_user))
+        .route("/users/unit", post(update_weight_unit))
+        .route("/users/warmups", post(update_warmup_ramps))
         .route("/dashboard", get(dashboard))
         .route("/logout", post(logout))
 }
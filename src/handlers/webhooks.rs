@@ -0,0 +1,173 @@
+use crate::error::AppError;
+use crate::handlers::get_current_user;
+use crate::models::Webhook;
+use crate::webhooks::{self, WebhookEvent};
+use askama::Template;
+use axum::{
+    Form, Router,
+    extract::{Path, State},
+    response::{Html, IntoResponse},
+    routing::post,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use tower_sessions::Session;
+use uuid::Uuid;
+
+/// Ein Kästchen je Ereignis, angehakt kommt `on`.
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookForm {
+    pub url: String,
+    pub workout_finished: Option<String>,
+    pub set_completed: Option<String>,
+    pub pr_achieved: Option<String>,
+}
+
+/// Abo mit dem Ergebnis der letzten Zustellung, für die Fehlersuche.
+#[derive(Debug)]
+pub struct WebhookEntry {
+    pub webhook: Webhook,
+    pub last_status: Option<String>,
+    pub last_error: Option<String>,
+}
+
+impl WebhookEntry {
+    pub fn last_delivery(&self) -> String {
+        match (self.last_status.as_deref(), self.last_error.as_deref()) {
+            (None, _) => "no deliveries yet".to_string(),
+            (Some("delivered"), _) => "last delivery ok".to_string(),
+            (Some("failed"), error) => format!("last delivery failed: {}", error.unwrap_or("unknown error")),
+            (Some(_), Some(error)) => format!("retrying: {error}"),
+            (Some(_), None) => "sending".to_string(),
+        }
+    }
+}
+
+/// Das Geheimnis steht nur in der Antwort auf das Anlegen.
+#[derive(Template)]
+#[template(path = "users/webhook_list_partial.html")]
+pub struct WebhookListPartialTemplate {
+    pub webhooks: Vec<WebhookEntry>,
+    pub new_secret: Option<String>,
+}
+
+pub async fn list_webhooks(pool: &SqlitePool, user_id: &str) -> Result<Vec<WebhookEntry>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"SELECT w.id, w.user_id, w.url, w.secret, w.events, w.created_at,
+                  (SELECT d.status FROM webhook_deliveries d WHERE d.webhook_id = w.id
+                   ORDER BY d.created_at DESC LIMIT 1) as "last_status?: String",
+                  (SELECT d.last_error FROM webhook_deliveries d WHERE d.webhook_id = w.id
+                   ORDER BY d.created_at DESC LIMIT 1) as "last_error?: String"
+           FROM webhooks w WHERE w.user_id = ? ORDER BY w.created_at"#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| WebhookEntry {
+            webhook: Webhook {
+                id: row.id,
+                user_id: row.user_id,
+                url: row.url,
+                secret: row.secret,
+                events: row.events,
+                created_at: row.created_at,
+            },
+            last_status: row.last_status,
+            last_error: row.last_error,
+        })
+        .collect())
+}
+
+pub async fn create(
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+    Form(form): Form<CreateWebhookForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+
+    let url = form.url.trim().to_string();
+    if !(url.starts_with("http://") || url.starts_with("https://")) || url.len() > 500 {
+        return Err(AppError::BadRequest("Webhook URL must start with http:// or https://".to_string()));
+    }
+    webhooks::check_url(&url, webhooks::private_targets_allowed()).map_err(AppError::BadRequest)?;
+    let events: Vec<&str> = [
+        (WebhookEvent::WorkoutFinished, &form.workout_finished),
+        (WebhookEvent::SetCompleted, &form.set_completed),
+        (WebhookEvent::PrAchieved, &form.pr_achieved),
+    ]
+    .into_iter()
+    .filter(|(_, checked)| checked.is_some())
+    .map(|(event, _)| event.as_str())
+    .collect();
+    if events.is_empty() {
+        return Err(AppError::BadRequest("Choose at least one event".to_string()));
+    }
+
+    let webhook = Webhook {
+        id: Uuid::new_v4().to_string(),
+        user_id: current_user.id.clone(),
+        url,
+        secret: webhooks::generate_secret(),
+        events: events.join(","),
+        created_at: Utc::now().to_rfc3339(),
+    };
+    sqlx::query!(
+        "INSERT INTO webhooks (id, user_id, url, secret, events, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+        webhook.id,
+        webhook.user_id,
+        webhook.url,
+        webhook.secret,
+        webhook.events,
+        webhook.created_at
+    )
+    .execute(&database_pool)
+    .await?;
+    tracing::info!("webhook to {} ({}) created for user {}", webhook.url, webhook.events, current_user.id);
+
+    let template = WebhookListPartialTemplate {
+        webhooks: list_webhooks(&database_pool, &current_user.id).await?,
+        new_secret: Some(webhook.secret),
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+/// Löscht das Abo samt offener Zustellungen.
+pub async fn delete(
+    Path(webhook_id): Path<String>,
+    State(database_pool): State<SqlitePool>,
+    session: Session,
+) -> Result<impl IntoResponse, AppError> {
+    let current_user = match get_current_user(&session, &database_pool).await? {
+        Some(user) => user,
+        None => return Err(AppError::Unauthorized),
+    };
+
+    let result = sqlx::query!(
+        "DELETE FROM webhooks WHERE id = ? AND user_id = ?",
+        webhook_id,
+        current_user.id
+    )
+    .execute(&database_pool)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Webhook {webhook_id}")));
+    }
+
+    let template = WebhookListPartialTemplate {
+        webhooks: list_webhooks(&database_pool, &current_user.id).await?,
+        new_secret: None,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+pub fn router() -> Router<SqlitePool> {
+    Router::new()
+        .route("/users/webhooks", post(create))
+        .route("/users/webhooks/{id}/delete", post(delete))
+}
@@ -1,6 +1,10 @@
 use crate::error::AppError;
-use crate::models::{AddExerciseToWorkoutForm, CreateWorkoutForm, UpdateWorkoutScheduleForm};
+use crate::handlers::get_current_user;
+use crate::models::{AddExerciseToWorkoutForm, CreateWorkoutForm, UpdateWorkoutScheduleForm, parse_group_label, parse_rep_range};
+use crate::handlers::progression::{TargetChangeEntry, target_history};
 use crate::models::{Exercise, User, Workout, WorkoutExercise, WorkoutExerciseDetail};
+use crate::progression::Strategy;
+use crate::units::WeightUnit;
 use crate::workout_yaml::{PlanExercise, WorkoutPlan, filename_for, from_yaml, to_yaml};
 use askama::Template;
 use axum::{
@@ -27,6 +31,9 @@ pub struct WorkoutDetailTemplate {
     pub workout: Workout,
     pub exercises: Vec<WorkoutExerciseDetail>,
     pub available_exercises: Vec<Exercise>,
+    /// Übernommene Zieländerungen, neueste zuerst.
+    pub target_changes: Vec<TargetChangeEntry>,
+    pub unit: WeightUnit,
     pub current_user: Option<User>,
     pub is_dashboard: bool,
 }
@@ -37,17 +44,6 @@ pub struct WorkoutListPartialTemplate {
     pub workouts: Vec<Workout>,
 }
 
-async fn get_current_user(session: &Session, database_pool: &SqlitePool) -> Result<Option<User>, AppError> {
-    if let Ok(Some(user_id)) = session.get::<String>("current_user_id").await {
-        let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = ?", user_id)
-            .fetch_optional(database_pool)
-            .await?;
-        Ok(user)
-    } else {
-        Ok(None)
-    }
-}
-
 pub async fn list_workouts(
     State(database_pool): State<SqlitePool>,
     session: Session,
@@ -133,7 +129,12 @@ pub async fn show_workout(
             we.position as "position: i32",
             we.target_sets as "target_sets: i32",
             we.target_weight as "target_weight: f32",
+            we.target_reps_min as "target_reps_min: i32",
+            we.target_reps_max as "target_reps_max: i32",
+            we.rest_seconds as "rest_seconds: i32",
             we.notes,
+            we.progression,
+            we.group_label,
             e.id as exercise_id,
             e.name as exercise_name,
             e.instructions as exercise_instructions,
@@ -146,10 +147,14 @@ pub async fn show_workout(
         .fetch_all(&database_pool)
         .await?;
 
+    let target_changes = target_history(&database_pool, &workout.id).await?;
+
     let template = WorkoutDetailTemplate {
         workout,
         exercises,
         available_exercises,
+        target_changes,
+        unit: current_user.as_ref().map(User::unit).unwrap_or_default(),
         current_user,
         is_dashboard: false,
     };
@@ -201,7 +206,12 @@ pub async fn export_workout(
             we.position as "position: i32",
             we.target_sets as "target_sets: i32",
             we.target_weight as "target_weight: f32",
+            we.target_reps_min as "target_reps_min: i32",
+            we.target_reps_max as "target_reps_max: i32",
+            we.rest_seconds as "rest_seconds: i32",
             we.notes,
+            we.progression,
+            we.group_label,
             e.id as exercise_id,
             e.name as exercise_name,
             e.instructions as exercise_instructions,
@@ -210,11 +220,15 @@ pub async fn export_workout(
         workout_id
     ).fetch_all(&database_pool).await?;
 
+    // Gewichte in der Einheit des Nutzers — wer in lb plant, will die Datei
+    // in lb lesen.
+    let unit = current_user.unit();
     let plan = WorkoutPlan {
         name: workout.name.clone(),
         description: workout.description.clone(),
         schedule_type: workout.schedule_type.clone(),
         schedule_day: workout.schedule_day.map(|d| d as i64),
+        unit,
         exercises: exercises
             .into_iter()
             .map(|e| PlanExercise {
@@ -222,8 +236,12 @@ pub async fn export_workout(
                 instructions: Some(e.exercise_instructions),
                 video_url: e.exercise_video_url,
                 sets: e.target_sets as i64,
-                weight: e.target_weight.map(|w| w as f64),
+                weight: e.target_weight.map(|w| unit.convert_kg(w as f64)),
+                reps_min: e.target_reps_min.map(|r| r as i64),
+                reps_max: e.target_reps_max.map(|r| r as i64),
+                rest_seconds: e.rest_seconds.map(|r| r as i64),
                 notes: e.notes,
+                group: e.group_label,
             })
             .collect(),
     };
@@ -337,6 +355,38 @@ pub async fn import_workout(
     Ok((headers, Html(format!("Imported '{}'", workout.name))).into_response())
 }
 
+/// Übung über den Namen finden, ohne Groß-/Kleinschreibung und Leerzeichen
+/// am Rand. Wer von außen kommt — Plan, Archiv, fremde App — kennt keine
+/// IDs dieser Instanz; der Name ist das einzige stabile Merkmal.
+pub async fn find_exercise_by_name(
+    conn: &mut sqlx::SqliteConnection,
+    name: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT id FROM exercises WHERE lower(trim(name)) = lower(trim(?)) LIMIT 1",
+        name
+    )
+    .fetch_optional(&mut *conn)
+    .await
+}
+
+pub async fn insert_exercise(
+    conn: &mut sqlx::SqliteConnection,
+    exercise: &Exercise,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO exercises (id, name, instructions, video_url, created_at) VALUES (?, ?, ?, ?, ?)",
+        exercise.id,
+        exercise.name,
+        exercise.instructions,
+        exercise.video_url,
+        exercise.created_at
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
 /// Den geprüften Plan schreiben. Nimmt eine Verbindung statt des Pools, damit
 /// der Aufrufer die Transaktion besitzt — nur so lässt sich prüfen, dass ein
 /// Fehler nichts hinterlässt.
@@ -372,16 +422,7 @@ pub async fn write_plan(
     for (index, planned) in plan.exercises.iter().enumerate() {
         let position = index as i32 + 1;
 
-        // Zuordnung über den Namen, ohne Groß-/Kleinschreibung. Ein Modell
-        // kennt keine IDs — der Name ist das einzige stabile Merkmal.
-        let existing = sqlx::query_scalar!(
-            "SELECT id FROM exercises WHERE lower(trim(name)) = lower(trim(?)) LIMIT 1",
-            planned.name
-        )
-        .fetch_optional(&mut *conn)
-        .await?;
-
-        let exercise_id = match existing {
+        let exercise_id = match find_exercise_by_name(conn, &planned.name).await? {
             // Vorhandene Übung bleibt unverändert: ein Import ist kein Weg,
             // fremde Anleitungen in die eigene Bibliothek zu schreiben.
             Some(id) => id,
@@ -402,16 +443,7 @@ pub async fn write_plan(
                     instructions.to_string(),
                     planned.video_url.clone(),
                 );
-                sqlx::query!(
-                    "INSERT INTO exercises (id, name, instructions, video_url, created_at) VALUES (?, ?, ?, ?, ?)",
-                    new_exercise.id,
-                    new_exercise.name,
-                    new_exercise.instructions,
-                    new_exercise.video_url,
-                    new_exercise.created_at
-                )
-                .execute(&mut *conn)
-                .await?;
+                insert_exercise(conn, &new_exercise).await?;
                 new_exercise.id
             }
         };
@@ -421,20 +453,28 @@ pub async fn write_plan(
             exercise_id,
             position,
             planned.sets as i32,
-            planned.weight.map(|w| w as f32),
+            planned.weight.map(|w| plan.unit.to_kg(w) as f32),
         );
+        link.target_reps_min = planned.reps_min.map(|r| r as i32);
+        link.target_reps_max = planned.reps_max.map(|r| r as i32);
+        link.rest_seconds = planned.rest_seconds.map(|r| r as i32);
         link.notes = planned.notes.clone();
+        link.group_label = planned.group.clone();
 
         sqlx::query!(
-            "INSERT INTO workout_exercises (id, workout_id, exercise_id, position, target_sets, target_weight, notes, created_at)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO workout_exercises (id, workout_id, exercise_id, position, target_sets, target_weight, target_reps_min, target_reps_max, rest_seconds, notes, group_label, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             link.id,
             link.workout_id,
             link.exercise_id,
             link.position,
             link.target_sets,
             link.target_weight,
+            link.target_reps_min,
+            link.target_reps_max,
+            link.rest_seconds,
             link.notes,
+            link.group_label,
             link.created_at
         )
         .execute(&mut *conn)
@@ -447,6 +487,7 @@ pub async fn write_plan(
 pub async fn add_exercise_to_workout(
     Path(workout_id): Path<String>,
     State(database_pool): State<SqlitePool>,
+    session: Session,
     Form(form): Form<AddExerciseToWorkoutForm>,
 ) -> Result<impl IntoResponse, AppError> {
     // Validate input
@@ -456,6 +497,22 @@ pub async fn add_exercise_to_workout(
     if form.target_sets < 1 || form.target_sets > 50 {
         return Err(AppError::BadRequest("Target sets must be between 1 and 50".to_string()));
     }
+    let target_reps = parse_rep_range(form.target_reps.as_deref().unwrap_or(""))
+        .map_err(AppError::BadRequest)?;
+    let rest_seconds = match form.rest_seconds.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        None => None,
+        Some(raw) => match raw.parse::<i32>() {
+            Ok(secs) if (0..=600).contains(&secs) => Some(secs),
+            _ => return Err(AppError::BadRequest("Rest must be between 0 and 600 seconds".to_string())),
+        },
+    };
+    let progression = match form.progression.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        None => Strategy::default(),
+        Some(raw) => Strategy::parse(raw)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown progression '{raw}'")))?,
+    };
+    let group_label = parse_group_label(form.group_label.as_deref().unwrap_or(""))
+        .map_err(AppError::BadRequest)?;
 
     // Verify workout exists first
     sqlx::query_as!(
@@ -465,6 +522,11 @@ pub async fn add_exercise_to_workout(
     ).fetch_optional(&database_pool).await?
         .ok_or_else(|| AppError::NotFound(format!("Workout '{}' not found", workout_id)))?;
 
+    let unit = get_current_user(&session, &database_pool)
+        .await?
+        .map(|u| u.unit())
+        .unwrap_or_default();
+
     let max_position = sqlx::query_scalar!(
         "SELECT COALESCE(MAX(position), 0) FROM workout_exercises WHERE workout_id = ?",
         workout_id
@@ -478,22 +540,33 @@ pub async fn add_exercise_to_workout(
         form.exercise_id,
         max_position + 1,
         form.target_sets,
-        form.target_weight,
+        // Eingabe in der Einheit des Nutzers, gespeichert wird kg.
+        form.target_weight.map(|w| unit.to_kg(w as f64) as f32),
     );
 
     let mut exercise_with_notes = new_exercise.clone();
+    exercise_with_notes.target_reps_min = target_reps.map(|(min, _)| min);
+    exercise_with_notes.target_reps_max = target_reps.map(|(_, max)| max);
+    exercise_with_notes.rest_seconds = rest_seconds;
     exercise_with_notes.notes = form.notes;
+    exercise_with_notes.progression = progression.as_str().to_string();
+    exercise_with_notes.group_label = group_label;
 
     sqlx::query!(
-        "INSERT INTO workout_exercises (id, workout_id, exercise_id, position, target_sets, target_weight, notes, created_at) 
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?)", 
+        "INSERT INTO workout_exercises (id, workout_id, exercise_id, position, target_sets, target_weight, target_reps_min, target_reps_max, rest_seconds, notes, progression, group_label, created_at) 
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)", 
         exercise_with_notes.id,
         exercise_with_notes.workout_id,
         exercise_with_notes.exercise_id,
         exercise_with_notes.position,
         exercise_with_notes.target_sets,
         exercise_with_notes.target_weight,
+        exercise_with_notes.target_reps_min,
+        exercise_with_notes.target_reps_max,
+        exercise_with_notes.rest_seconds,
         exercise_with_notes.notes,
+        exercise_with_notes.progression,
+        exercise_with_notes.group_label,
         exercise_with_notes.created_at
     ).execute(&database_pool).await?;
 
@@ -588,6 +661,7 @@ mod tests {
             description: None,
             schedule_type: "manual".into(),
             schedule_day: None,
+            unit: WeightUnit::Kg,
             exercises: vec![
                 PlanExercise {
                     name: "Neue Übung mit Anleitung".into(),
@@ -595,7 +669,11 @@ mod tests {
                     video_url: None,
                     sets: 3,
                     weight: None,
+                    reps_min: None,
+                    reps_max: None,
+                    rest_seconds: None,
                     notes: None,
+                    group: None,
                 },
                 // Unbekannt und ohne Anleitung: bricht ab, nachdem oben
                 // bereits Workout, Übung und Zuordnung geschrieben wurden.
@@ -605,7 +683,11 @@ mod tests {
                     video_url: None,
                     sets: 3,
                     weight: None,
+                    reps_min: None,
+                    reps_max: None,
+                    rest_seconds: None,
                     notes: None,
+                    group: None,
                 },
             ],
         }
@@ -655,7 +737,11 @@ mod tests {
             video_url: None,
             sets: 5,
             weight: Some(60.0),
+            reps_min: Some(8),
+            reps_max: Some(12),
+            rest_seconds: Some(90),
             notes: Some("langsam".into()),
+            group: Some("A".into()),
         });
 
         let mut tx = pool.begin().await.unwrap();
@@ -676,6 +762,33 @@ mod tests {
         assert_eq!(positions[0].name, "Neue Übung mit Anleitung");
         assert_eq!(positions[1].position, 2);
         assert_eq!(positions[1].name, "Zweite Übung");
+
+        let targets = sqlx::query!(
+            r#"SELECT target_reps_min as "min: i32", target_reps_max as "max: i32", rest_seconds as "rest: i32", group_label
+               FROM workout_exercises WHERE workout_id = ? AND position = 2"#,
+            workout.id
+        ).fetch_one(&pool).await.unwrap();
+        assert_eq!((targets.min, targets.max, targets.rest), (Some(8), Some(12), Some(90)));
+        assert_eq!(targets.group_label.as_deref(), Some("A"));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn pound_plans_are_stored_in_kg(pool: SqlitePool) {
+        let user_id = seed_user(&pool).await;
+        let mut plan = plan_with_unknown_exercise_last();
+        plan.exercises.pop();
+        plan.unit = WeightUnit::Lb;
+        plan.exercises[0].weight = Some(135.0);
+
+        let mut tx = pool.begin().await.unwrap();
+        let workout = write_plan(&mut tx, &user_id, &plan).await.unwrap();
+        tx.commit().await.unwrap();
+
+        let weight = sqlx::query_scalar!(
+            r#"SELECT target_weight as "w: f64" FROM workout_exercises WHERE workout_id = ?"#,
+            workout.id
+        ).fetch_one(&pool).await.unwrap();
+        assert_eq!(weight.map(|w| (w * 100.0).round() / 100.0), Some(61.23));
     }
 
     #[sqlx::test(migrations = "./migrations")]
@@ -691,6 +804,7 @@ mod tests {
             description: None,
             schedule_type: "manual".into(),
             schedule_day: None,
+            unit: WeightUnit::Kg,
             exercises: vec![PlanExercise {
                 // andere Schreibweise, zusätzliche Leerzeichen
                 name: "  bench press  ".into(),
@@ -698,7 +812,11 @@ mod tests {
                 video_url: Some("https://example.com".into()),
                 sets: 3,
                 weight: None,
+                reps_min: None,
+                reps_max: None,
+                rest_seconds: None,
                 notes: None,
+                group: None,
             }],
         };
 
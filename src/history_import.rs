@@ -0,0 +1,536 @@
+//! Trainingshistorie aus den CSV-Exporten anderer Apps — Strong, Hevy und
+//! FitNotes. Welche App, verrät die Kopfzeile; Spalten werden über ihren
+//! Namen gesucht, nicht über die Position.
+//!
+//! Die Dateien tragen lokale Uhrzeiten ohne Zone. Sie werden als UTC
+//! übernommen — eine Stunde Versatz ist für eine Historie harmloser als ein
+//! geratener Zeitzonenwechsel.
+//!
+//! Sätze haben in keinem der Formate eine eigene Uhrzeit. Sie bekommen den
+//! Beginn des Trainings plus ihre Position in Sekunden, damit die
+//! Reihenfolge erhalten bleibt.
+
+use crate::csv;
+use crate::models::SetType;
+use crate::units::WeightUnit;
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use std::collections::HashMap;
+
+pub const MAX_REPS: i64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Source {
+    Strong,
+    Hevy,
+    FitNotes,
+}
+
+impl Source {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Source::Strong => "Strong",
+            Source::Hevy => "Hevy",
+            Source::FitNotes => "FitNotes",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedHistory {
+    pub source: Source,
+    pub sessions: Vec<ImportedSession>,
+    /// Übersprungene Zeilen mit Grund, für die Vorschau.
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedSession {
+    /// Erkennt ein Training beim zweiten Import derselben Datei wieder.
+    pub key: String,
+    pub workout_name: String,
+    pub started_at: NaiveDateTime,
+    pub completed_at: NaiveDateTime,
+    pub notes: Option<String>,
+    pub sets: Vec<ImportedSet>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedSet {
+    pub exercise_name: String,
+    pub set_number: i64,
+    /// `None` für Körpergewicht — die Apps schreiben dort 0 oder nichts.
+    pub weight_kg: Option<f64>,
+    pub reps: i64,
+    pub notes: Option<String>,
+    /// Strong und Hevy exportieren RPE, FitNotes nicht.
+    pub rpe: Option<f64>,
+    /// Strong markiert die Satzart in „Set Order", Hevy in `set_type`.
+    pub set_type: SetType,
+    pub completed_at: NaiveDateTime,
+}
+
+impl ImportedSession {
+    pub fn duration_minutes(&self) -> i64 {
+        (self.completed_at - self.started_at).num_minutes().max(0)
+    }
+
+    /// Sätze ohne Aufwärmsätze, wie `completed_workouts.total_sets`.
+    pub fn working_set_count(&self) -> i64 {
+        self.sets.iter().filter(|s| !s.set_type.is_warmup()).count() as i64
+    }
+
+    pub fn volume_kg(&self) -> f64 {
+        self.sets
+            .iter()
+            .filter(|s| !s.set_type.is_warmup())
+            .filter_map(|s| s.weight_kg.map(|w| w * s.reps as f64))
+            .sum()
+    }
+}
+
+/// Spalten einer Kopfzeile, nachschlagbar über den Namen.
+struct Columns(HashMap<String, usize>);
+
+impl Columns {
+    fn new(header: &[String]) -> Self {
+        Columns(
+            header
+                .iter()
+                .enumerate()
+                .map(|(i, name)| (name.trim().to_lowercase(), i))
+                .collect(),
+        )
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+
+    /// Erste vorhandene Spalte aus mehreren Schreibweisen.
+    fn find(&self, names: &[&str]) -> Option<usize> {
+        names.iter().find_map(|n| self.0.get(*n).copied())
+    }
+
+    fn require(&self, names: &[&str]) -> Result<usize, String> {
+        self.find(names)
+            .ok_or_else(|| format!("missing column '{}'", names[0]))
+    }
+
+    /// Spalte, deren Name mit `prefix` beginnt — für „Weight (lbs)".
+    fn starting_with(&self, prefix: &str) -> Option<(usize, &str)> {
+        self.0
+            .iter()
+            .filter(|(name, _)| name.starts_with(prefix))
+            .min_by_key(|(_, i)| **i)
+            .map(|(name, i)| (*i, name.as_str()))
+    }
+}
+
+fn cell(fields: &[String], index: Option<usize>) -> &str {
+    index.and_then(|i| fields.get(i)).map(|s| s.trim()).unwrap_or("")
+}
+
+fn optional(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() { None } else { Some(text.to_string()) }
+}
+
+/// Zahl mit Punkt oder Komma als Dezimalzeichen.
+fn number(text: &str) -> Option<f64> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    text.replace(',', ".").parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+fn datetime(text: &str) -> Option<NaiveDateTime> {
+    const FORMATS: [&str; 4] = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%d %b %Y, %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+    ];
+    let text = text.trim();
+    FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(text, f).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })
+}
+
+/// Dauer wie Strong sie schreibt: `1h 5m`, `45m`, `50s`. Reine Zahlen sind
+/// Sekunden — so steht es in älteren Exporten.
+fn duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if let Ok(seconds) = text.parse::<i64>() {
+        return Some(Duration::seconds(seconds));
+    }
+    let mut total = Duration::zero();
+    for part in text.split_whitespace() {
+        let (value, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+        let value: i64 = value.parse().ok()?;
+        total += match unit {
+            "h" => Duration::hours(value),
+            "m" | "min" => Duration::minutes(value),
+            "s" => Duration::seconds(value),
+            _ => return None,
+        };
+    }
+    Some(total)
+}
+
+/// Eine Zeile, wie sie aus jedem Format herauskommt — vor dem Gruppieren.
+struct Row {
+    line: usize,
+    workout_name: String,
+    started_at: NaiveDateTime,
+    completed_at: Option<NaiveDateTime>,
+    session_notes: Option<String>,
+    exercise_name: String,
+    weight: Option<f64>,
+    /// Hevy und FitNotes nennen die Einheit in der Kopfzeile, Strong nicht —
+    /// dort gilt, was der Nutzer angibt.
+    unit: WeightUnit,
+    reps: Option<f64>,
+    notes: Option<String>,
+    rpe: Option<f64>,
+    set_type: SetType,
+}
+
+pub fn detect(header: &[String]) -> Option<Source> {
+    let columns = Columns::new(header);
+    if columns.has("exercise_title") && columns.has("start_time") {
+        Some(Source::Hevy)
+    } else if columns.has("exercise name") && columns.has("workout name") {
+        Some(Source::Strong)
+    } else if columns.has("exercise") && columns.has("category") && columns.has("date") {
+        Some(Source::FitNotes)
+    } else {
+        None
+    }
+}
+
+/// Datei lesen und nach Trainings gruppieren. `fallback_unit` gilt nur,
+/// wenn die Datei ihre Einheit nicht selbst nennt.
+pub fn parse(input: &str, fallback_unit: WeightUnit) -> Result<ImportedHistory, String> {
+    let records = csv::parse(input, csv::detect_delimiter(input))?;
+    let Some((header, rows)) = records.split_first() else {
+        return Err("the file is empty".to_string());
+    };
+    let source = detect(&header.fields).ok_or_else(|| {
+        "unknown CSV format: expected an export from Strong, Hevy or FitNotes".to_string()
+    })?;
+    let columns = Columns::new(&header.fields);
+
+    let mut skipped = Vec::new();
+    let mut parsed = Vec::new();
+    for record in rows {
+        let row = match source {
+            Source::Strong => strong_row(&columns, record, fallback_unit),
+            Source::Hevy => hevy_row(&columns, record, fallback_unit),
+            Source::FitNotes => fitnotes_row(&columns, record, fallback_unit),
+        };
+        match row {
+            Ok(row) => parsed.push(row),
+            Err(reason) if reason.starts_with("missing column") => return Err(reason),
+            Err(reason) => skipped.push(format!("line {}: {reason}", record.line)),
+        }
+    }
+
+    let mut sessions: Vec<ImportedSession> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for row in parsed {
+        let reps = match row.reps {
+            Some(r) if r >= 1.0 && r.fract() == 0.0 && r <= MAX_REPS as f64 => r as i64,
+            // Zeit- und Cardio-Sätze haben keine Wiederholungen.
+            None | Some(0.0) => {
+                skipped.push(format!("line {}: no reps (timed or cardio set)", row.line));
+                continue;
+            }
+            Some(r) => {
+                skipped.push(format!("line {}: invalid reps '{r}'", row.line));
+                continue;
+            }
+        };
+        if row.exercise_name.is_empty() {
+            skipped.push(format!("line {}: no exercise name", row.line));
+            continue;
+        }
+
+        let key = format!("{}:{}:{}", source.label(), row.started_at, row.workout_name);
+        let position = *index.entry(key.clone()).or_insert_with(|| {
+            sessions.push(ImportedSession {
+                key,
+                workout_name: row.workout_name.clone(),
+                started_at: row.started_at,
+                completed_at: row.started_at,
+                notes: row.session_notes.clone(),
+                sets: Vec::new(),
+            });
+            sessions.len() - 1
+        });
+        let session = &mut sessions[position];
+
+        // Aufwärmsätze werden getrennt gezählt, wie im Training.
+        let set_number = session
+            .sets
+            .iter()
+            .filter(|s| {
+                s.exercise_name == row.exercise_name
+                    && s.set_type.is_warmup() == row.set_type.is_warmup()
+            })
+            .count() as i64
+            + 1;
+        let completed_at = session.started_at + Duration::seconds(session.sets.len() as i64 + 1);
+        session.completed_at = session
+            .completed_at
+            .max(row.completed_at.unwrap_or(completed_at))
+            .max(completed_at);
+        session.sets.push(ImportedSet {
+            exercise_name: row.exercise_name,
+            set_number,
+            // Negative Gewichte schreibt Strong für Unterstützung (assistierte
+            // Klimmzüge) — hier zählt das wie Körpergewicht.
+            weight_kg: row.weight.filter(|w| *w > 0.0).map(|w| row.unit.to_kg(w)),
+            reps,
+            notes: row.notes,
+            // Außerhalb der Skala ist eher ein Tippfehler als eine Angabe.
+            rpe: row.rpe.filter(|r| (1.0..=10.0).contains(r)),
+            set_type: row.set_type,
+            completed_at,
+        });
+    }
+
+    if sessions.is_empty() {
+        return Err("the file contains no sets that can be imported".to_string());
+    }
+    Ok(ImportedHistory { source, sessions, skipped })
+}
+
+fn strong_row(columns: &Columns, record: &csv::Record, fallback: WeightUnit) -> Result<Row, String> {
+    let fields = &record.fields;
+    let date = cell(fields, Some(columns.require(&["date"])?));
+    let started_at = datetime(date).ok_or_else(|| format!("invalid date '{date}'"))?;
+    let duration = duration(cell(fields, columns.find(&["duration", "workout duration"])));
+    // Ältere Exporte haben eine Einheit pro Zeile.
+    let unit = WeightUnit::parse(cell(fields, columns.find(&["weight unit"]))).unwrap_or(fallback);
+
+    Ok(Row {
+        line: record.line,
+        workout_name: cell(fields, Some(columns.require(&["workout name"])?)).to_string(),
+        started_at,
+        completed_at: duration.map(|d| started_at + d),
+        session_notes: optional(cell(fields, columns.find(&["workout notes"]))),
+        exercise_name: cell(fields, Some(columns.require(&["exercise name"])?)).to_string(),
+        weight: number(cell(fields, columns.find(&["weight"]))),
+        unit,
+        reps: number(cell(fields, Some(columns.require(&["reps"])?))),
+        notes: optional(cell(fields, columns.find(&["notes"]))),
+        rpe: number(cell(fields, columns.find(&["rpe"]))),
+        set_type: strong_set_type(cell(fields, columns.find(&["set order"]))),
+    })
+}
+
+/// Strong schreibt statt einer Nummer `W`, `D` oder `F` für Aufwärm-,
+/// Drop- und Versagenssätze.
+fn strong_set_type(order: &str) -> SetType {
+    match order.trim().to_uppercase().as_str() {
+        "W" => SetType::Warmup,
+        "D" => SetType::Drop,
+        "F" => SetType::Failure,
+        _ => SetType::Working,
+    }
+}
+
+fn hevy_row(columns: &Columns, record: &csv::Record, fallback: WeightUnit) -> Result<Row, String> {
+    let fields = &record.fields;
+    let start = cell(fields, Some(columns.require(&["start_time"])?));
+    let started_at = datetime(start).ok_or_else(|| format!("invalid start_time '{start}'"))?;
+    let (weight_column, unit) = match (columns.find(&["weight_kg"]), columns.find(&["weight_lbs"])) {
+        (Some(i), _) => (Some(i), WeightUnit::Kg),
+        (None, Some(i)) => (Some(i), WeightUnit::Lb),
+        (None, None) => (None, fallback),
+    };
+
+    Ok(Row {
+        line: record.line,
+        workout_name: cell(fields, Some(columns.require(&["title"])?)).to_string(),
+        started_at,
+        completed_at: datetime(cell(fields, columns.find(&["end_time"]))),
+        session_notes: optional(cell(fields, columns.find(&["description"]))),
+        exercise_name: cell(fields, Some(columns.require(&["exercise_title"])?)).to_string(),
+        weight: number(cell(fields, weight_column)),
+        unit,
+        reps: number(cell(fields, Some(columns.require(&["reps"])?))),
+        notes: optional(cell(fields, columns.find(&["exercise_notes"]))),
+        rpe: number(cell(fields, columns.find(&["rpe"]))),
+        set_type: SetType::parse(cell(fields, columns.find(&["set_type"]))).unwrap_or_default(),
+    })
+}
+
+/// FitNotes kennt keine Trainings, nur Tage. Ein Tag wird ein Training.
+fn fitnotes_row(columns: &Columns, record: &csv::Record, fallback: WeightUnit) -> Result<Row, String> {
+    let fields = &record.fields;
+    let date = cell(fields, Some(columns.require(&["date"])?));
+    let started_at = datetime(date).ok_or_else(|| format!("invalid date '{date}'"))?;
+    // „Weight (kgs)" oder „Weight (lbs)" — die Einheit steht im Spaltennamen.
+    let (weight_column, unit) = match columns.starting_with("weight") {
+        Some((i, name)) => {
+            let unit = name
+                .trim_start_matches("weight")
+                .trim()
+                .trim_matches(|c| c == '(' || c == ')');
+            (Some(i), WeightUnit::parse(unit).unwrap_or(fallback))
+        }
+        None => (None, fallback),
+    };
+
+    Ok(Row {
+        line: record.line,
+        workout_name: "FitNotes".to_string(),
+        started_at,
+        completed_at: None,
+        session_notes: None,
+        exercise_name: cell(fields, Some(columns.require(&["exercise"])?)).to_string(),
+        weight: number(cell(fields, weight_column)),
+        unit,
+        reps: number(cell(fields, Some(columns.require(&["reps"])?))),
+        notes: optional(cell(fields, columns.find(&["comment"]))),
+        rpe: None,
+        set_type: SetType::Working,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        let path = format!("{}/fixtures/import/{name}", env!("CARGO_MANIFEST_DIR"));
+        std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{path}: {e}"))
+    }
+
+    fn at(text: &str) -> NaiveDateTime {
+        datetime(text).unwrap()
+    }
+
+    #[test]
+    fn strong_export_groups_sets_into_sessions() {
+        let history = parse(&fixture("strong.csv"), WeightUnit::Kg).unwrap();
+        assert_eq!(history.source, Source::Strong);
+        assert_eq!(history.sessions.len(), 2);
+
+        let push = &history.sessions[0];
+        assert_eq!(push.workout_name, "Push Day");
+        assert_eq!(push.started_at, at("2024-03-04 18:02:11"));
+        assert_eq!(push.duration_minutes(), 65);
+        assert_eq!(push.notes.as_deref(), Some("Gutes Training"));
+        assert_eq!(push.sets.len(), 4);
+        assert_eq!(push.sets[0].weight_kg, Some(60.0));
+        assert_eq!(push.sets[2].set_number, 3);
+        assert_eq!(push.sets[3].exercise_name, "Dips");
+        assert_eq!(push.sets[3].weight_kg, None, "0 in Strong means bodyweight");
+        assert_eq!(push.sets[1].notes.as_deref(), Some("schwer, aber sauber"));
+
+        // Die Cardio-Zeile ohne Wiederholungen fällt weg, mit Begründung.
+        assert_eq!(history.skipped.len(), 1);
+        assert!(history.skipped[0].contains("no reps"), "{:?}", history.skipped);
+    }
+
+    #[test]
+    fn strong_weights_follow_the_chosen_unit() {
+        let history = parse(&fixture("strong.csv"), WeightUnit::Lb).unwrap();
+        assert_eq!(history.sessions[0].sets[0].weight_kg, Some(27.22));
+    }
+
+    #[test]
+    fn hevy_export_in_pounds_is_converted() {
+        // Die Datei nennt ihre Einheit — die Vorgabe des Nutzers zählt nicht.
+        let history = parse(&fixture("hevy_lbs.csv"), WeightUnit::Kg).unwrap();
+        assert_eq!(history.source, Source::Hevy);
+        assert_eq!(history.sessions.len(), 2);
+
+        let legs = &history.sessions[0];
+        assert_eq!(legs.workout_name, "Leg Day");
+        assert_eq!(legs.started_at, at("2024-10-22 18:05"));
+        assert_eq!(legs.completed_at, at("2024-10-22 19:10"));
+        assert_eq!(legs.sets[0].weight_kg, Some(61.23), "135 lb");
+        assert_eq!(legs.sets[0].set_number, 1);
+        assert_eq!(legs.sets[1].set_number, 1, "warm-ups are numbered separately");
+        assert_eq!((legs.sets[0].set_type, legs.sets[1].set_type), (SetType::Warmup, SetType::Working));
+        assert_eq!((legs.sets[0].rpe, legs.sets[1].rpe), (None, Some(8.0)));
+        assert_eq!(legs.working_set_count(), legs.sets.len() as i64 - 1);
+        assert_eq!(legs.notes.as_deref(), Some("Knie ok"));
+        assert_eq!(history.sessions[1].sets[0].weight_kg, None, "pull-ups without weight");
+    }
+
+    #[test]
+    fn fitnotes_export_becomes_one_session_per_day() {
+        let history = parse(&fixture("fitnotes.csv"), WeightUnit::Lb).unwrap();
+        assert_eq!(history.source, Source::FitNotes);
+        assert_eq!(history.sessions.len(), 2);
+        assert!(history.sessions.iter().all(|s| s.workout_name == "FitNotes"));
+
+        let first = &history.sessions[0];
+        assert_eq!(first.started_at, at("2024-01-05"));
+        assert_eq!(first.sets.len(), 3);
+        assert_eq!(first.sets[0].weight_kg, Some(100.0), "'Weight (kgs)' wins over the fallback");
+        assert_eq!(first.sets[2].exercise_name, "Flat Barbell Bench Press");
+        assert_eq!(first.sets[2].notes.as_deref(), Some("Pause unten"));
+    }
+
+    #[test]
+    fn sets_keep_their_order_within_a_session() {
+        let history = parse(&fixture("strong.csv"), WeightUnit::Kg).unwrap();
+        let times: Vec<_> = history.sessions[0].sets.iter().map(|s| s.completed_at).collect();
+        assert!(times.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn semicolon_export_with_decimal_commas_is_read() {
+        let input = "Date;Workout Name;Duration;Exercise Name;Set Order;Weight;Reps;Notes;Workout Notes\n\
+                     2024-03-04 18:02:11;Push;45m;Bench Press;1;62,5;8;;\n";
+        let history = parse(input, WeightUnit::Kg).unwrap();
+        assert_eq!(history.sessions[0].sets[0].weight_kg, Some(62.5));
+    }
+
+    #[test]
+    fn unknown_and_broken_files_are_rejected() {
+        let err = parse("a,b,c\n1,2,3\n", WeightUnit::Kg).unwrap_err();
+        assert!(err.contains("unknown CSV format"), "{err}");
+
+        assert!(parse("", WeightUnit::Kg).unwrap_err().contains("empty"));
+
+        let only_cardio = "Date,Workout Name,Duration,Exercise Name,Set Order,Weight,Reps\n\
+                           2024-03-04 18:02:11,Run,30m,Running,1,0,0\n";
+        assert!(parse(only_cardio, WeightUnit::Kg).unwrap_err().contains("no sets"));
+    }
+
+    #[test]
+    fn bad_rows_are_skipped_with_their_line() {
+        let input = "Date,Workout Name,Duration,Exercise Name,Set Order,Weight,Reps\n\
+                     2024-03-04 18:02:11,Push,45m,Bench Press,1,60,8\n\
+                     gestern,Push,45m,Bench Press,2,60,8\n\
+                     2024-03-04 18:02:11,Push,45m,Bench Press,3,60,7.5\n";
+        let history = parse(input, WeightUnit::Kg).unwrap();
+        assert_eq!(history.sessions[0].sets.len(), 1);
+        assert_eq!(history.skipped.len(), 2);
+        assert!(history.skipped[0].starts_with("line 3: invalid date"), "{:?}", history.skipped);
+        assert!(history.skipped[1].starts_with("line 4: invalid reps"), "{:?}", history.skipped);
+    }
+
+    #[test]
+    fn durations_parse_like_strong_writes_them() {
+        assert_eq!(duration("1h 5m"), Some(Duration::minutes(65)));
+        assert_eq!(duration("45m"), Some(Duration::minutes(45)));
+        assert_eq!(duration("3900"), Some(Duration::minutes(65)));
+        assert_eq!(duration("lang"), None);
+    }
+}
@@ -0,0 +1,186 @@
+//! iCalendar (RFC 5545) für den Kalender-Link: geplante Trainings als
+//! wöchentliche Serien, abgeschlossene als vergangene Termine.
+//!
+//! Nur das, was Kalender-Apps für ein Abo brauchen — kein Parser, keine
+//! Zeitzonen. Wochentermine sind ganztägig, weil ein Plan nur den Tag kennt;
+//! abgeschlossene Trainings stehen in UTC.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+
+/// Längste Zeile in Oktetten, ohne CRLF.
+const MAX_LINE: usize = 75;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventTime {
+    /// Ganztägig, jede Woche am Wochentag von `first`, ab `first`.
+    Weekly { first: NaiveDate },
+    Timed { start: DateTime<Utc>, end: DateTime<Utc> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    /// Bleibt über Abrufe gleich, sonst doppeln sich die Termine.
+    pub uid: String,
+    pub summary: String,
+    pub description: Option<String>,
+    pub time: EventTime,
+}
+
+/// Erster Tag ab `from` mit dem Wochentag aus `workouts.schedule_day`
+/// (0 = Sonntag … 6 = Samstag). `None` für Werte außerhalb.
+pub fn first_weekday_from(from: NaiveDate, schedule_day: i32) -> Option<NaiveDate> {
+    if !(0..=6).contains(&schedule_day) {
+        return None;
+    }
+    let today = from.weekday().num_days_from_sunday() as i32;
+    Some(from + Duration::days(((schedule_day - today + 7) % 7) as i64))
+}
+
+/// Der ganze Kalender mit CRLF-Zeilen. `now` landet als `DTSTAMP` in jedem
+/// Termin.
+pub fn render(name: &str, events: &[Event], now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//WOPlanner//Workout Calendar//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape(name)),
+    ];
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", escape(&event.uid)));
+        lines.push(format!("DTSTAMP:{}", utc(now)));
+        match &event.time {
+            EventTime::Weekly { first } => {
+                lines.push(format!("DTSTART;VALUE=DATE:{}", first.format("%Y%m%d")));
+                lines.push(format!("DTEND;VALUE=DATE:{}", (*first + Duration::days(1)).format("%Y%m%d")));
+                lines.push(format!("RRULE:FREQ=WEEKLY;BYDAY={}", byday(first.weekday())));
+                lines.push("TRANSP:TRANSPARENT".to_string());
+            }
+            EventTime::Timed { start, end } => {
+                lines.push(format!("DTSTART:{}", utc(*start)));
+                lines.push(format!("DTEND:{}", utc(*end)));
+            }
+        }
+        lines.push(format!("SUMMARY:{}", escape(&event.summary)));
+        if let Some(description) = &event.description {
+            lines.push(format!("DESCRIPTION:{}", escape(description)));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+fn utc(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn byday(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+/// TEXT-Werte: Backslash, Semikolon und Komma maskiert, Zeilenumbrüche als `\n`.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Bricht nach [`MAX_LINE`] Oktetten um, Folgezeilen beginnen mit einem
+/// Leerzeichen. Nie mitten in einem UTF-8-Zeichen.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / MAX_LINE * 3);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn schedule_days_count_from_sunday() {
+        // 2026-10-18 ist ein Sonntag.
+        assert_eq!(first_weekday_from(date("2026-10-18"), 0), Some(date("2026-10-18")));
+        assert_eq!(first_weekday_from(date("2026-10-18"), 1), Some(date("2026-10-19")));
+        assert_eq!(first_weekday_from(date("2026-10-20"), 1), Some(date("2026-10-26")));
+        assert_eq!(first_weekday_from(date("2026-10-18"), 7), None);
+    }
+
+    #[test]
+    fn weekly_and_timed_events_render_with_crlf() {
+        let now = DateTime::parse_from_rfc3339("2026-10-18T12:00:00Z").unwrap().to_utc();
+        let events = [
+            Event {
+                uid: "w-1@woplanner".into(),
+                summary: "Push, Pull".into(),
+                description: Some("Heavy day;\nbench first".into()),
+                time: EventTime::Weekly { first: date("2026-10-19") },
+            },
+            Event {
+                uid: "s-1@woplanner".into(),
+                summary: "Legs".into(),
+                description: None,
+                time: EventTime::Timed {
+                    start: DateTime::parse_from_rfc3339("2026-10-17T20:00:00+02:00").unwrap().to_utc(),
+                    end: DateTime::parse_from_rfc3339("2026-10-17T21:00:00+02:00").unwrap().to_utc(),
+                },
+            },
+        ];
+        let calendar = render("WOPlanner", &events, now);
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert!(!calendar.replace("\r\n", "").contains('\n'));
+        assert!(calendar.contains("DTSTART;VALUE=DATE:20261019\r\nDTEND;VALUE=DATE:20261020\r\n"));
+        assert!(calendar.contains("RRULE:FREQ=WEEKLY;BYDAY=MO\r\n"));
+        assert!(calendar.contains("SUMMARY:Push\\, Pull\r\n"));
+        assert!(calendar.contains("DESCRIPTION:Heavy day\\;\\nbench first\r\n"));
+        assert!(calendar.contains("DTSTART:20261017T180000Z\r\nDTEND:20261017T190000Z\r\n"));
+        assert_eq!(calendar.matches("DTSTAMP:20261018T120000Z").count(), 2);
+    }
+
+    #[test]
+    fn long_lines_fold_without_splitting_characters() {
+        let line = format!("SUMMARY:{}", "ü".repeat(60));
+        let folded = fold(&line);
+        let parts: Vec<&str> = folded.split("\r\n").collect();
+        assert!(parts.len() > 1);
+        assert!(parts.iter().all(|part| part.len() <= MAX_LINE));
+        assert!(parts[1..].iter().all(|part| part.starts_with(' ')));
+        let unfolded: String = parts.iter().enumerate().map(|(i, part)| if i == 0 { *part } else { &part[1..] }).collect();
+        assert_eq!(unfolded, line);
+    }
+}
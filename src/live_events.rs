@@ -0,0 +1,102 @@
+//! Ereignisse einer laufenden Sitzung für zweite Bildschirme: das Tablet
+//! am Rack folgt dem Training, das auf dem Handy geloggt wird.
+//!
+//! Nur im Prozess und nur für die, die gerade zuhören — wer später kommt,
+//! lädt die Seite und hat den Stand. Ausgeliefert als Server-Sent Events
+//! unter `/live-training/{id}/events`.
+
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use tokio::sync::broadcast;
+
+/// So viele Ereignisse darf ein langsamer Zuhörer zurückliegen, bevor er
+/// welche verpasst.
+const CAPACITY: usize = 32;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveEvent {
+    /// SSE-Eventname: `set-completed`, `exercise-changed`, `rest-started`
+    /// oder `finished`.
+    pub name: &'static str,
+    pub data: Value,
+}
+
+impl LiveEvent {
+    pub fn set_completed(set: Value, records: &[String]) -> Self {
+        LiveEvent { name: "set-completed", data: json!({"set": set, "records": records}) }
+    }
+
+    /// `exercise` ist `None`, wenn alle Übungen durch sind.
+    pub fn exercise_changed(exercise: Option<(i32, &str, &str)>) -> Self {
+        let data = match exercise {
+            Some((position, exercise_id, exercise_name)) => {
+                json!({"position": position, "exercise_id": exercise_id, "exercise_name": exercise_name})
+            }
+            None => json!({"position": null, "exercise_id": null, "exercise_name": null}),
+        };
+        LiveEvent { name: "exercise-changed", data }
+    }
+
+    pub fn rest_started(seconds: i32) -> Self {
+        LiveEvent { name: "rest-started", data: json!({"seconds": seconds}) }
+    }
+
+    /// Beendet oder verworfen; danach kommt nichts mehr.
+    pub fn finished(discarded: bool) -> Self {
+        LiveEvent { name: "finished", data: json!({"discarded": discarded}) }
+    }
+}
+
+static CHANNELS: LazyLock<Mutex<HashMap<String, broadcast::Sender<LiveEvent>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Ereignisse der Sitzung ab jetzt.
+pub fn subscribe(session_id: &str) -> broadcast::Receiver<LiveEvent> {
+    let mut channels = CHANNELS.lock().expect("live event channels poisoned");
+    // Kanäle, deren Zuhörer alle weg sind, bei der Gelegenheit aufräumen.
+    channels.retain(|_, sender| sender.receiver_count() > 0);
+    channels
+        .entry(session_id.to_string())
+        .or_insert_with(|| broadcast::channel(CAPACITY).0)
+        .subscribe()
+}
+
+/// Ob gerade jemand zusieht — sonst lohnt es nicht, Ereignisse
+/// zusammenzustellen.
+pub fn is_watched(session_id: &str) -> bool {
+    let channels = CHANNELS.lock().expect("live event channels poisoned");
+    channels.get(session_id).is_some_and(|sender| sender.receiver_count() > 0)
+}
+
+/// An alle, die der Sitzung gerade folgen. Ohne Zuhörer passiert nichts.
+pub fn publish(session_id: &str, event: LiveEvent) {
+    let mut channels = CHANNELS.lock().expect("live event channels poisoned");
+    let finished = event.name == "finished";
+    let Some(sender) = channels.get(session_id) else {
+        return;
+    };
+    if sender.send(event).is_err() || finished {
+        channels.remove(session_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn events_reach_current_listeners_of_their_session_only() {
+        publish("s-1", LiveEvent::rest_started(60));
+        let mut first = subscribe("s-1");
+        let mut other = subscribe("s-2");
+
+        publish("s-1", LiveEvent::rest_started(90));
+        publish("s-1", LiveEvent::finished(false));
+        assert_eq!(first.recv().await.unwrap(), LiveEvent::rest_started(90));
+        assert_eq!(first.recv().await.unwrap().name, "finished");
+        // Nach `finished` ist der Kanal zu.
+        assert!(first.recv().await.is_err());
+        assert!(other.try_recv().is_err());
+    }
+}
@@ -1,9 +1,19 @@
+mod account_archive;
+mod api_token;
+mod csv;
 mod database;
 mod error;
 mod filters;
 mod handlers;
+mod history_import;
+mod ical;
+mod live_events;
 mod models;
+mod plates;
+mod progression;
 mod templates;
+mod units;
+mod webhooks;
 mod workout_yaml;
 
 use axum::{
@@ -11,10 +21,21 @@ use axum::{
     response::{Html, Json},
     routing::get,
 };
+use handlers::account::router as account_router;
+use handlers::api::router as api_router;
+use handlers::calendar::router as calendar_router;
+use handlers::equipment::router as equipment_router;
+use handlers::progression::router as progression_router;
+use handlers::pwa::router as pwa_router;
 use handlers::exercise::router as exercise_router;
 use handlers::history::router as history_router;
+use handlers::history_import::router as history_import_router;
+use handlers::live_stream::router as live_stream_router;
 use handlers::live_training::router as live_training_router;
+use handlers::openapi::router as openapi_router;
+use handlers::tokens::router as tokens_router;
 use handlers::users::router as users_router;
+use handlers::webhooks::router as webhooks_router;
 use handlers::workouts::router as workout_router;
 use serde_json::{Value, json};
 use std::net::SocketAddr;
@@ -56,14 +77,40 @@ async fn main() {
         .with_secure(false)
         .with_expiry(Expiry::OnInactivity(Duration::hours(24)));
 
+    // Offen gebliebene Trainings nach so vielen Stunden ohne Satz schließen,
+    // 0 schaltet den Job ab.
+    let auto_close_hours = std::env::var("SESSION_AUTO_CLOSE_HOURS")
+        .unwrap_or_else(|_| "12".to_string())
+        .parse::<i64>()
+        .expect("SESSION_AUTO_CLOSE_HOURS must be a whole number of hours");
+    if auto_close_hours > 0 {
+        tokio::spawn(handlers::live_training::auto_close_job(
+            database_pool.clone(),
+            chrono::Duration::hours(auto_close_hours),
+        ));
+    }
+
+    tokio::spawn(webhooks::delivery_job(database_pool.clone()));
+
     let app = Router::new()
         .route("/", get(root))
         .route("/health", get(health_check))
         .merge(users_router())
+        .merge(tokens_router())
+        .merge(webhooks_router())
+        .merge(calendar_router())
         .merge(exercise_router())
         .merge(workout_router())
         .merge(live_training_router())
+        .merge(live_stream_router())
         .merge(history_router())
+        .merge(history_import_router())
+        .merge(account_router())
+        .merge(api_router())
+        .merge(openapi_router())
+        .merge(equipment_router())
+        .merge(progression_router())
+        .merge(pwa_router())
         .nest_service("/static", ServeDir::new("static"))
         .layer(session_layer)
         .with_state(database_pool);
@@ -1,17 +1,29 @@
+use crate::progression::{Prescription, Strategy};
+use crate::units::WeightUnit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::ops::Range;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct ActiveWorkout {
     pub id: String,
     pub user_id: String,
-    pub workout_id: String,
+    pub workout_id: Option<String>,
     pub started_at: String,
     pub created_at: String,
+    /// Gesetzt, solange das Training pausiert ist.
+    pub paused_at: Option<String>,
+    /// Summe der abgeschlossenen Pausen.
+    pub paused_seconds: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+/// Anzeigename eines Trainings ohne Vorlage.
+pub const FREESTYLE_NAME: &str = "Freestyle";
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct CompletedSet {
     pub id: String,
     pub active_workout_id: String,
@@ -20,15 +32,80 @@ pub struct CompletedSet {
     pub weight: Option<f32>,
     pub reps: i32,
     pub notes: Option<String>,
+    /// Anstrengung auf der RPE-Skala (1–10). RIR-Eingaben landen als `10 - RIR`.
+    pub rpe: Option<f64>,
+    /// Satzart, siehe [`SetType`].
+    pub set_type: String,
     pub completed_at: String,
     pub created_at: String,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, FromRow)]
+/// Art eines erledigten Satzes. Aufwärmsätze zählen weder als Arbeitssatz
+/// noch zum Volumen oder zu Rekorden; alle anderen Arten schon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SetType {
+    Warmup,
+    #[default]
+    Working,
+    Drop,
+    Failure,
+    Amrap,
+}
+
+impl SetType {
+    /// Auch die Schreibweisen fremder Exporte: `normal`, `dropset`, `warm-up`.
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "warmup" | "warm-up" | "warm_up" => Some(SetType::Warmup),
+            "working" | "normal" => Some(SetType::Working),
+            "drop" | "dropset" | "drop_set" => Some(SetType::Drop),
+            "failure" => Some(SetType::Failure),
+            "amrap" => Some(SetType::Amrap),
+            _ => None,
+        }
+    }
+
+    /// Wert aus `completed_sets.set_type`. Unbekanntes gilt als Arbeitssatz,
+    /// wie vor der Spalte.
+    pub fn from_db(raw: &str) -> Self {
+        Self::parse(raw).unwrap_or_default()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SetType::Warmup => "warmup",
+            SetType::Working => "working",
+            SetType::Drop => "drop",
+            SetType::Failure => "failure",
+            SetType::Amrap => "amrap",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SetType::Warmup => "Warm-up",
+            SetType::Working => "Working",
+            SetType::Drop => "Drop set",
+            SetType::Failure => "To failure",
+            SetType::Amrap => "AMRAP",
+        }
+    }
+
+    pub fn is_warmup(self) -> bool {
+        self == SetType::Warmup
+    }
+
+    /// Kennzeichnung neben dem Satz; Arbeitssätze brauchen keine.
+    pub fn badge(self) -> Option<&'static str> {
+        (self != SetType::Working).then(|| self.label())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, FromRow, JsonSchema)]
 pub struct CompletedWorkout {
     pub id: String,
     pub user_id: String,
-    pub workout_id: String,
+    pub workout_id: Option<String>,
     pub started_at: String,
     pub completed_at: String,
     pub total_duration_minutes: i32,
@@ -36,6 +113,10 @@ pub struct CompletedWorkout {
     pub total_volume_kg: f32,
     pub notes: Option<String>,
     pub created_at: String,
+    /// Vom Hintergrundjob geschlossen statt vom Nutzer beendet.
+    pub auto_closed: bool,
+    /// JSON-Liste von [`ExerciseTime`], siehe [`session_timing`].
+    pub exercise_times: Option<String>,
 }
 
 // view modeL: exercise info
@@ -44,7 +125,12 @@ pub struct WorkoutExerciseDetail {
     pub position: i32,
     pub target_sets: i32,
     pub target_weight: Option<f32>,
+    pub target_reps_min: Option<i32>,
+    pub target_reps_max: Option<i32>,
+    pub rest_seconds: Option<i32>,
     pub notes: Option<String>,
+    pub progression: String,
+    pub group_label: Option<String>,
     pub exercise_id: String,
     pub exercise_name: String,
     pub exercise_instructions: String,
@@ -88,6 +174,167 @@ impl WorkoutExerciseDetail {
     pub fn youtube_embed_id(&self) -> Option<String> {
         youtube_embed_id(self.exercise_video_url.as_ref()?)
     }
+
+    pub fn strategy(&self) -> Strategy {
+        Strategy::from_db(&self.progression)
+    }
+
+    /// Planwerte für die Progressions-Engine.
+    pub fn prescription(&self) -> Prescription {
+        Prescription::new(
+            self.target_sets,
+            self.target_reps_min,
+            self.target_reps_max,
+            self.target_weight.map(f64::from),
+        )
+    }
+
+    pub fn group(&self) -> Option<&str> {
+        self.group_label.as_deref().map(str::trim).filter(|g| !g.is_empty())
+    }
+
+    /// Zielbereich als „8–12" oder „10", `None` ohne Vorgabe.
+    pub fn reps_display(&self) -> Option<String> {
+        match (self.target_reps_min, self.target_reps_max) {
+            (Some(min), Some(max)) if min != max => Some(format!("{}–{}", min, max)),
+            (Some(reps), _) | (None, Some(reps)) => Some(reps.to_string()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Block um die Übung an `index`: aufeinanderfolgende Übungen mit derselben
+/// Gruppe bilden einen Supersatz (zwei) oder Zirkel (mehr). Ohne Gruppe ist
+/// der Block die Übung allein. Nur benachbarte Übungen zählen — dieselbe
+/// Gruppe weiter hinten im Plan ist ein eigener Block.
+pub fn exercise_block(exercises: &[WorkoutExerciseDetail], index: usize) -> Range<usize> {
+    let Some(group) = exercises[index].group() else {
+        return index..index + 1;
+    };
+    let mut start = index;
+    while start > 0 && exercises[start - 1].group() == Some(group) {
+        start -= 1;
+    }
+    let mut end = index + 1;
+    while end < exercises.len() && exercises[end].group() == Some(group) {
+        end += 1;
+    }
+    start..end
+}
+
+/// Nächste Übung im geführten Training. `done` zählt die Arbeitssätze je
+/// Übung, in Planreihenfolge.
+///
+/// Blöcke werden nacheinander abgearbeitet; innerhalb eines Blocks geht es
+/// reihum — A1, B1, A2, B2 —, also zur offenen Übung mit den wenigsten
+/// Sätzen, bei Gleichstand zur früheren. Hat eine Übung mehr Sätze als die
+/// anderen, läuft sie am Ende allein weiter.
+pub fn next_exercise(exercises: &[WorkoutExerciseDetail], done: &[i32]) -> Option<usize> {
+    let open = |i: &usize| done[*i] < exercises[*i].target_sets;
+    let first = (0..exercises.len()).find(open)?;
+    exercise_block(exercises, first)
+        .filter(open)
+        .min_by_key(|&i| (done[i], i))
+}
+
+/// Ob nach dem nächsten Satz von `current` Pause ist: erst wenn die Runde
+/// durch ist oder der Block wechselt. Im Supersatz geht es von A direkt zu B.
+pub fn rest_after_set(exercises: &[WorkoutExerciseDetail], done: &[i32], current: usize) -> bool {
+    let mut after = done.to_vec();
+    after[current] += 1;
+    match next_exercise(exercises, &after) {
+        Some(next) => next <= current || !exercise_block(exercises, current).contains(&next),
+        None => false,
+    }
+}
+
+/// „Superset A: Bench Press + Row" für die Anzeige im Training, `None`
+/// außerhalb einer Gruppe.
+pub fn block_display(exercises: &[WorkoutExerciseDetail], index: usize) -> Option<String> {
+    let group = exercises[index].group()?;
+    let block = exercise_block(exercises, index);
+    if block.len() < 2 {
+        return None;
+    }
+    let kind = if block.len() == 2 { "Superset" } else { "Circuit" };
+    let names: Vec<&str> = exercises[block].iter().map(|e| e.exercise_name.as_str()).collect();
+    Some(format!("{kind} {group}: {}", names.join(" + ")))
+}
+
+/// Neue Reihenfolge der Sitzung, nachdem der Block um `index` nach vorn
+/// (Springen) oder ans Ende (Überspringen) verschoben wurde. Liefert die
+/// Planpositionen; ein Supersatz wandert immer als Ganzes.
+pub fn move_block(exercises: &[WorkoutExerciseDetail], index: usize, to_front: bool) -> Vec<i32> {
+    let block = exercise_block(exercises, index);
+    let moved = exercises[block.clone()].iter().map(|e| e.position);
+    let rest = exercises
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !block.contains(i))
+        .map(|(_, e)| e.position);
+    if to_front {
+        moved.chain(rest).collect()
+    } else {
+        rest.chain(moved).collect()
+    }
+}
+
+/// Fortschritt der Sitzung in Prozent: erledigte gegen vorgegebene
+/// Arbeitssätze, Zusatzsätze und spontane Übungen eingerechnet. Mehr Sätze
+/// als vorgegeben zählen nicht doppelt.
+pub fn progress_percent(exercises: &[WorkoutExerciseDetail], done: &[i32]) -> f32 {
+    let planned: i32 = exercises.iter().map(|e| e.target_sets).sum();
+    if planned == 0 {
+        return 0.0;
+    }
+    let completed: i32 = exercises
+        .iter()
+        .zip(done)
+        .map(|(e, done)| (*done).min(e.target_sets))
+        .sum();
+    completed as f32 / planned as f32 * 100.0
+}
+
+/// Eine Übung im Sitzungsplan der Live-Ansicht.
+#[derive(Debug, Clone)]
+pub struct SessionSlot {
+    pub position: i32,
+    pub name: String,
+    pub done: i32,
+    pub target_sets: i32,
+    /// Geplante Übung, falls für diese Sitzung ersetzt.
+    pub planned_name: Option<String>,
+    /// Nicht im Plan, erst in der Sitzung hinzugefügt.
+    pub is_ad_hoc: bool,
+    pub is_current: bool,
+}
+
+/// Für eine Sitzung ersetzte Übung; der Plan selbst bleibt unverändert.
+#[derive(Debug, Clone, Serialize)]
+pub struct Substitution {
+    pub position: i32,
+    pub planned_name: String,
+    pub exercise_name: String,
+}
+
+/// Formular für Überspringen und Springen.
+#[derive(Debug, Deserialize)]
+pub struct SessionExerciseForm {
+    pub position: i32,
+}
+
+/// Formular für eine spontan hinzugefügte Übung.
+#[derive(Debug, Deserialize)]
+pub struct AddSessionExerciseForm {
+    pub exercise_id: String,
+}
+
+/// Formular für das Ersetzen; leer oder die geplante Übung stellt den Plan
+/// wieder her.
+#[derive(Debug, Deserialize)]
+pub struct SubstituteExerciseForm {
+    pub position: i32,
+    pub exercise_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -106,20 +353,78 @@ pub struct CompletedSetDetail {
     pub weight: Option<f32>,
     pub reps: i32,
     pub notes: Option<String>,
+    pub rpe: Option<f64>,
+    pub set_type: String,
     pub completed_at: String,
     pub exercise_name: String,
     pub exercise_instructions: String,
 }
 
+impl CompletedSetDetail {
+    pub fn rpe_display(&self) -> Option<String> {
+        self.rpe.map(rpe_display)
+    }
+
+    pub fn set_type(&self) -> SetType {
+        SetType::from_db(&self.set_type)
+    }
+
+    /// Satznummer für die Anzeige: Aufwärmsätze werden getrennt gezählt
+    /// und erscheinen als `W1`, `W2`.
+    pub fn number_display(&self) -> String {
+        set_number_display(self.set_type(), self.set_number)
+    }
+}
+
+pub fn set_number_display(set_type: SetType, set_number: i32) -> String {
+    if set_type.is_warmup() {
+        format!("W{set_number}")
+    } else {
+        set_number.to_string()
+    }
+}
+
+/// `RPE 8`, `RPE 7.5`.
+pub fn rpe_display(rpe: f64) -> String {
+    format!("RPE {rpe}")
+}
+
+/// Optionale Anstrengung aus dem Satzformular. `scale` ist `rpe` oder `rir`;
+/// RIR wird als `10 - RIR` gespeichert, damit nur eine Skala in der
+/// Datenbank steht. Erlaubt sind halbe Schritte.
+pub fn parse_effort(raw: &str, scale: &str) -> Result<Option<f64>, String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Ok(None);
+    }
+    let value = raw
+        .replace(',', ".")
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite() && (v * 2.0).fract() == 0.0);
+    match scale.trim().to_lowercase().as_str() {
+        "" | "rpe" => value
+            .filter(|v| (1.0..=10.0).contains(v))
+            .map(Some)
+            .ok_or_else(|| "RPE must be between 1 and 10 in steps of 0.5".to_string()),
+        "rir" => value
+            .filter(|v| (0.0..=9.0).contains(v))
+            .map(|rir| Some(10.0 - rir))
+            .ok_or_else(|| "RIR must be between 0 and 9 in steps of 0.5".to_string()),
+        other => Err(format!("Unknown effort scale '{other}'")),
+    }
+}
+
 /// Trainingsdauer für die Anzeige, oder `None` wenn sie nichts aussagt.
 ///
 /// `0` heißt „nicht gemessen" und erschien bisher als „0m" — auf sieben von
 /// acht Einträgen der Seed-Datenbank. Werte über einem Tag entstehen, wenn ein
 /// Training offen blieb und Tage später abgeschlossen wurde; „526h 36m" hilft
-/// niemandem weiter.
+/// niemandem weiter. Neue Trainings zählen Pausen nicht mit und werden
+/// automatisch geschlossen, die Grenze bleibt für alte Zeilen.
 ///
-/// Frei stehende Funktion, weil zwei Typen dieselbe Anzeige brauchen:
-/// `CompletedWorkout` und `handlers::history::CompletedWorkoutWithName`.
+/// Frei stehende Funktion, weil Verlauf und Kalender dieselbe Anzeige
+/// brauchen: `handlers::history::CompletedWorkoutWithName` und der ICS-Export.
 pub fn duration_display(total_minutes: i32) -> Option<String> {
     const MAX_PLAUSIBLE_MINUTES: i32 = 24 * 60;
     match total_minutes {
@@ -129,6 +434,92 @@ pub fn duration_display(total_minutes: i32) -> Option<String> {
     }
 }
 
+/// Abstand zwischen zwei Sätzen, ab dem die Zeit als Leerlauf gilt: Das
+/// Training lag offen herum, gezählt wird sie nicht.
+pub const IDLE_GAP_SECONDS: i64 = 20 * 60;
+
+/// Zeit an einer Übung innerhalb eines Trainings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExerciseTime {
+    pub exercise_id: String,
+    pub seconds: i64,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct SessionTiming {
+    pub sets: usize,
+    /// Vom ersten bis zum letzten Satz, ohne Leerlauf.
+    pub active_seconds: i64,
+    pub exercises: Vec<ExerciseTime>,
+}
+
+/// Dauer eines Trainings aus den Zeitpunkten seiner Sätze, zeitlich
+/// sortiert. Jeder Abstand gehört zu der Übung, deren Satz ihn beendet —
+/// Pause und Satz davor. Ausdrückliche Pausen (`pauses`, Anfang und Ende)
+/// gehen vom Abstand ab; was danach über [`IDLE_GAP_SECONDS`] liegt, zählt
+/// nicht. Der erste Satz hat keinen Vorgänger und bringt nur die Übung in
+/// die Liste.
+pub fn session_timing(
+    sets: &[(String, DateTime<Utc>)],
+    pauses: &[(DateTime<Utc>, DateTime<Utc>)],
+) -> SessionTiming {
+    let mut timing = SessionTiming { sets: sets.len(), ..SessionTiming::default() };
+    let mut previous: Option<DateTime<Utc>> = None;
+    for (exercise_id, completed_at) in sets {
+        let gap = previous
+            .map(|p| {
+                let paused: i64 = pauses
+                    .iter()
+                    .map(|(from, to)| {
+                        let overlap = (*to).min(*completed_at).signed_duration_since((*from).max(p));
+                        overlap.num_seconds().max(0)
+                    })
+                    .sum();
+                completed_at.signed_duration_since(p).num_seconds() - paused
+            })
+            .filter(|gap| (0..=IDLE_GAP_SECONDS).contains(gap))
+            .unwrap_or(0);
+        previous = Some(*completed_at);
+
+        timing.active_seconds += gap;
+        match timing.exercises.iter_mut().find(|e| &e.exercise_id == exercise_id) {
+            Some(exercise) => exercise.seconds += gap,
+            None => timing.exercises.push(ExerciseTime {
+                exercise_id: exercise_id.clone(),
+                seconds: gap,
+            }),
+        }
+    }
+    timing
+}
+
+/// Zeitstempel aus der Datenbank. Neuere Zeilen sind RFC 3339, die Seed-Daten
+/// stammen aus SQLites `datetime('now')`.
+pub fn parse_timestamp(raw: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(raw)
+        .map(|t| t.with_timezone(&Utc))
+        .or_else(|_| {
+            chrono::NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S").map(|t| t.and_utc())
+        })
+        .ok()
+}
+
+/// Zeitpunkt eines nachgereichten Satzes. Leer heißt jetzt; in der Zukunft
+/// liegt ein offline geloggter Satz nie, ein paar Minuten Uhrenabweichung
+/// sind erlaubt.
+pub fn parse_logged_at(raw: Option<&str>, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let Some(raw) = raw.map(str::trim).filter(|r| !r.is_empty()) else {
+        return Ok(now);
+    };
+    let logged_at = DateTime::parse_from_rfc3339(raw)
+        .map_err(|_| format!("Invalid time '{raw}'"))?
+        .with_timezone(&Utc);
+    if logged_at > now + chrono::Duration::minutes(5) {
+        return Err("A set can't be logged in the future".to_string());
+    }
+    Ok(logged_at)
+}
+
 /// Notiz ohne leere Zeichenketten. Die Datenbank enthält `''` statt NULL,
 /// wodurch `Some("")` im Template als einsames Paar Anführungszeichen erschien.
 pub fn notes_display(notes: &Option<String>) -> Option<&str> {
@@ -136,37 +527,58 @@ pub fn notes_display(notes: &Option<String>) -> Option<&str> {
 }
 
 impl CompletedWorkout {
+    /// Abschluss zum Zeitpunkt `ended_at`. Summen und Dauer kommen allein
+    /// aus den Sätzen: Aufwärmsätze zählen weder als Satz noch zum Volumen,
+    /// die Dauer misst von Satz zu Satz ohne die beendeten `pauses` und eine
+    /// noch laufende (siehe [`session_timing`]). Mit weniger als zwei Sätzen
+    /// gibt es keinen Abstand zu messen; dann gilt die aktive Zeit seit dem
+    /// Start ohne Pausen.
     pub fn new(
         active_workout: ActiveWorkout,
-        total_sets: i32,
-        total_volume_kg: f32,
+        sets: &[CompletedSet],
+        pauses: &[(DateTime<Utc>, DateTime<Utc>)],
         notes: Option<String>,
+        ended_at: DateTime<Utc>,
     ) -> Self {
-        let now = chrono::Utc::now().to_rfc3339();
-        let started = chrono::DateTime::parse_from_rfc3339(&active_workout.started_at)
-            .unwrap_or_else(|_| chrono::Utc::now().into());
-        let duration_minutes = chrono::Utc::now()
-            .signed_duration_since(started)
-            .num_minutes() as i32;
+        let now = Utc::now().to_rfc3339();
+        let working: Vec<&CompletedSet> = sets.iter().filter(|s| !s.set_type().is_warmup()).collect();
+        let total_volume_kg: f64 = working
+            .iter()
+            .filter_map(|s| s.weight.map(|w| w as f64 * s.reps as f64))
+            .sum();
+
+        let mut set_times: Vec<(String, DateTime<Utc>)> = sets
+            .iter()
+            .filter_map(|s| parse_timestamp(&s.completed_at).map(|at| (s.exercise_id.clone(), at)))
+            .collect();
+        set_times.sort_by_key(|(_, at)| *at);
+        let mut pauses = pauses.to_vec();
+        if let Some(paused_at) = active_workout.paused_at.as_deref().and_then(parse_timestamp) {
+            pauses.push((paused_at, ended_at.max(paused_at)));
+        }
+        let timing = session_timing(&set_times, &pauses);
+        let duration_minutes = if timing.sets >= 2 {
+            timing.active_seconds / 60
+        } else {
+            active_workout.active_minutes_at(ended_at).unwrap_or(0)
+        };
 
         Self {
             id: active_workout.id,
             user_id: active_workout.user_id,
             workout_id: active_workout.workout_id,
             started_at: active_workout.started_at,
-            completed_at: now.clone(),
-            total_duration_minutes: duration_minutes,
-            total_sets,
-            total_volume_kg,
+            completed_at: ended_at.to_rfc3339(),
+            total_duration_minutes: duration_minutes as i32,
+            total_sets: working.len() as i32,
+            total_volume_kg: total_volume_kg as f32,
             notes,
             created_at: now,
+            auto_closed: false,
+            exercise_times: serde_json::to_string(&timing.exercises).ok(),
         }
     }
 
-    pub fn duration_display(&self) -> Option<String> {
-        duration_display(self.total_duration_minutes)
-    }
-
     pub fn notes_display(&self) -> Option<&str> {
         notes_display(&self.notes)
     }
@@ -182,22 +594,42 @@ impl CompletedWorkout {
 }
 
 impl ActiveWorkout {
-    pub fn new(user_id: String, workout_id: String) -> Self {
-        let now = chrono::Utc::now().to_rfc3339();
+    pub fn new(user_id: String, workout_id: Option<String>) -> Self {
+        let now = Utc::now().to_rfc3339();
         Self {
             id: Uuid::new_v4().to_string(),
             user_id,
             workout_id,
             started_at: now.clone(),
             created_at: now,
+            paused_at: None,
+            paused_seconds: 0,
         }
     }
 
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// Sekunden der laufenden Pause bis `at`, 0 ohne Pause.
+    pub fn current_pause_seconds(&self, at: DateTime<Utc>) -> i64 {
+        self.paused_at
+            .as_deref()
+            .and_then(|p| DateTime::parse_from_rfc3339(p).ok())
+            .map_or(0, |paused| at.signed_duration_since(paused).num_seconds().max(0))
+    }
+
+    /// Aktive Minuten bis `end`: Zeit seit dem Start ohne frühere und
+    /// laufende Pausen.
+    pub fn active_minutes_at(&self, end: DateTime<Utc>) -> Option<i64> {
+        let started = DateTime::parse_from_rfc3339(&self.started_at).ok()?;
+        let paused = self.paused_seconds + self.current_pause_seconds(end);
+        let seconds = end.signed_duration_since(started).num_seconds() - paused;
+        Some(seconds.max(0) / 60)
+    }
+
     pub fn duration_minutes(&self) -> Option<i64> {
-        let started = chrono::DateTime::parse_from_rfc3339(&self.started_at).ok()?;
-        let now = chrono::Utc::now();
-        let duration = now.signed_duration_since(started);
-        Some(duration.num_minutes())
+        self.active_minutes_at(Utc::now())
     }
 
     pub fn duration_display(&self) -> String {
@@ -226,26 +658,39 @@ impl CompletedSet {
             weight,
             reps,
             notes: None,
+            rpe: None,
+            set_type: SetType::default().as_str().to_string(),
             completed_at: now.clone(),
             created_at: now,
         }
     }
 
     #[allow(dead_code)]
-    pub fn weight_display(&self) -> String {
+    pub fn weight_display(&self, unit: WeightUnit) -> String {
         match self.weight {
-            Some(w) => format!("{:.1}kg", w),
+            Some(w) => unit.format(w),
             None => "Bodyweight".to_string(),
         }
     }
 
     #[allow(dead_code)]
-    pub fn set_display(&self) -> String {
-        format!("{} x {}", self.weight_display(), self.reps)
+    pub fn set_display(&self, unit: WeightUnit) -> String {
+        match self.rpe {
+            Some(rpe) => format!("{} x {} @ {}", self.weight_display(unit), self.reps, rpe_display(rpe)),
+            None => format!("{} x {}", self.weight_display(unit), self.reps),
+        }
+    }
+
+    pub fn set_type(&self) -> SetType {
+        SetType::from_db(&self.set_type)
     }
 
+    /// Volumen des Satzes; Aufwärmsätze zählen nicht.
     #[allow(dead_code)]
     pub fn volume(&self) -> f32 {
+        if self.set_type().is_warmup() {
+            return 0.0;
+        }
         self.weight.unwrap_or(0.0) * self.reps as f32
     }
 }
@@ -255,15 +700,25 @@ pub struct StartWorkoutForm {
     pub workout_id: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CompleteSetForm {
     pub exercise_id: String,
+    /// Im Formular in der Einheit des Nutzers, in der JSON-API immer kg.
     pub weight: Option<f32>,
     pub reps: i32,
     pub notes: Option<String>,
+    /// Freitext, leer heißt: nicht angegeben. Siehe `parse_effort`.
+    pub effort: Option<String>,
+    pub effort_scale: Option<String>,
+    /// Leer heißt Arbeitssatz. Siehe [`SetType::parse`].
+    pub set_type: Option<String>,
+    /// Vom Client erzeugter Schlüssel; nachgereichte Sätze zählen nur einmal.
+    pub client_request_id: Option<String>,
+    /// Wann der Satz offline geloggt wurde (RFC 3339), sonst jetzt.
+    pub logged_at: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct FinishTrainingForm {
     pub notes: Option<String>,
 }
@@ -283,10 +738,13 @@ mod tests {
             weight: Some(100.5),
             reps: 10,
             notes: None,
+            rpe: None,
+            set_type: "working".to_string(),
             completed_at: "2025-01-01T12:00:00Z".to_string(),
             created_at: "2025-01-01T12:00:00Z".to_string(),
         };
-        assert_eq!(set.weight_display(), "100.5kg");
+        assert_eq!(set.weight_display(WeightUnit::Kg), "100.5kg");
+        assert_eq!(set.weight_display(WeightUnit::Lb), "221.6lb");
     }
 
     #[test]
@@ -299,10 +757,12 @@ mod tests {
             weight: None,
             reps: 15,
             notes: None,
+            rpe: None,
+            set_type: "working".to_string(),
             completed_at: "2025-01-01T12:00:00Z".to_string(),
             created_at: "2025-01-01T12:00:00Z".to_string(),
         };
-        assert_eq!(set.weight_display(), "Bodyweight");
+        assert_eq!(set.weight_display(WeightUnit::Lb), "Bodyweight");
     }
 
     #[test]
@@ -315,10 +775,12 @@ mod tests {
             weight: Some(0.0),
             reps: 10,
             notes: None,
+            rpe: None,
+            set_type: "working".to_string(),
             completed_at: "2025-01-01T12:00:00Z".to_string(),
             created_at: "2025-01-01T12:00:00Z".to_string(),
         };
-        assert_eq!(set.weight_display(), "0.0kg");
+        assert_eq!(set.weight_display(WeightUnit::Kg), "0kg");
     }
 
     #[test]
@@ -331,10 +793,12 @@ mod tests {
             weight: Some(80.0),
             reps: 12,
             notes: None,
+            rpe: None,
+            set_type: "working".to_string(),
             completed_at: "2025-01-01T12:00:00Z".to_string(),
             created_at: "2025-01-01T12:00:00Z".to_string(),
         };
-        assert_eq!(set.set_display(), "80.0kg x 12");
+        assert_eq!(set.set_display(WeightUnit::Kg), "80kg x 12");
     }
 
     #[test]
@@ -347,10 +811,31 @@ mod tests {
             weight: None,
             reps: 20,
             notes: None,
+            rpe: None,
+            set_type: "working".to_string(),
             completed_at: "2025-01-01T12:00:00Z".to_string(),
             created_at: "2025-01-01T12:00:00Z".to_string(),
         };
-        assert_eq!(set.set_display(), "Bodyweight x 20");
+        assert_eq!(set.set_display(WeightUnit::Kg), "Bodyweight x 20");
+    }
+
+    #[test]
+    fn test_completed_set_display_with_rpe() {
+        let mut set = CompletedSet::new("w".into(), "e".into(), 1, Some(80.0), 8);
+        set.rpe = Some(8.5);
+        assert_eq!(set.set_display(WeightUnit::Kg), "80kg x 8 @ RPE 8.5");
+    }
+
+    #[test]
+    fn test_parse_effort_accepts_rpe_and_converts_rir() {
+        assert_eq!(parse_effort("", "rpe"), Ok(None));
+        assert_eq!(parse_effort("8", ""), Ok(Some(8.0)));
+        assert_eq!(parse_effort("7,5", "rpe"), Ok(Some(7.5)));
+        assert_eq!(parse_effort("2", "rir"), Ok(Some(8.0)));
+        assert!(parse_effort("11", "rpe").is_err());
+        assert!(parse_effort("8.3", "rpe").is_err());
+        assert!(parse_effort("-1", "rir").is_err());
+        assert!(parse_effort("8", "borg").is_err());
     }
 
     #[test]
@@ -363,6 +848,8 @@ mod tests {
             weight: Some(50.0),
             reps: 10,
             notes: None,
+            rpe: None,
+            set_type: "working".to_string(),
             completed_at: "2025-01-01T12:00:00Z".to_string(),
             created_at: "2025-01-01T12:00:00Z".to_string(),
         };
@@ -379,6 +866,8 @@ mod tests {
             weight: None,
             reps: 10,
             notes: None,
+            rpe: None,
+            set_type: "working".to_string(),
             completed_at: "2025-01-01T12:00:00Z".to_string(),
             created_at: "2025-01-01T12:00:00Z".to_string(),
         };
@@ -395,70 +884,54 @@ mod tests {
             weight: Some(100.0),
             reps: 0,
             notes: None,
+            rpe: None,
+            set_type: "working".to_string(),
             completed_at: "2025-01-01T12:00:00Z".to_string(),
             created_at: "2025-01-01T12:00:00Z".to_string(),
         };
         assert_eq!(set.volume(), 0.0);
     }
 
-    // CompletedWorkout Tests
     #[test]
-    fn test_completed_workout_duration_display_minutes() {
-        let workout = CompletedWorkout {
-            id: "test-id".to_string(),
-            user_id: "user-id".to_string(),
-            workout_id: "workout-id".to_string(),
-            started_at: "2025-01-01T12:00:00Z".to_string(),
-            completed_at: "2025-01-01T12:45:00Z".to_string(),
-            total_duration_minutes: 45,
-            total_sets: 10,
-            total_volume_kg: 1000.0,
-            notes: None,
-            created_at: "2025-01-01T12:45:00Z".to_string(),
-        };
-        assert_eq!(workout.duration_display().as_deref(), Some("45m"));
+    fn test_warmup_set_has_no_volume() {
+        let mut set = CompletedSet::new("w".into(), "e".into(), 1, Some(60.0), 5);
+        assert_eq!(set.volume(), 300.0);
+        set.set_type = SetType::Warmup.as_str().to_string();
+        assert_eq!(set.volume(), 0.0);
     }
 
     #[test]
-    fn test_completed_workout_duration_display_hours() {
-        let workout = CompletedWorkout {
-            id: "test-id".to_string(),
-            user_id: "user-id".to_string(),
-            workout_id: "workout-id".to_string(),
-            started_at: "2025-01-01T12:00:00Z".to_string(),
-            completed_at: "2025-01-01T14:30:00Z".to_string(),
-            total_duration_minutes: 150,
-            total_sets: 10,
-            total_volume_kg: 1000.0,
-            notes: None,
-            created_at: "2025-01-01T14:30:00Z".to_string(),
-        };
-        assert_eq!(workout.duration_display().as_deref(), Some("2h 30m"));
+    fn test_set_type_parses_export_spellings_and_db_falls_back_to_working() {
+        assert_eq!(SetType::parse("normal"), Some(SetType::Working));
+        assert_eq!(SetType::parse("Warm-up"), Some(SetType::Warmup));
+        assert_eq!(SetType::parse("dropset"), Some(SetType::Drop));
+        assert_eq!(SetType::parse("superset"), None);
+        assert_eq!(SetType::from_db("superset"), SetType::Working);
+        for set_type in [SetType::Warmup, SetType::Working, SetType::Drop, SetType::Failure, SetType::Amrap] {
+            assert_eq!(SetType::from_db(set_type.as_str()), set_type);
+        }
     }
 
     #[test]
-    fn test_completed_workout_duration_display_exact_hour() {
-        let workout = CompletedWorkout {
-            id: "test-id".to_string(),
-            user_id: "user-id".to_string(),
-            workout_id: "workout-id".to_string(),
-            started_at: "2025-01-01T12:00:00Z".to_string(),
-            completed_at: "2025-01-01T13:00:00Z".to_string(),
-            total_duration_minutes: 60,
-            total_sets: 10,
-            total_volume_kg: 1000.0,
-            notes: None,
-            created_at: "2025-01-01T13:00:00Z".to_string(),
-        };
-        assert_eq!(workout.duration_display().as_deref(), Some("1h 0m"));
+    fn test_warmups_are_numbered_separately() {
+        assert_eq!(set_number_display(SetType::Warmup, 2), "W2");
+        assert_eq!(set_number_display(SetType::Amrap, 3), "3");
+    }
+
+    #[test]
+    fn test_duration_display() {
+        assert_eq!(duration_display(45).as_deref(), Some("45m"));
+        assert_eq!(duration_display(150).as_deref(), Some("2h 30m"));
+        assert_eq!(duration_display(60).as_deref(), Some("1h 0m"));
     }
 
+    // CompletedWorkout Tests
     #[test]
     fn test_completed_workout_average_volume_per_set() {
         let workout = CompletedWorkout {
             id: "test-id".to_string(),
             user_id: "user-id".to_string(),
-            workout_id: "workout-id".to_string(),
+            workout_id: Some("workout-id".to_string()),
             started_at: "2025-01-01T12:00:00Z".to_string(),
             completed_at: "2025-01-01T13:00:00Z".to_string(),
             total_duration_minutes: 60,
@@ -466,6 +939,8 @@ mod tests {
             total_volume_kg: 1000.0,
             notes: None,
             created_at: "2025-01-01T13:00:00Z".to_string(),
+            auto_closed: false,
+            exercise_times: None,
         };
         assert_eq!(workout.average_volume_per_set(), 100.0);
     }
@@ -475,7 +950,7 @@ mod tests {
         let workout = CompletedWorkout {
             id: "test-id".to_string(),
             user_id: "user-id".to_string(),
-            workout_id: "workout-id".to_string(),
+            workout_id: Some("workout-id".to_string()),
             started_at: "2025-01-01T12:00:00Z".to_string(),
             completed_at: "2025-01-01T13:00:00Z".to_string(),
             total_duration_minutes: 60,
@@ -483,6 +958,8 @@ mod tests {
             total_volume_kg: 0.0,
             notes: None,
             created_at: "2025-01-01T13:00:00Z".to_string(),
+            auto_closed: false,
+            exercise_times: None,
         };
         assert_eq!(workout.average_volume_per_set(), 0.0);
     }
@@ -492,7 +969,7 @@ mod tests {
         let workout = CompletedWorkout {
             id: "test-id".to_string(),
             user_id: "user-id".to_string(),
-            workout_id: "workout-id".to_string(),
+            workout_id: Some("workout-id".to_string()),
             started_at: "2025-01-01T12:00:00Z".to_string(),
             completed_at: "2025-01-01T13:00:00Z".to_string(),
             total_duration_minutes: 60,
@@ -500,6 +977,8 @@ mod tests {
             total_volume_kg: 100.0,
             notes: None,
             created_at: "2025-01-01T13:00:00Z".to_string(),
+            auto_closed: false,
+            exercise_times: None,
         };
         assert!((workout.average_volume_per_set() - 33.333_332).abs() < 0.01);
     }
@@ -515,9 +994,11 @@ mod tests {
         let active_workout = ActiveWorkout {
             id: "test-id".to_string(),
             user_id: "user-id".to_string(),
-            workout_id: "workout-id".to_string(),
+            workout_id: Some("workout-id".to_string()),
             started_at: started_45_min_ago.to_rfc3339(),
             created_at: started_45_min_ago.to_rfc3339(),
+            paused_at: None,
+            paused_seconds: 0,
         };
 
         let display = active_workout.duration_display();
@@ -533,9 +1014,11 @@ mod tests {
         let active_workout = ActiveWorkout {
             id: "test-id".to_string(),
             user_id: "user-id".to_string(),
-            workout_id: "workout-id".to_string(),
+            workout_id: Some("workout-id".to_string()),
             started_at: started_2h_30m_ago.to_rfc3339(),
             created_at: started_2h_30m_ago.to_rfc3339(),
+            paused_at: None,
+            paused_seconds: 0,
         };
 
         let display = active_workout.duration_display();
@@ -547,9 +1030,11 @@ mod tests {
         let active_workout = ActiveWorkout {
             id: "test-id".to_string(),
             user_id: "user-id".to_string(),
-            workout_id: "workout-id".to_string(),
+            workout_id: Some("workout-id".to_string()),
             started_at: "invalid-timestamp".to_string(),
             created_at: "invalid-timestamp".to_string(),
+            paused_at: None,
+            paused_seconds: 0,
         };
 
         assert_eq!(active_workout.duration_display(), "0m");
@@ -563,9 +1048,11 @@ mod tests {
         let active_workout = ActiveWorkout {
             id: "test-id".to_string(),
             user_id: "user-id".to_string(),
-            workout_id: "workout-id".to_string(),
+            workout_id: Some("workout-id".to_string()),
             started_at: started_30_min_ago.to_rfc3339(),
             created_at: started_30_min_ago.to_rfc3339(),
+            paused_at: None,
+            paused_seconds: 0,
         };
 
         let duration = active_workout.duration_minutes();
@@ -579,9 +1066,11 @@ mod tests {
         let active_workout = ActiveWorkout {
             id: "test-id".to_string(),
             user_id: "user-id".to_string(),
-            workout_id: "workout-id".to_string(),
+            workout_id: Some("workout-id".to_string()),
             started_at: "invalid-timestamp".to_string(),
             created_at: "invalid-timestamp".to_string(),
+            paused_at: None,
+            paused_seconds: 0,
         };
 
         assert!(active_workout.duration_minutes().is_none());
@@ -629,4 +1118,196 @@ mod tests {
             Some("xyz789".to_string())
         );
     }
+
+    fn planned(name: &str, sets: i32, group: Option<&str>) -> WorkoutExerciseDetail {
+        WorkoutExerciseDetail {
+            position: 0,
+            target_sets: sets,
+            target_weight: None,
+            target_reps_min: None,
+            target_reps_max: None,
+            rest_seconds: None,
+            notes: None,
+            progression: "double".to_string(),
+            group_label: group.map(str::to_string),
+            exercise_id: name.to_lowercase(),
+            exercise_name: name.to_string(),
+            exercise_instructions: String::new(),
+            exercise_video_url: None,
+        }
+    }
+
+    #[test]
+    fn test_superset_alternates_and_rests_after_the_round() {
+        let plan = vec![
+            planned("Bench", 2, Some("A")),
+            planned("Row", 2, Some("A")),
+            planned("Curl", 2, None),
+        ];
+        let order: Vec<(usize, bool)> = {
+            let mut done = vec![0; plan.len()];
+            let mut order = Vec::new();
+            while let Some(next) = next_exercise(&plan, &done) {
+                order.push((next, rest_after_set(&plan, &done, next)));
+                done[next] += 1;
+            }
+            order
+        };
+        assert_eq!(
+            order,
+            vec![(0, false), (1, true), (0, false), (1, true), (2, true), (2, false)]
+        );
+    }
+
+    #[test]
+    fn test_uneven_superset_finishes_the_longer_exercise_alone() {
+        let plan = vec![planned("Bench", 1, Some("A")), planned("Row", 2, Some("A"))];
+        assert_eq!(next_exercise(&plan, &[1, 1]), Some(1));
+        assert!(!rest_after_set(&plan, &[1, 1], 1), "last set of the workout");
+        assert_eq!(next_exercise(&plan, &[1, 2]), None);
+    }
+
+    #[test]
+    fn test_blocks_need_neighbours() {
+        let plan = vec![
+            planned("Squat", 3, Some("A")),
+            planned("Plank", 3, None),
+            planned("Lunge", 3, Some("A")),
+        ];
+        assert_eq!(exercise_block(&plan, 0), 0..1);
+        assert_eq!(block_display(&plan, 0), None);
+
+        let circuit = vec![
+            planned("Squat", 3, Some("B")),
+            planned("Push-up", 3, Some("B")),
+            planned("Row", 3, Some("B")),
+        ];
+        assert_eq!(exercise_block(&circuit, 1), 0..3);
+        assert_eq!(block_display(&circuit, 2).as_deref(), Some("Circuit B: Squat + Push-up + Row"));
+    }
+
+    #[test]
+    fn test_move_block_keeps_supersets_together() {
+        let mut plan = vec![
+            planned("Squat", 3, None),
+            planned("Bench", 3, Some("A")),
+            planned("Row", 3, Some("A")),
+            planned("Curl", 3, None),
+        ];
+        for (position, exercise) in plan.iter_mut().enumerate() {
+            exercise.position = position as i32;
+        }
+        assert_eq!(move_block(&plan, 0, false), vec![1, 2, 3, 0]);
+        assert_eq!(move_block(&plan, 2, false), vec![0, 3, 1, 2]);
+        assert_eq!(move_block(&plan, 3, true), vec![3, 0, 1, 2]);
+        assert_eq!(move_block(&plan, 1, true), vec![1, 2, 0, 3]);
+    }
+
+    #[test]
+    fn test_progress_counts_extra_sets_and_caps_each_exercise() {
+        let plan = vec![planned("Squat", 3, None), planned("Bench", 1, None)];
+        assert_eq!(progress_percent(&plan, &[0, 0]), 0.0);
+        assert_eq!(progress_percent(&plan, &[5, 0]), 75.0);
+        assert_eq!(progress_percent(&plan, &[3, 1]), 100.0);
+        assert_eq!(progress_percent(&[], &[]), 0.0);
+    }
+
+    #[test]
+    fn test_paused_time_is_not_training_time() {
+        let end = chrono::Utc::now();
+        let mut active_workout = ActiveWorkout::new("user-id".to_string(), None);
+        active_workout.started_at = (end - chrono::Duration::minutes(90)).to_rfc3339();
+        active_workout.paused_seconds = 20 * 60;
+        assert_eq!(active_workout.active_minutes_at(end), Some(70));
+
+        active_workout.paused_at = Some((end - chrono::Duration::minutes(10)).to_rfc3339());
+        assert!(active_workout.is_paused());
+        assert_eq!(active_workout.active_minutes_at(end), Some(60));
+    }
+
+    #[test]
+    fn test_session_timing_skips_idle_gaps_and_splits_per_exercise() {
+        let start = chrono::Utc::now();
+        let at = |minutes: i64| start + chrono::Duration::minutes(minutes);
+        let sets = vec![
+            ("squat".to_string(), at(0)),
+            ("squat".to_string(), at(3)),
+            ("bench".to_string(), at(8)),
+            // Eine Stunde liegen gelassen.
+            ("bench".to_string(), at(68)),
+            ("bench".to_string(), at(71)),
+        ];
+        let timing = session_timing(&sets, &[]);
+        assert_eq!(timing.sets, 5);
+        assert_eq!(timing.active_seconds, 11 * 60);
+        assert_eq!(
+            timing.exercises,
+            vec![
+                ExerciseTime { exercise_id: "squat".to_string(), seconds: 3 * 60 },
+                ExerciseTime { exercise_id: "bench".to_string(), seconds: 8 * 60 },
+            ]
+        );
+        assert_eq!(session_timing(&[], &[]), SessionTiming::default());
+    }
+
+    #[test]
+    fn test_pauses_between_sets_are_not_training_time() {
+        let start = chrono::Utc::now();
+        let at = |minutes: i64| start + chrono::Duration::minutes(minutes);
+        let active_workout = ActiveWorkout::new("user".to_string(), None);
+        let set = |minutes: i64| {
+            let mut set = CompletedSet::new(active_workout.id.clone(), "squat".to_string(), 1, Some(100.0), 5);
+            set.completed_at = at(minutes).to_rfc3339();
+            set
+        };
+        // 15 Minuten Pause zwischen zwei Sätzen, die 19 Minuten auseinander
+        // liegen: unter der Leerlaufgrenze, zählte also bisher voll.
+        let sets = vec![set(0), set(19)];
+        let pauses = [(at(2), at(17))];
+
+        let workout = CompletedWorkout::new(active_workout.clone(), &sets, &pauses, None, at(20));
+        assert_eq!(workout.total_duration_minutes, 4);
+        let times: Vec<ExerciseTime> = serde_json::from_str(workout.exercise_times.as_deref().unwrap()).unwrap();
+        assert_eq!(times[0].seconds, 4 * 60);
+
+        // Noch laufende Pause seit Minute 5, beendet beim Abschluss.
+        let mut paused = active_workout;
+        paused.paused_at = Some(at(5).to_rfc3339());
+        let workout = CompletedWorkout::new(paused, &sets, &[], None, at(20));
+        assert_eq!(workout.total_duration_minutes, 5);
+    }
+
+    #[test]
+    fn test_totals_come_from_working_sets() {
+        let start = chrono::Utc::now();
+        let active_workout = ActiveWorkout::new("user".to_string(), None);
+        let set = |minutes: i64, set_type: SetType| {
+            let mut set = CompletedSet::new(active_workout.id.clone(), "squat".to_string(), 1, Some(100.0), 5);
+            set.set_type = set_type.as_str().to_string();
+            set.completed_at = (start + chrono::Duration::minutes(minutes)).to_rfc3339();
+            set
+        };
+        let sets = vec![set(0, SetType::Warmup), set(4, SetType::default()), set(7, SetType::default())];
+
+        let workout = CompletedWorkout::new(active_workout, &sets, &[], None, start + chrono::Duration::hours(1));
+        assert_eq!(workout.total_sets, 2);
+        assert_eq!(workout.total_volume_kg, 1000.0);
+        // Der Aufwärmsatz zählt nicht als Satz, aber zur Zeit.
+        assert_eq!(workout.total_duration_minutes, 7);
+    }
+
+    #[test]
+    fn test_parse_logged_at() {
+        let now = chrono::Utc::now();
+        assert_eq!(parse_logged_at(None, now), Ok(now));
+        assert_eq!(parse_logged_at(Some(" "), now), Ok(now));
+        let earlier = now - chrono::Duration::minutes(30);
+        assert_eq!(
+            parse_logged_at(Some(&earlier.to_rfc3339()), now).map(|t| t.timestamp()),
+            Ok(earlier.timestamp())
+        );
+        let later = now + chrono::Duration::hours(1);
+        assert!(parse_logged_at(Some(&later.to_rfc3339()), now).is_err());
+        assert!(parse_logged_at(Some("gestern"), now).is_err());
+    }
 }
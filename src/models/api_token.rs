@@ -0,0 +1,32 @@
+use crate::api_token::TokenScope;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Persönliches Zugangstoken, ohne das Token selbst — davon gibt es nur
+/// den Hash, und der bleibt in der Datenbank.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
+pub struct ApiToken {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    /// `read` oder `read_write`, siehe [`TokenScope`].
+    pub scope: String,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+}
+
+impl ApiToken {
+    pub fn scope(&self) -> TokenScope {
+        TokenScope::from_db(&self.scope)
+    }
+
+    pub fn created_date(&self) -> &str {
+        self.created_at.split('T').next().unwrap_or(&self.created_at)
+    }
+
+    pub fn last_used_date(&self) -> Option<&str> {
+        let used = self.last_used_at.as_deref()?;
+        Some(used.split('T').next().unwrap_or(used))
+    }
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Geheimer Kalender-Link eines Nutzers, siehe [`crate::ical`].
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CalendarFeed {
+    pub user_id: String,
+    pub token: String,
+    /// Abgeschlossene Trainings zusätzlich als vergangene Termine.
+    pub include_history: bool,
+    pub created_at: String,
+}
+
+impl CalendarFeed {
+    /// Relativ; die Seite macht daraus die volle URL zum Kopieren.
+    pub fn path(&self) -> String {
+        format!("/calendar/{}.ics", self.token)
+    }
+}
@@ -1,9 +1,17 @@
 pub mod active_workout;
+pub mod api_token;
+pub mod calendar_feed;
 pub mod exercise;
+pub mod personal_record;
 pub mod user;
+pub mod webhook;
 pub mod workout;
 
 pub use active_workout::*;
+pub use api_token::ApiToken;
+pub use calendar_feed::CalendarFeed;
 pub use exercise::Exercise;
+pub use personal_record::PersonalRecord;
 pub use user::User;
+pub use webhook::Webhook;
 pub use workout::*;
@@ -0,0 +1,20 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Bester Wert eines Nutzers in einer Übung, je `record_type` einer.
+/// Gewichte in kg.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
+pub struct PersonalRecord {
+    pub id: String,
+    pub user_id: String,
+    pub exercise_id: String,
+    /// `max_weight`, `max_reps` oder `max_volume`.
+    pub record_type: String,
+    pub weight: Option<f64>,
+    pub reps: Option<i64>,
+    pub volume_kg: Option<f64>,
+    pub completed_set_id: String,
+    pub achieved_at: String,
+    pub created_at: String,
+}
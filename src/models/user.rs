@@ -1,12 +1,19 @@
+use crate::units::WeightUnit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct User {
     pub id: String,
     pub name: String,
     pub created_at: String,
+    /// `kg` oder `lb`, siehe [`User::unit`].
+    pub weight_unit: String,
+    /// Aufwärmrampe im Training vorschlagen, siehe
+    /// [`crate::progression::warmup_ramp`].
+    pub warmup_ramps: bool,
 }
 
 impl User {
@@ -15,6 +22,12 @@ impl User {
             id: Uuid::new_v4().to_string(),
             name,
             created_at: chrono::Utc::now().to_rfc3339(),
+            weight_unit: WeightUnit::Kg.as_str().to_string(),
+            warmup_ramps: false,
         }
     }
+
+    pub fn unit(&self) -> WeightUnit {
+        WeightUnit::from_db(&self.weight_unit)
+    }
 }
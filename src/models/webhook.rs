@@ -0,0 +1,29 @@
+use crate::webhooks::WebhookEvent;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Webhook-Abo eines Nutzers. `events` ist kommagetrennt, siehe
+/// [`WebhookEvent::as_str`].
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Webhook {
+    pub id: String,
+    pub user_id: String,
+    pub url: String,
+    pub secret: String,
+    pub events: String,
+    pub created_at: String,
+}
+
+impl Webhook {
+    pub fn events(&self) -> Vec<WebhookEvent> {
+        self.events.split(',').filter_map(WebhookEvent::parse).collect()
+    }
+
+    pub fn event_labels(&self) -> String {
+        self.events()
+            .iter()
+            .map(|event| event.label())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
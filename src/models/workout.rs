@@ -1,4 +1,6 @@
+use crate::progression::Strategy;
 use ::chrono::Datelike;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, types::chrono};
 use uuid::Uuid;
@@ -35,7 +37,7 @@ impl ScheduleType {
 }
 
 // a single workout
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct Workout {
     pub id: String,
     pub user_id: String,
@@ -49,7 +51,7 @@ pub struct Workout {
 }
 
 // a single exercise as part of an workout
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct WorkoutExercise {
     pub id: String,
     pub workout_id: String,
@@ -57,12 +59,19 @@ pub struct WorkoutExercise {
     pub position: i32,
     pub target_sets: i32,
     pub target_weight: Option<f32>,
+    pub target_reps_min: Option<i32>,
+    pub target_reps_max: Option<i32>,
+    pub rest_seconds: Option<i32>,
     pub notes: Option<String>,
+    /// Progressionsstrategie, siehe `progression::Strategy`.
+    pub progression: String,
+    /// Supersatz oder Zirkel, siehe [`parse_group_label`]. `None` steht allein.
+    pub group_label: Option<String>,
     pub created_at: String,
 }
 
 // view model: workout with exercises
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WorkoutWithExercises {
     pub workout: Workout,
     pub exercises: Vec<WorkoutExercise>,
@@ -136,7 +145,12 @@ impl WorkoutExercise {
             position,
             target_sets,
             target_weight,
+            target_reps_min: None,
+            target_reps_max: None,
+            rest_seconds: None,
             notes: None,
+            progression: Strategy::default().as_str().to_string(),
+            group_label: None,
             created_at: chrono::Utc::now().to_rfc3339(),
         }
     }
@@ -162,7 +176,60 @@ pub struct AddExerciseToWorkoutForm {
     pub exercise_id: String,
     pub target_sets: i32,
     pub target_weight: Option<f32>,
+    /// Freitext wie im YAML-Format: `8` oder `8-12`. String statt Zahl, weil
+    /// ein leeres Feld als "" ankommt und sonst das ganze Formular scheitert.
+    pub target_reps: Option<String>,
+    pub rest_seconds: Option<String>,
     pub notes: Option<String>,
+    pub progression: Option<String>,
+    pub group_label: Option<String>,
+}
+
+pub const MAX_GROUP_LABEL_LEN: usize = 10;
+
+/// Gruppe aus einem Formularfeld: Buchstaben und Ziffern, großgeschrieben
+/// gespeichert — `a` und `A` sind derselbe Supersatz. Leer heißt: allein.
+pub fn parse_group_label(raw: &str) -> Result<Option<String>, String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Ok(None);
+    }
+    if raw.chars().count() > MAX_GROUP_LABEL_LEN || !raw.chars().all(char::is_alphanumeric) {
+        return Err(format!(
+            "Group must be up to {MAX_GROUP_LABEL_LEN} letters or digits, like A or B2, found '{raw}'"
+        ));
+    }
+    Ok(Some(raw.to_uppercase()))
+}
+
+pub const MAX_REPS: i32 = 100;
+
+/// `8` oder `8-12` — aus dem Formular und aus dem Plan-Import
+/// (`workout_yaml`). Leer heißt: kein Zielbereich.
+pub fn parse_rep_range(raw: &str) -> Result<Option<(i32, i32)>, String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Ok(None);
+    }
+    let parse = |s: &str| {
+        s.trim()
+            .parse::<i32>()
+            .map_err(|_| format!("Reps must be a number or a range like 8-12, found '{raw}'"))
+    };
+    let (min, max) = match raw.split_once('-') {
+        Some((low, high)) => (parse(low)?, parse(high)?),
+        None => {
+            let reps = parse(raw)?;
+            (reps, reps)
+        }
+    };
+    if min < 1 || max > MAX_REPS {
+        return Err(format!("Reps must be between 1 and {MAX_REPS}"));
+    }
+    if min > max {
+        return Err(format!("Rep range {min}-{max} is reversed: lower bound is above upper bound"));
+    }
+    Ok(Some((min, max)))
 }
 
 #[derive(Debug, Deserialize)]
@@ -377,6 +444,25 @@ mod tests {
         assert_ne!(workout.updated_at, original_updated_at);
     }
 
+    #[test]
+    fn test_parse_rep_range() {
+        assert_eq!(parse_rep_range(""), Ok(None));
+        assert_eq!(parse_rep_range(" 8-12 "), Ok(Some((8, 12))));
+        assert_eq!(parse_rep_range("5"), Ok(Some((5, 5))));
+        assert!(parse_rep_range("12-8").is_err());
+        assert!(parse_rep_range("0").is_err());
+        assert!(parse_rep_range("acht").is_err());
+    }
+
+    #[test]
+    fn test_parse_group_label() {
+        assert_eq!(parse_group_label(" "), Ok(None));
+        assert_eq!(parse_group_label(" a "), Ok(Some("A".to_string())));
+        assert_eq!(parse_group_label("b2"), Ok(Some("B2".to_string())));
+        assert!(parse_group_label("A 1").is_err());
+        assert!(parse_group_label("ABCDEFGHIJK").is_err());
+    }
+
     // WorkoutExercise Tests
     #[test]
     fn test_workout_exercise_weight_display_with_weight() {
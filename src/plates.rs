@@ -0,0 +1,388 @@
+//! Scheibenrechner: welche Scheiben auf jede Seite der Stange gehören.
+//!
+//! Gerechnet wird in der Einheit, in der die Scheiben beschriftet sind —
+//! eine 45-lb-Scheibe ist keine 20.41-kg-Scheibe. Zielgewichte kommen wie
+//! überall in kg und werden vorher umgerechnet.
+//!
+//! Statt gierig die schwerste passende Scheibe zu nehmen, werden alle
+//! erreichbaren Lasten pro Seite durchgerechnet. Das findet auch mit
+//! begrenzten oder krummen Scheiben (15er ohne 5er) die nächste Last.
+
+use crate::units::{LB_IN_KG, WeightUnit};
+
+/// Abweichung, die noch als „genau" gilt. Gespeichert wird auf 10 g, und
+/// 135 lb kommen als 134.99 lb zurück.
+const TOLERANCE: f64 = 0.025;
+
+pub const MAX_PLATE_TYPES: usize = 20;
+/// Leichteste Scheibe, die `parse_plates` annimmt. Darunter gibt es keine
+/// Hantelscheiben, und die Suche hätte zu viele Zustände.
+pub const MIN_PLATE: f64 = 0.25;
+pub const MAX_PLATE: f64 = 100.0;
+
+/// Höchstens so viele Schritte pro Seite durchrechnen; darüber wird gierig
+/// geladen. Mit 0.25er-Scheiben sind das 500 pro Seite.
+const MAX_STATES: usize = 2000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plate {
+    pub weight: f64,
+    /// Höchstens so viele pro Seite. `None`: so viele wie nötig.
+    pub per_side: Option<u32>,
+}
+
+/// Ausstattung eines Nutzers, alle Gewichte in `unit`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Equipment {
+    pub unit: WeightUnit,
+    pub bar: f64,
+    /// Pro Verschluss; eine Stange trägt zwei.
+    pub collar: f64,
+    /// Schwerste zuerst.
+    pub plates: Vec<Plate>,
+}
+
+/// Ergebnis für ein Zielgewicht, in der Einheit der Ausstattung.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Loading {
+    pub unit: WeightUnit,
+    pub target: f64,
+    /// Scheiben für eine Seite, schwerste zuerst.
+    pub per_side: Vec<f64>,
+    /// Was mit diesen Scheiben tatsächlich auf der Stange liegt.
+    pub total: f64,
+    pub exact: bool,
+}
+
+impl Loading {
+    /// `20 + 10 + 2.5` — oder `empty bar`.
+    pub fn per_side_display(&self) -> String {
+        if self.per_side.is_empty() {
+            return "empty bar".to_string();
+        }
+        self.per_side
+            .iter()
+            .map(|p| self.unit.amount(*p))
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+
+    pub fn target_display(&self) -> String {
+        format!("{}{}", self.unit.amount(self.target), self.unit)
+    }
+
+    pub fn total_display(&self) -> String {
+        format!("{}{}", self.unit.amount(self.total), self.unit)
+    }
+}
+
+impl Equipment {
+    /// Olympische Stange und übliche Hantelscheiben.
+    pub fn default_for(unit: WeightUnit) -> Self {
+        let (bar, plates): (f64, &[f64]) = match unit {
+            WeightUnit::Kg => (20.0, &[25.0, 20.0, 15.0, 10.0, 5.0, 2.5, 1.25]),
+            WeightUnit::Lb => (45.0, &[45.0, 35.0, 25.0, 10.0, 5.0, 2.5]),
+        };
+        Equipment {
+            unit,
+            bar,
+            collar: 0.0,
+            plates: plates.iter().map(|&weight| Plate { weight, per_side: None }).collect(),
+        }
+    }
+
+    /// Scheibenliste wie im Formular: `25, 20, 10x2, 1.25`. `x2` begrenzt
+    /// auf zwei pro Seite; das Komma trennt, Dezimalzeichen ist der Punkt.
+    pub fn parse_plates(raw: &str) -> Result<Vec<Plate>, String> {
+        let mut plates = Vec::new();
+        for part in raw.split([',', ';']).map(str::trim).filter(|p| !p.is_empty()) {
+            let (weight, count) = match part.split_once(['x', 'X', '×']) {
+                Some((w, c)) => {
+                    let count = c
+                        .trim()
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|c| *c > 0)
+                        .ok_or_else(|| format!("'{part}': the count after 'x' must be a whole number above 0"))?;
+                    (w, Some(count))
+                }
+                None => (part, None),
+            };
+            let weight = weight
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|w| w.is_finite() && (MIN_PLATE..=MAX_PLATE).contains(w))
+                .ok_or_else(|| format!("'{part}' is not a plate weight between {MIN_PLATE} and {MAX_PLATE}"))?;
+            plates.push(Plate { weight, per_side: count });
+        }
+        if plates.len() > MAX_PLATE_TYPES {
+            return Err(format!("at most {MAX_PLATE_TYPES} plate sizes"));
+        }
+        plates.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+        plates.dedup_by(|a, b| a.weight == b.weight);
+        Ok(plates)
+    }
+
+    /// Gegenstück zu `parse_plates` fürs Formular.
+    pub fn plates_text(&self) -> String {
+        self.plates
+            .iter()
+            .map(|p| match p.per_side {
+                Some(n) => format!("{}x{n}", self.unit.amount(p.weight)),
+                None => self.unit.amount(p.weight),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn bar_text(&self) -> String {
+        self.unit.amount(self.bar)
+    }
+
+    pub fn collar_text(&self) -> String {
+        self.unit.amount(self.collar)
+    }
+
+    /// Stange mit Verschlüssen, ohne Scheiben.
+    pub fn empty_bar(&self) -> f64 {
+        self.bar + 2.0 * self.collar
+    }
+
+    /// Scheiben für `target_kg`. Ist das Ziel nicht genau ladbar, liegt in
+    /// `total` das nächste ladbare Gewicht — bei Gleichstand das leichtere.
+    pub fn load(&self, target_kg: f64) -> Loading {
+        let target = match self.unit {
+            WeightUnit::Kg => target_kg,
+            WeightUnit::Lb => target_kg / LB_IN_KG,
+        };
+        let empty = self.empty_bar();
+        let mut loading = Loading {
+            unit: self.unit,
+            target,
+            per_side: Vec::new(),
+            total: empty,
+            exact: (target - empty).abs() <= TOLERANCE,
+        };
+        let wanted = (target - empty) / 2.0;
+        if wanted <= 0.0 || self.plates.is_empty() {
+            return loading;
+        }
+
+        // In Hundertsteln rechnen, und zwar in Schritten des größten
+        // gemeinsamen Teilers — bei 1.25 als kleinster Scheibe sind das
+        // wenige hundert Zustände. Was auf null Hundertstel rundet, ist
+        // keine Scheibe.
+        let hundredths: Vec<u64> = self.plates.iter().map(|p| (p.weight * 100.0).round() as u64).collect();
+        let step = hundredths.iter().copied().fold(0, gcd).max(1);
+        let sizes: Vec<usize> = hundredths.iter().map(|h| (h / step) as usize).collect();
+        let wanted_steps = wanted * 100.0 / step as f64;
+        let cap = (wanted_steps.ceil() as usize).saturating_add(sizes.iter().copied().max().unwrap_or(0));
+        if cap > MAX_STATES {
+            return self.load_greedy(loading, wanted);
+        }
+
+        // best[s]: wenigste Scheiben für s Schritte, mit Anzahl je Sorte
+        // (Reihenfolge wie `plates`, schwerste zuerst).
+        let mut best: Vec<Option<(u32, Vec<u32>)>> = vec![None; cap + 1];
+        best[0] = Some((0, vec![0; sizes.len()]));
+        for (index, (&size, plate)) in sizes.iter().zip(&self.plates).enumerate() {
+            if size == 0 {
+                continue;
+            }
+            let most = cap / size;
+            match plate.per_side.map(|n| n as usize).filter(|&n| n < most) {
+                // Begrenzt: vom Stand vor dieser Sorte aus, höchstens `limit`.
+                Some(limit) => {
+                    let previous = best.clone();
+                    for (s, slot) in previous.iter().enumerate() {
+                        let Some((count, counts)) = slot else { continue };
+                        for k in 1..=limit {
+                            let reached = s + k * size;
+                            if reached > cap {
+                                break;
+                            }
+                            offer(&mut best, reached, count + k as u32, counts, index, k as u32);
+                        }
+                    }
+                }
+                // Unbegrenzt: aufsteigend, jede Lage darf auf sich selbst aufbauen.
+                None => {
+                    for s in 0..=cap - size {
+                        let Some((count, counts)) = best[s].clone() else { continue };
+                        offer(&mut best, s + size, count + 1, &counts, index, 1);
+                    }
+                }
+            }
+        }
+
+        let Some((steps, counts)) = best
+            .iter()
+            .enumerate()
+            .filter_map(|(s, slot)| slot.as_ref().map(|(_, counts)| (s, counts)))
+            .min_by(|(a, _), (b, _)| {
+                let da = (*a as f64 - wanted_steps).abs();
+                let db = (*b as f64 - wanted_steps).abs();
+                da.total_cmp(&db).then(a.cmp(b))
+            })
+        else {
+            return loading;
+        };
+
+        for (plate, &n) in self.plates.iter().zip(counts) {
+            loading.per_side.extend(std::iter::repeat_n(plate.weight, n as usize));
+        }
+        loading.total = empty + 2.0 * (steps as u64 * step) as f64 / 100.0;
+        loading.exact = (loading.total - target).abs() <= TOLERANCE;
+        loading
+    }
+
+    /// Für Lasten jenseits von [`MAX_STATES`]: schwerste Scheibe zuerst,
+    /// so viele wie passen, ohne über das Ziel zu gehen.
+    fn load_greedy(&self, mut loading: Loading, wanted: f64) -> Loading {
+        let mut left = wanted;
+        for plate in &self.plates {
+            if plate.weight < MIN_PLATE {
+                continue;
+            }
+            // Mehr als MAX_STATES gleiche Scheiben trägt keine Stange.
+            let fits = ((left + TOLERANCE / 2.0) / plate.weight).floor().clamp(0.0, MAX_STATES as f64);
+            let n = plate.per_side.map_or(fits, |most| fits.min(most as f64)) as usize;
+            loading.per_side.extend(std::iter::repeat_n(plate.weight, n));
+            left -= n as f64 * plate.weight;
+        }
+        loading.total = self.empty_bar() + 2.0 * loading.per_side.iter().sum::<f64>();
+        loading.exact = (loading.total - loading.target).abs() <= TOLERANCE;
+        loading
+    }
+
+    /// Wie `load`, aber nur, wenn das Ziel überhaupt eine beladene Stange
+    /// ist. Leichter als die leere Stange heißt: Kurzhantel, Maschine,
+    /// Kabelzug — dort gibt es nichts zu rechnen.
+    pub fn barbell(&self, target_kg: f64) -> Option<Loading> {
+        let loading = self.load(target_kg);
+        (loading.target + TOLERANCE >= self.empty_bar()).then_some(loading)
+    }
+
+    /// Nächstes ladbares Gewicht in kg. Ohne Stange bleibt das Gewicht, wie
+    /// es ist.
+    pub fn nearest_loadable_kg(&self, kg: f64) -> f64 {
+        match self.barbell(kg) {
+            Some(loading) => self.unit.to_kg(loading.total),
+            None => kg,
+        }
+    }
+}
+
+/// `best[reached]` übernehmen, wenn `count` Scheiben besser sind als was dort
+/// steht. Bei gleich vielen Scheiben die schwereren: 25 + 15 statt 20 + 20,
+/// so wie man im Studio lädt.
+fn offer(best: &mut [Option<(u32, Vec<u32>)>], reached: usize, count: u32, from: &[u32], index: usize, added: u32) {
+    let mut counts = from.to_vec();
+    counts[index] += added;
+    let better = match &best[reached] {
+        None => true,
+        Some((c, existing)) => count < *c || (count == *c && counts > *existing),
+    };
+    if better {
+        best[reached] = Some((count, counts));
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_kg_plates_load_exactly() {
+        let gym = Equipment::default_for(WeightUnit::Kg);
+        let loading = gym.load(102.5);
+        assert!(loading.exact);
+        assert_eq!(loading.per_side, vec![25.0, 15.0, 1.25]);
+        assert_eq!(loading.per_side_display(), "25 + 15 + 1.25");
+        assert_eq!(gym.load(20.0).per_side_display(), "empty bar");
+    }
+
+    #[test]
+    fn pound_plates_are_counted_in_pounds() {
+        let gym = Equipment::default_for(WeightUnit::Lb);
+        // 135 lb, gespeichert als 61.23 kg
+        let loading = gym.load(WeightUnit::Lb.to_kg(135.0));
+        assert!(loading.exact, "{loading:?}");
+        assert_eq!(loading.per_side, vec![45.0]);
+        assert_eq!(loading.total_display(), "135lb");
+    }
+
+    #[test]
+    fn unreachable_targets_name_the_closest_weight() {
+        let gym = Equipment::default_for(WeightUnit::Kg);
+        let loading = gym.load(83.0);
+        assert!(!loading.exact);
+        assert_eq!(loading.total, 82.5);
+        // Gleichstand zwischen 82.5 und 85 → das leichtere
+        assert_eq!(gym.load(83.75).total, 82.5);
+        assert_eq!(gym.nearest_loadable_kg(83.0), 82.5);
+    }
+
+    #[test]
+    fn limited_plates_and_collars_are_respected() {
+        let gym = Equipment {
+            unit: WeightUnit::Kg,
+            bar: 15.0,
+            collar: 2.5,
+            plates: Equipment::parse_plates("20x1, 10x1").unwrap(),
+        };
+        // 15 + 2×2.5 + 2×(20 + 10) = 80, mehr geht nicht
+        let loading = gym.load(100.0);
+        assert_eq!(loading.total, 80.0);
+        assert_eq!(loading.per_side, vec![20.0, 10.0]);
+    }
+
+    #[test]
+    fn light_weights_stay_untouched() {
+        let gym = Equipment::default_for(WeightUnit::Kg);
+        assert_eq!(gym.nearest_loadable_kg(12.0), 12.0);
+        assert!(gym.barbell(12.0).is_none());
+        assert!(gym.barbell(20.0).is_some());
+    }
+
+    #[test]
+    fn plate_lists_parse_and_print() {
+        let plates = Equipment::parse_plates("1.25, 25 ,10 x 2; 25").unwrap();
+        assert_eq!(plates, vec![
+            Plate { weight: 25.0, per_side: None },
+            Plate { weight: 10.0, per_side: Some(2) },
+            Plate { weight: 1.25, per_side: None },
+        ]);
+        let gym = Equipment { plates, ..Equipment::default_for(WeightUnit::Kg) };
+        assert_eq!(gym.plates_text(), "25, 10x2, 1.25");
+        assert!(Equipment::parse_plates("20, heavy").unwrap_err().contains("heavy"));
+        assert!(Equipment::parse_plates("20x0").unwrap_err().contains("above 0"));
+        assert!(Equipment::parse_plates("20, 0.004").unwrap_err().contains("between 0.25 and 100"));
+        assert!(Equipment::parse_plates("0.25, 101").is_err());
+    }
+
+    #[test]
+    fn huge_targets_and_tiny_plates_stay_cheap() {
+        let gym = Equipment {
+            plates: Equipment::parse_plates("20, 0.25").unwrap(),
+            ..Equipment::default_for(WeightUnit::Kg)
+        };
+        // 4000 kg sind weit über dem Zustandsbudget: gierig, aber genau.
+        let loading = gym.load(4000.0);
+        assert!(loading.exact, "{loading:?}");
+        assert_eq!(loading.per_side.iter().filter(|&&p| p == 20.0).count(), 99);
+
+        // Von Hand gebaute Ausstattung mit einer Scheibe, die auf null rundet.
+        let odd = Equipment {
+            plates: vec![Plate { weight: 10.0, per_side: None }, Plate { weight: 0.004, per_side: None }],
+            ..Equipment::default_for(WeightUnit::Kg)
+        };
+        assert_eq!(odd.load(60.0).per_side, vec![10.0, 10.0]);
+        assert!(!odd.load(1e12).exact);
+    }
+}
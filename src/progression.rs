@@ -0,0 +1,622 @@
+//! Progression über mehrere Trainings: aus den letzten Einheiten einer Übung
+//! das Ziel für die nächste ableiten.
+//!
+//! Alles hier rechnet in kg und kennt weder Datenbank noch Scheiben — was
+//! sich tatsächlich laden lässt, kommt als Funktion herein. Die Historie ist
+//! nach Datum absteigend sortiert, die letzte Einheit steht vorn.
+
+use crate::models::workout::MAX_REPS;
+use crate::units::WeightUnit;
+
+/// So viele Einheiten schaut die Engine höchstens zurück.
+pub const HISTORY_SESSIONS: i64 = 5;
+/// So oft in Folge mit demselben Gewicht verfehlt, dann Deload.
+pub const FAILURES_BEFORE_DELOAD: usize = 2;
+pub const DELOAD_FACTOR: f64 = 0.9;
+/// Ziel-RPE für die RPE-Strategie: zwei Wiederholungen im Tank.
+pub const TARGET_RPE: f64 = 8.0;
+/// Wiederholungsbereich, wenn der Plan keinen nennt.
+pub const DEFAULT_REPS: (i32, i32) = (8, 12);
+/// Aufwärmrampe nach der leeren Stange: Anteil am Arbeitsgewicht und
+/// Wiederholungen.
+pub const WARMUP_RAMP: [(f64, i32); 3] = [(0.4, 5), (0.6, 3), (0.8, 2)];
+pub const EMPTY_BAR_REPS: i32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// Erst Wiederholungen bis ans obere Ende des Bereichs, dann Gewicht.
+    #[default]
+    Double,
+    /// Gewicht rauf, sobald alle Sätze das untere Ende schaffen.
+    Linear,
+    /// Nach gemeldeter Anstrengung; ohne RPE wie `Double`.
+    Rpe,
+}
+
+impl Strategy {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "double" => Some(Strategy::Double),
+            "linear" => Some(Strategy::Linear),
+            "rpe" => Some(Strategy::Rpe),
+            _ => None,
+        }
+    }
+
+    /// Wert aus `workout_exercises.progression`. Unbekanntes gilt als `Double`.
+    pub fn from_db(raw: &str) -> Self {
+        Self::parse(raw).unwrap_or_default()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Strategy::Double => "double",
+            Strategy::Linear => "linear",
+            Strategy::Rpe => "rpe",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Strategy::Double => "Double progression",
+            Strategy::Linear => "Linear progression",
+            Strategy::Rpe => "RPE-based",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// Noch keine Historie: Planwerte.
+    Start,
+    Increase,
+    /// Gewicht halten, Wiederholungen steigern.
+    AddReps,
+    /// Einmal verfehlt: dasselbe nochmal.
+    Repeat,
+    Decrease,
+    Deload,
+}
+
+impl Decision {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "start" => Some(Decision::Start),
+            "increase" => Some(Decision::Increase),
+            "add_reps" => Some(Decision::AddReps),
+            "repeat" => Some(Decision::Repeat),
+            "decrease" => Some(Decision::Decrease),
+            "deload" => Some(Decision::Deload),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Decision::Start => "start",
+            Decision::Increase => "increase",
+            Decision::AddReps => "add_reps",
+            Decision::Repeat => "repeat",
+            Decision::Decrease => "decrease",
+            Decision::Deload => "deload",
+        }
+    }
+}
+
+/// Was der Plan für die Übung vorsieht.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prescription {
+    pub sets: i32,
+    pub reps_min: i32,
+    pub reps_max: i32,
+    pub weight_kg: Option<f64>,
+}
+
+impl Prescription {
+    /// Fehlende Grenzen aus der jeweils anderen, sonst `DEFAULT_REPS`.
+    pub fn new(sets: i32, reps_min: Option<i32>, reps_max: Option<i32>, weight_kg: Option<f64>) -> Self {
+        let (reps_min, reps_max) = match (reps_min, reps_max) {
+            (Some(min), Some(max)) => (min.min(max), min.max(max)),
+            (Some(reps), None) | (None, Some(reps)) => (reps, reps),
+            (None, None) => DEFAULT_REPS,
+        };
+        Self { sets: sets.max(1), reps_min, reps_max, weight_kg }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedSet {
+    pub weight_kg: Option<f64>,
+    pub reps: i32,
+    pub rpe: Option<f64>,
+}
+
+/// Alle Sätze einer Übung aus einem Training.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SessionLog {
+    pub sets: Vec<LoggedSet>,
+}
+
+impl SessionLog {
+    /// Schwerstes Gewicht der Einheit; `None` bei reinem Körpergewicht.
+    fn working_weight(&self) -> Option<f64> {
+        self.sets.iter().filter_map(|s| s.weight_kg).reduce(f64::max)
+    }
+
+    /// Sätze mit dem Arbeitsgewicht. Aufwärm- und Backoff-Sätze zählen nicht.
+    fn working_sets(&self) -> Vec<&LoggedSet> {
+        let top = self.working_weight();
+        self.sets
+            .iter()
+            .filter(|s| match (s.weight_kg, top) {
+                (Some(w), Some(top)) => same_weight(w, top),
+                (None, None) => true,
+                _ => false,
+            })
+            .collect()
+    }
+
+    /// Alle geplanten Sätze mit mindestens `reps` Wiederholungen.
+    fn all_sets_reached(&self, plan: &Prescription, reps: i32) -> bool {
+        let sets = self.working_sets();
+        sets.len() >= plan.sets as usize && sets.iter().all(|s| s.reps >= reps)
+    }
+
+    fn average_rpe(&self) -> Option<f64> {
+        let rpes: Vec<f64> = self.working_sets().iter().filter_map(|s| s.rpe).collect();
+        (!rpes.is_empty()).then(|| rpes.iter().sum::<f64>() / rpes.len() as f64)
+    }
+}
+
+fn same_weight(a: f64, b: f64) -> bool {
+    (a - b).abs() < 0.005
+}
+
+/// Ziel für die nächste Einheit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Target {
+    pub strategy: Strategy,
+    pub decision: Decision,
+    pub weight_kg: Option<f64>,
+    pub sets: i32,
+    pub reps_min: i32,
+    pub reps_max: i32,
+    /// Arbeitsgewicht der letzten Einheit, auf das sich die Entscheidung bezieht.
+    pub basis_kg: Option<f64>,
+    /// Alle geplanten Sätze der letzten Einheit am oberen Ende des Bereichs.
+    pub top_of_range: bool,
+}
+
+impl Target {
+    /// `8–12` oder `5`, wie `WorkoutExerciseDetail::reps_display`.
+    pub fn reps_display(&self) -> String {
+        if self.reps_min == self.reps_max {
+            self.reps_min.to_string()
+        } else {
+            format!("{}–{}", self.reps_min, self.reps_max)
+        }
+    }
+
+    pub fn weight_display(&self, unit: WeightUnit) -> String {
+        match self.weight_kg {
+            Some(kg) => unit.format(kg as f32),
+            None => "Bodyweight".to_string(),
+        }
+    }
+
+    /// Begründung in einem Satz, in der Einheit des Nutzers.
+    pub fn reason(&self, unit: WeightUnit) -> String {
+        let basis = self.basis_kg.map(|kg| unit.format(kg as f32));
+        match (self.decision, basis) {
+            (Decision::Start, _) => "First session with this plan: start at the planned target.".to_string(),
+            (Decision::Increase, Some(basis)) => format!("Every set hit its target at {basis}, so add weight."),
+            (Decision::Increase, None) => {
+                "Every set reached the top of the range. Add load or a harder variation.".to_string()
+            }
+            (Decision::AddReps, Some(basis)) => format!(
+                "Stay at {basis} and add a rep per set until every set reaches {}.",
+                self.reps_max
+            ),
+            (Decision::AddReps, None) => {
+                format!("Add a rep per set until every set reaches {}.", self.reps_max)
+            }
+            (Decision::Repeat, Some(basis)) => format!("Missed reps at {basis}, so repeat the weight."),
+            (Decision::Repeat, None) => "Missed reps last time, so repeat the session.".to_string(),
+            (Decision::Decrease, Some(basis)) => {
+                format!("{basis} was harder than RPE {TARGET_RPE}, so take a step back.")
+            }
+            (Decision::Deload, Some(basis)) => format!(
+                "Missed reps at {basis} {FAILURES_BEFORE_DELOAD} sessions in a row, so deload and build back up."
+            ),
+            (Decision::Decrease | Decision::Deload, None) => "Take a step back this session.".to_string(),
+        }
+    }
+}
+
+/// Ziel für die nächste Einheit aus Plan und Historie (neueste zuerst).
+///
+/// `increment_kg` ist der kleinste Sprung in der Einheit des Nutzers,
+/// `loadable` rundet auf das, was sich mit den Scheiben laden lässt.
+pub fn next_target(
+    strategy: Strategy,
+    plan: &Prescription,
+    history: &[SessionLog],
+    increment_kg: f64,
+    loadable: impl Fn(f64) -> f64,
+) -> Target {
+    let mut target = Target {
+        strategy,
+        decision: Decision::Start,
+        weight_kg: plan.weight_kg,
+        sets: plan.sets,
+        reps_min: plan.reps_min,
+        reps_max: plan.reps_max,
+        basis_kg: None,
+        top_of_range: false,
+    };
+    let Some(latest) = history.iter().find(|s| !s.sets.is_empty()) else {
+        return target;
+    };
+
+    let basis = latest.working_weight();
+    target.basis_kg = basis;
+    target.top_of_range = latest.all_sets_reached(plan, plan.reps_max);
+    target.weight_kg = basis;
+
+    let step = |delta: f64| {
+        basis.map(|from| {
+            let raw = (from + delta).max(0.0);
+            let rounded = loadable(raw);
+            // Rundet die Scheibenlage zurück aufs alte Gewicht, bleibt der
+            // ungerundete Sprung — sonst hieße das Ziel „mach dasselbe nochmal".
+            if same_weight(rounded, from) { raw } else { rounded }
+        })
+    };
+
+    let missed = !latest.all_sets_reached(plan, plan.reps_min);
+    if missed {
+        let failures = history
+            .iter()
+            .filter(|s| !s.sets.is_empty())
+            .take_while(|s| {
+                !s.all_sets_reached(plan, plan.reps_min)
+                    && match (s.working_weight(), basis) {
+                        (Some(w), Some(b)) => same_weight(w, b),
+                        (None, None) => true,
+                        _ => false,
+                    }
+            })
+            .count();
+        if failures >= FAILURES_BEFORE_DELOAD && basis.is_some() {
+            target.decision = Decision::Deload;
+            target.weight_kg = basis.map(|b| {
+                let deload = loadable(b * DELOAD_FACTOR);
+                if same_weight(deload, b) { b * DELOAD_FACTOR } else { deload }
+            });
+        } else {
+            target.decision = Decision::Repeat;
+        }
+        return target;
+    }
+
+    match strategy {
+        Strategy::Linear => {
+            target.decision = Decision::Increase;
+            target.weight_kg = step(increment_kg);
+        }
+        Strategy::Rpe if latest.average_rpe().is_some() => {
+            let rpe = latest.average_rpe().unwrap_or(TARGET_RPE);
+            if rpe <= TARGET_RPE - 1.5 {
+                target.decision = Decision::Increase;
+                target.weight_kg = step(2.0 * increment_kg);
+            } else if rpe <= TARGET_RPE - 0.5 {
+                target.decision = Decision::Increase;
+                target.weight_kg = step(increment_kg);
+            } else if rpe <= TARGET_RPE + 0.5 {
+                target.decision = Decision::AddReps;
+            } else {
+                target.decision = Decision::Decrease;
+                target.weight_kg = step(-increment_kg);
+            }
+            if basis.is_none() && target.decision != Decision::AddReps {
+                // Körpergewicht lässt sich nicht in kg verstellen.
+                target.decision = if rpe <= TARGET_RPE { Decision::Increase } else { Decision::AddReps };
+            }
+        }
+        // Doppelte Progression, auch für RPE ohne gemeldete Anstrengung.
+        Strategy::Double | Strategy::Rpe => {
+            if target.top_of_range {
+                target.decision = Decision::Increase;
+                target.weight_kg = step(increment_kg);
+            } else {
+                target.decision = Decision::AddReps;
+            }
+        }
+    }
+    target
+}
+
+/// Geschätztes Einzelwiederholungsmaximum nach Epley. Mit RPE zählen die
+/// Wiederholungen in Reserve mit: 100 kg × 5 bei RPE 8 ist ein Satz, der bis
+/// zum Versagen sieben Wiederholungen gehabt hätte.
+pub fn estimated_1rm(weight_kg: f64, reps: i32, rpe: Option<f64>) -> f64 {
+    let in_reserve = rpe.map(|rpe| (10.0 - rpe).clamp(0.0, 9.0)).unwrap_or(0.0);
+    let to_failure = reps as f64 + in_reserve;
+    if to_failure <= 1.0 {
+        weight_kg
+    } else {
+        weight_kg * (1.0 + to_failure / 30.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WarmupStep {
+    pub weight_kg: f64,
+    pub reps: i32,
+}
+
+/// Aufwärmsätze vor dem ersten Arbeitssatz: leere Stange, dann
+/// [`WARMUP_RAMP`]. Stufen, die nach dem Runden nicht schwerer als die
+/// vorige oder schon so schwer wie das Arbeitsgewicht sind, fallen weg —
+/// bei 40 kg an der 20-kg-Stange etwa die 40-%-Stufe. Ab der leeren Stange
+/// abwärts gibt es keine Rampe.
+pub fn warmup_ramp(working_kg: f64, empty_bar_kg: f64, loadable: impl Fn(f64) -> f64) -> Vec<WarmupStep> {
+    let mut steps = Vec::new();
+    if working_kg <= empty_bar_kg || same_weight(working_kg, empty_bar_kg) {
+        return steps;
+    }
+    steps.push(WarmupStep { weight_kg: empty_bar_kg, reps: EMPTY_BAR_REPS });
+    let mut last = empty_bar_kg;
+    for (share, reps) in WARMUP_RAMP {
+        let kg = loadable(working_kg * share);
+        if kg > last && !same_weight(kg, last) && kg < working_kg && !same_weight(kg, working_kg) {
+            steps.push(WarmupStep { weight_kg: kg, reps });
+            last = kg;
+        }
+    }
+    steps
+}
+
+/// Vorschlag, den Plan selbst nachzuziehen. `reps: None` lässt den
+/// Wiederholungsbereich, wie er ist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanChange {
+    pub weight_kg: Option<f64>,
+    pub reps: Option<(i32, i32)>,
+}
+
+/// Änderung am Plan nach einem Ziel der Engine. Vorgeschlagen wird nur bei
+/// einem Sprung nach oben, wenn alle Sätze das obere Ende geschafft haben,
+/// oder bei einem Deload — gleich nach welcher Strategie. Linear und RPE
+/// steigern im Training früher; der Plan zieht erst am Ende des Bereichs nach.
+pub fn plan_change(plan: &Prescription, target: &Target) -> Option<PlanChange> {
+    let earned = match target.decision {
+        Decision::Increase => target.top_of_range,
+        Decision::Deload => true,
+        _ => false,
+    };
+    if !earned {
+        return None;
+    }
+    match target.weight_kg {
+        Some(kg) if plan.weight_kg.is_none_or(|planned| !same_weight(planned, kg)) => {
+            Some(PlanChange { weight_kg: Some(kg), reps: None })
+        }
+        Some(_) => None,
+        // Körpergewicht: der Bereich wandert eine Wiederholung nach oben,
+        // höchstens bis `MAX_REPS` wie im Formular und im YAML.
+        None if target.decision == Decision::Increase && plan.reps_max < MAX_REPS => Some(PlanChange {
+            weight_kg: plan.weight_kg,
+            reps: Some((plan.reps_min + 1, plan.reps_max + 1)),
+        }),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(sets: i32, min: i32, max: i32, weight: f64) -> Prescription {
+        Prescription::new(sets, Some(min), Some(max), Some(weight))
+    }
+
+    fn session(weight: f64, reps: &[i32]) -> SessionLog {
+        SessionLog {
+            sets: reps
+                .iter()
+                .map(|&reps| LoggedSet { weight_kg: Some(weight), reps, rpe: None })
+                .collect(),
+        }
+    }
+
+    fn with_rpe(mut log: SessionLog, rpe: f64) -> SessionLog {
+        for set in &mut log.sets {
+            set.rpe = Some(rpe);
+        }
+        log
+    }
+
+    fn exact(kg: f64) -> f64 {
+        kg
+    }
+
+    #[test]
+    fn without_history_the_plan_is_the_target() {
+        let target = next_target(Strategy::Double, &plan(3, 8, 12, 60.0), &[], 2.5, exact);
+        assert_eq!(target.decision, Decision::Start);
+        assert_eq!(target.weight_kg, Some(60.0));
+        assert_eq!(target.reps_display(), "8–12");
+    }
+
+    #[test]
+    fn double_progression_adds_reps_before_weight() {
+        let plan = plan(3, 8, 12, 60.0);
+        let history = [session(60.0, &[12, 11, 10]), session(60.0, &[10, 9, 9])];
+        let target = next_target(Strategy::Double, &plan, &history, 2.5, exact);
+        assert_eq!(target.decision, Decision::AddReps);
+        assert_eq!(target.weight_kg, Some(60.0));
+
+        let history = [session(60.0, &[12, 12, 12]), session(60.0, &[12, 11, 10])];
+        let target = next_target(Strategy::Double, &plan, &history, 2.5, exact);
+        assert_eq!(target.decision, Decision::Increase);
+        assert_eq!(target.weight_kg, Some(62.5));
+        assert_eq!(target.reason(WeightUnit::Kg), "Every set hit its target at 60kg, so add weight.");
+    }
+
+    #[test]
+    fn linear_progression_adds_weight_whenever_the_minimum_holds() {
+        let plan = plan(5, 5, 5, 100.0);
+        let target = next_target(Strategy::Linear, &plan, &[session(100.0, &[5, 5, 5, 5, 5])], 2.5, exact);
+        assert_eq!(target.decision, Decision::Increase);
+        assert_eq!(target.weight_kg, Some(102.5));
+        assert_eq!(target.reps_display(), "5");
+    }
+
+    #[test]
+    fn one_miss_repeats_and_repeated_misses_deload() {
+        let plan = plan(5, 5, 5, 100.0);
+        let once = [session(100.0, &[5, 5, 5, 4, 3]), session(97.5, &[5, 5, 5, 5, 5])];
+        let target = next_target(Strategy::Linear, &plan, &once, 2.5, exact);
+        assert_eq!(target.decision, Decision::Repeat);
+        assert_eq!(target.weight_kg, Some(100.0));
+
+        let twice = [session(100.0, &[5, 5, 4, 4, 3]), session(100.0, &[5, 5, 5, 4, 3])];
+        let target = next_target(Strategy::Linear, &plan, &twice, 2.5, |kg| (kg / 2.5).round() * 2.5);
+        assert_eq!(target.decision, Decision::Deload);
+        assert_eq!(target.weight_kg, Some(90.0));
+    }
+
+    #[test]
+    fn fewer_sets_than_planned_count_as_a_miss() {
+        let target = next_target(Strategy::Double, &plan(3, 8, 12, 60.0), &[session(60.0, &[12, 12])], 2.5, exact);
+        assert_eq!(target.decision, Decision::Repeat);
+    }
+
+    #[test]
+    fn warm_ups_below_the_working_weight_are_ignored() {
+        let mut log = session(80.0, &[12, 12, 12]);
+        log.sets.insert(0, LoggedSet { weight_kg: Some(40.0), reps: 5, rpe: None });
+        let target = next_target(Strategy::Double, &plan(3, 8, 12, 80.0), &[log], 2.5, exact);
+        assert_eq!(target.decision, Decision::Increase);
+        assert_eq!(target.basis_kg, Some(80.0));
+    }
+
+    #[test]
+    fn rpe_steers_the_jump_and_falls_back_without_ratings() {
+        let plan = plan(3, 8, 12, 60.0);
+        let easy = next_target(Strategy::Rpe, &plan, &[with_rpe(session(60.0, &[8, 8, 8]), 6.0)], 2.5, exact);
+        assert_eq!((easy.decision, easy.weight_kg), (Decision::Increase, Some(65.0)));
+
+        let right = next_target(Strategy::Rpe, &plan, &[with_rpe(session(60.0, &[8, 8, 8]), 8.0)], 2.5, exact);
+        assert_eq!((right.decision, right.weight_kg), (Decision::AddReps, Some(60.0)));
+
+        let hard = next_target(Strategy::Rpe, &plan, &[with_rpe(session(60.0, &[8, 8, 8]), 9.5)], 2.5, exact);
+        assert_eq!((hard.decision, hard.weight_kg), (Decision::Decrease, Some(57.5)));
+
+        let unrated = next_target(Strategy::Rpe, &plan, &[session(60.0, &[12, 12, 12])], 2.5, exact);
+        assert_eq!((unrated.decision, unrated.weight_kg), (Decision::Increase, Some(62.5)));
+    }
+
+    #[test]
+    fn targets_are_rounded_to_loadable_weights_but_never_back_to_the_start() {
+        let plan = plan(3, 8, 12, 60.0);
+        let history = [session(60.0, &[12, 12, 12])];
+        // Nur 5-kg-Sprünge möglich: 62.27 wird 65.
+        let target = next_target(Strategy::Double, &plan, &history, 2.27, |kg| (kg / 5.0).ceil() * 5.0);
+        assert_eq!(target.weight_kg, Some(65.0));
+        // Rundet zurück auf 60: dann der ungerundete Sprung.
+        let target = next_target(Strategy::Double, &plan, &history, 2.5, |kg| (kg / 5.0).floor() * 5.0);
+        assert_eq!(target.weight_kg, Some(62.5));
+    }
+
+    #[test]
+    fn bodyweight_exercises_progress_in_reps_only() {
+        let plan = Prescription::new(3, Some(8), Some(12), None);
+        let log = SessionLog {
+            sets: vec![LoggedSet { weight_kg: None, reps: 12, rpe: None }; 3],
+        };
+        let target = next_target(Strategy::Double, &plan, &[log], 2.5, exact);
+        assert_eq!(target.decision, Decision::Increase);
+        assert_eq!(target.weight_kg, None);
+        assert_eq!(target.weight_display(WeightUnit::Kg), "Bodyweight");
+    }
+
+    #[test]
+    fn estimated_1rm_counts_reps_in_reserve() {
+        assert_eq!(estimated_1rm(100.0, 1, None), 100.0);
+        assert_eq!(estimated_1rm(100.0, 1, Some(10.0)), 100.0);
+        assert!((estimated_1rm(100.0, 5, None) - 116.67).abs() < 0.01);
+        assert!((estimated_1rm(100.0, 5, Some(8.0)) - 123.33).abs() < 0.01);
+    }
+
+    #[test]
+    fn only_increases_and_deloads_change_the_plan() {
+        let plan = plan(3, 8, 12, 60.0);
+        let up = next_target(Strategy::Double, &plan, &[session(60.0, &[12, 12, 12])], 2.5, exact);
+        assert_eq!(plan_change(&plan, &up), Some(PlanChange { weight_kg: Some(62.5), reps: None }));
+
+        let hold = next_target(Strategy::Double, &plan, &[session(60.0, &[12, 10, 9])], 2.5, exact);
+        assert_eq!(plan_change(&plan, &hold), None);
+
+        let failing = [session(60.0, &[7, 6, 6]), session(60.0, &[8, 7, 6])];
+        let deload = next_target(Strategy::Double, &plan, &failing, 2.5, exact);
+        assert_eq!(plan_change(&plan, &deload), Some(PlanChange { weight_kg: Some(54.0), reps: None }));
+    }
+
+    #[test]
+    fn early_increases_stay_out_of_the_plan() {
+        let plan = plan(3, 5, 8, 100.0);
+        let linear = next_target(Strategy::Linear, &plan, &[session(100.0, &[5, 5, 5])], 2.5, exact);
+        assert_eq!(linear.decision, Decision::Increase);
+        assert_eq!(plan_change(&plan, &linear), None);
+
+        let easy = with_rpe(session(100.0, &[6, 6, 6]), 6.0);
+        let rpe = next_target(Strategy::Rpe, &plan, &[easy], 2.5, exact);
+        assert_eq!(rpe.decision, Decision::Increase);
+        assert_eq!(plan_change(&plan, &rpe), None);
+
+        let top = next_target(Strategy::Linear, &plan, &[session(100.0, &[8, 8, 8])], 2.5, exact);
+        assert_eq!(plan_change(&plan, &top), Some(PlanChange { weight_kg: Some(102.5), reps: None }));
+    }
+
+    #[test]
+    fn bodyweight_plans_move_the_rep_range_up() {
+        let plan = Prescription::new(3, Some(8), Some(12), None);
+        let log = SessionLog {
+            sets: vec![LoggedSet { weight_kg: None, reps: 12, rpe: None }; 3],
+        };
+        let target = next_target(Strategy::Double, &plan, &[log], 2.5, exact);
+        assert_eq!(plan_change(&plan, &target), Some(PlanChange { weight_kg: None, reps: Some((9, 13)) }));
+    }
+
+    #[test]
+    fn bodyweight_rep_range_stops_at_the_rep_limit() {
+        let top = |max: i32| {
+            let plan = Prescription::new(3, Some(max - 4), Some(max), None);
+            let log = SessionLog {
+                sets: vec![LoggedSet { weight_kg: None, reps: max, rpe: None }; 3],
+            };
+            let target = next_target(Strategy::Double, &plan, &[log], 2.5, exact);
+            plan_change(&plan, &target)
+        };
+        assert_eq!(top(MAX_REPS - 1).and_then(|change| change.reps), Some((MAX_REPS - 4, MAX_REPS)));
+        assert_eq!(top(MAX_REPS), None);
+    }
+
+    #[test]
+    fn warmup_ramp_climbs_from_the_bar_and_drops_duplicate_steps() {
+        let to_2_5 = |kg: f64| (kg / 2.5).round() * 2.5;
+        let ramp = warmup_ramp(100.0, 20.0, to_2_5);
+        let weights: Vec<f64> = ramp.iter().map(|s| s.weight_kg).collect();
+        assert_eq!(weights, vec![20.0, 40.0, 60.0, 80.0]);
+        assert_eq!(ramp[0].reps, EMPTY_BAR_REPS);
+
+        // 40 % von 40 kg sind 16 kg, leichter als die Stange.
+        let light: Vec<f64> = warmup_ramp(40.0, 20.0, to_2_5).iter().map(|s| s.weight_kg).collect();
+        assert_eq!(light, vec![20.0, 25.0, 32.5]);
+
+        assert!(warmup_ramp(20.0, 20.0, to_2_5).is_empty());
+        assert!(warmup_ramp(12.0, 20.0, to_2_5).is_empty());
+    }
+}
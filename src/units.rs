@@ -0,0 +1,145 @@
+//! Gewichtseinheit pro Nutzer. Gespeichert wird immer in kg — umgerechnet
+//! wird nur an den Rändern: beim Lesen eines Formulars und bei der Anzeige.
+//!
+//! Angezeigt wird in kg auf 10 g genau (81.25 kg gibt es mit Mikroscheiben),
+//! in lb auf eine Nachkommastelle. So wird aus gespeicherten 61.23 kg wieder
+//! glatt 135 lb und nicht 134.99.
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::fmt;
+
+pub const LB_IN_KG: f64 = 0.453_592_37;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeightUnit {
+    #[default]
+    Kg,
+    Lb,
+}
+
+impl WeightUnit {
+    /// Auch die Schreibweisen fremder Exporte: `kgs`, `lbs`.
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "kg" | "kgs" => Some(WeightUnit::Kg),
+            "lb" | "lbs" => Some(WeightUnit::Lb),
+            _ => None,
+        }
+    }
+
+    /// Wert aus `users.weight_unit`. Unbekanntes gilt als kg, wie vor der
+    /// Einstellung.
+    pub fn from_db(raw: &str) -> Self {
+        Self::parse(raw).unwrap_or_default()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WeightUnit::Kg => "kg",
+            WeightUnit::Lb => "lb",
+        }
+    }
+
+    /// Eingabe in dieser Einheit nach kg, auf 10 g gerundet.
+    pub fn to_kg(self, value: f64) -> f64 {
+        let kg = match self {
+            WeightUnit::Kg => value,
+            WeightUnit::Lb => value * LB_IN_KG,
+        };
+        (kg * 100.0).round() / 100.0
+    }
+
+    /// Gespeicherte kg in dieser Einheit, auf die Anzeigegenauigkeit gerundet.
+    pub fn convert_kg(self, kg: f64) -> f64 {
+        match self {
+            WeightUnit::Kg => (kg * 100.0).round() / 100.0,
+            WeightUnit::Lb => (kg / LB_IN_KG * 10.0).round() / 10.0,
+        }
+    }
+
+    /// Kleinster üblicher Sprung an der Stange: zwei 1.25er Scheiben in kg,
+    /// zwei 2.5er in lb.
+    pub fn increment(self) -> f64 {
+        match self {
+            WeightUnit::Kg => 2.5,
+            WeightUnit::Lb => 5.0,
+        }
+    }
+
+    /// Zahl in dieser Einheit ohne überflüssige Nachkommastellen: `80`, `82.5`.
+    pub fn amount(self, value: f64) -> String {
+        let rounded = match self {
+            WeightUnit::Kg => (value * 100.0).round() / 100.0,
+            WeightUnit::Lb => (value * 10.0).round() / 10.0,
+        };
+        format!("{rounded}")
+    }
+
+    // Die Anzeigehelfer nehmen auch `&f32`: Askama reicht Argumente aus
+    // Templates als Referenz durch.
+
+    /// Gewicht für Formularfelder, ohne Einheit.
+    pub fn input_value(self, kg: impl Borrow<f32>) -> String {
+        self.amount(self.convert_kg(*kg.borrow() as f64))
+    }
+
+    /// Gewicht mit Einheit: `80kg`, `176.4lb`.
+    pub fn format(self, kg: impl Borrow<f32>) -> String {
+        format!("{}{}", self.input_value(kg), self.as_str())
+    }
+
+    /// Volumen, groß genug für Summen über Monate: `640kg`, `12.4t`,
+    /// `27.3k lb`.
+    pub fn format_volume(self, kg: impl Borrow<f32>) -> String {
+        let value = self.convert_kg(*kg.borrow() as f64);
+        match self {
+            WeightUnit::Kg if value >= 1000.0 => format!("{:.1}t", value / 1000.0),
+            WeightUnit::Lb if value >= 1000.0 => format!("{:.1}k lb", value / 1000.0),
+            _ => format!("{}{}", value.round(), self.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for WeightUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pounds_survive_the_round_trip_through_kg() {
+        let stored = WeightUnit::Lb.to_kg(135.0);
+        assert_eq!(stored, 61.23);
+        assert_eq!(WeightUnit::Lb.format(stored as f32), "135lb");
+        assert_eq!(WeightUnit::Lb.input_value(WeightUnit::Lb.to_kg(137.5) as f32), "137.5");
+    }
+
+    #[test]
+    fn kilograms_keep_micro_plates() {
+        assert_eq!(WeightUnit::Kg.to_kg(81.25), 81.25);
+        assert_eq!(WeightUnit::Kg.format(81.25), "81.25kg");
+        assert_eq!(WeightUnit::Kg.format(80.0), "80kg");
+        assert_eq!(WeightUnit::Lb.format(80.0), "176.4lb");
+    }
+
+    #[test]
+    fn volume_is_compact_when_large() {
+        assert_eq!(WeightUnit::Kg.format_volume(640.0), "640kg");
+        assert_eq!(WeightUnit::Kg.format_volume(12_400.0), "12.4t");
+        assert_eq!(WeightUnit::Lb.format_volume(12_400.0), "27.3k lb");
+    }
+
+    #[test]
+    fn parse_accepts_export_spellings_and_db_falls_back_to_kg() {
+        assert_eq!(WeightUnit::parse(" LBS "), Some(WeightUnit::Lb));
+        assert_eq!(WeightUnit::parse("kgs"), Some(WeightUnit::Kg));
+        assert_eq!(WeightUnit::parse("stone"), None);
+        assert_eq!(WeightUnit::from_db("stone"), WeightUnit::Kg);
+    }
+}
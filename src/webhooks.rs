@@ -0,0 +1,605 @@
+//! Ausgehende Webhooks: Ereignisse aus dem Training an Adressen des Nutzers,
+//! etwa für Licht im Smart Home oder eine Chat-Nachricht.
+//!
+//! Ereignisse landen mit [`enqueue`] in `webhook_deliveries`, in derselben
+//! Transaktion wie das, was sie melden — kein Webhook für einen Satz, der
+//! nie gespeichert wurde, und keiner geht bei einem Absturz verloren. Den
+//! Versand übernimmt [`delivery_job`]; wer nicht mit 2xx antwortet, bekommt
+//! es mit wachsendem Abstand erneut, bis [`MAX_ATTEMPTS`].
+//!
+//! Jeder Request trägt `X-WOPlanner-Signature: sha256=<hex>`, den
+//! HMAC-SHA256 des Bodys mit dem Geheimnis des Webhooks.
+//!
+//! Die Adresse gibt der Nutzer vor, gesendet wird vom Server aus. Damit
+//! darüber niemand Dienste im Netz des Servers erreicht, geht nichts an
+//! Loopback-, private oder link-local Adressen — weder als IP in der URL
+//! noch nach der DNS-Auflösung —, und Weiterleitungen werden nicht verfolgt.
+//! Wer Webhooks im eigenen LAN braucht, setzt [`ALLOW_PRIVATE_ENV`].
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use hmac::{Hmac, Mac};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use sqlx::{SqliteConnection, SqlitePool};
+use futures_util::{StreamExt, stream};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+pub const EVENT_HEADER: &str = "X-WOPlanner-Event";
+pub const DELIVERY_HEADER: &str = "X-WOPlanner-Delivery";
+pub const SIGNATURE_HEADER: &str = "X-WOPlanner-Signature";
+
+/// Danach bleibt eine Zustellung als `failed` liegen. Mit dem Abstand aus
+/// [`retry_delay`] gut eine Stunde nach dem ersten Versuch.
+pub const MAX_ATTEMPTS: i64 = 8;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Auch ohne Anstoß schaut der Job so oft nach fälligen Wiederholungen.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+const BATCH_SIZE: i64 = 50;
+/// So viele Empfänger werden gleichzeitig beliefert; an denselben Host geht
+/// immer nur ein Request, in der Reihenfolge der Ereignisse.
+const PARALLEL_HOSTS: usize = 8;
+
+/// `true` erlaubt Ziele im eigenen Netz, etwa Home Assistant im LAN.
+pub const ALLOW_PRIVATE_ENV: &str = "WEBHOOKS_ALLOW_PRIVATE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    WorkoutFinished,
+    SetCompleted,
+    PrAchieved,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 3] = [
+        WebhookEvent::WorkoutFinished,
+        WebhookEvent::SetCompleted,
+        WebhookEvent::PrAchieved,
+    ];
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|event| event.as_str() == raw.trim())
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::WorkoutFinished => "workout.finished",
+            WebhookEvent::SetCompleted => "set.completed",
+            WebhookEvent::PrAchieved => "pr.achieved",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            WebhookEvent::WorkoutFinished => "Workout finished",
+            WebhookEvent::SetCompleted => "Set completed",
+            WebhookEvent::PrAchieved => "Personal record",
+        }
+    }
+}
+
+/// Neues Geheimnis zum Signieren, mit erkennbarem Präfix wie die API-Token.
+pub fn generate_secret() -> String {
+    format!("whsec_{}", Uuid::new_v4().simple())
+}
+
+/// Wert für [`SIGNATURE_HEADER`].
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Wartezeit nach dem `attempts`-ten Fehlversuch: 30 s, 1 min, 2 min, …
+pub fn retry_delay(attempts: i64) -> chrono::Duration {
+    chrono::Duration::seconds(30 << (attempts - 1).clamp(0, 10))
+}
+
+/// Sekundengenau und immer mit `Z`, damit die Zeitpunkte in SQL als Text
+/// vergleichbar sind.
+fn queue_time(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Ob [`ALLOW_PRIVATE_ENV`] gesetzt ist; sonst nur öffentliche Ziele.
+pub fn private_targets_allowed() -> bool {
+    std::env::var(ALLOW_PRIVATE_ENV)
+        .ok()
+        .and_then(|raw| raw.parse::<bool>().ok())
+        .unwrap_or(false)
+}
+
+/// Adresse im öffentlichen Internet — nicht Loopback, privat, link-local,
+/// CGNAT, Multicast oder unspezifiziert. IPv4 in IPv6 zählt wie IPv4.
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match embedded_v4(ip) {
+            Some(v4) => is_public_v4(v4),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+/// IPv4-Adresse, die in `ip` steckt und über die die Verbindung am Ende
+/// läuft: IPv4-mapped `::ffff:0:0/96`, IPv4-kompatibel `::/96`, NAT64
+/// `64:ff9b::/96` und 6to4 `2002::/16`.
+fn embedded_v4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    let s = ip.segments();
+    let last = Ipv4Addr::new((s[6] >> 8) as u8, s[6] as u8, (s[7] >> 8) as u8, s[7] as u8);
+    match s {
+        [0, 0, 0, 0, 0, 0xffff, ..] => Some(last),
+        // `::` und `::1` sind keine eingebetteten Adressen.
+        [0, 0, 0, 0, 0, 0, 0, 0 | 1] => None,
+        [0, 0, 0, 0, 0, 0, ..] => Some(last),
+        [0x64, 0xff9b, 0, 0, 0, 0, ..] => Some(last),
+        [0x2002, a, b, ..] => Some(Ipv4Addr::new((a >> 8) as u8, a as u8, (b >> 8) as u8, b as u8)),
+        _ => None,
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || a == 0
+        || (a == 100 && (64..128).contains(&b)))
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || (first & 0xfe00) == 0xfc00 // fc00::/7, unique local
+        || (first & 0xffc0) == 0xfe80) // fe80::/10, link-local
+}
+
+/// IP-Adresse, wenn der Host der URL eine ist — die fragt reqwest nicht
+/// beim Resolver an. IPv6 steht in eckigen Klammern.
+fn literal_ip(url: &reqwest::Url) -> Option<IpAddr> {
+    url.host_str()?.trim_start_matches('[').trim_end_matches(']').parse().ok()
+}
+
+/// Prüfung beim Anlegen, ohne DNS: IP-Adressen und `localhost`. Namen, die
+/// ins eigene Netz zeigen, fängt der Versand ab.
+pub fn check_url(raw: &str, allow_private: bool) -> Result<(), String> {
+    let url = reqwest::Url::parse(raw).map_err(|_| "Webhook URL is not a valid URL".to_string())?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err("Webhook URL must start with http:// or https://".to_string());
+    }
+    let local = match literal_ip(&url) {
+        Some(ip) => !is_public(ip),
+        None => url.host_str().is_some_and(|host| host == "localhost" || host.ends_with(".localhost")),
+    };
+    if local && !allow_private {
+        return Err("Webhook URL must point to a public address".to_string());
+    }
+    Ok(())
+}
+
+/// Resolver, der nur öffentliche Adressen weitergibt. Die Prüfung sitzt
+/// damit zwischen Auflösung und Verbindung; ein Name, der beim Anlegen
+/// öffentlich war und später ins LAN zeigt, kommt nicht durch.
+struct PublicOnly;
+
+impl Resolve for PublicOnly {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((host.as_str(), 0)).await?.filter(|addr| is_public(addr.ip())).collect();
+            if addrs.is_empty() {
+                return Err(format!("{host} has no public address").into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// HTTP-Client für die Zustellung: folgt keinen Weiterleitungen und
+/// erreicht ohne `allow_private` nur öffentliche Adressen.
+#[derive(Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    allow_private: bool,
+}
+
+impl Client {
+    pub fn new(allow_private: bool) -> Self {
+        let mut builder = reqwest::Client::builder()
+            .user_agent(concat!("WOPlanner-Webhooks/", env!("CARGO_PKG_VERSION")))
+            .redirect(reqwest::redirect::Policy::none())
+            .timeout(REQUEST_TIMEOUT);
+        if !allow_private {
+            builder = builder.dns_resolver(Arc::new(PublicOnly));
+        }
+        let http = builder.build().expect("error: can't build the webhook HTTP client!");
+        Self { http, allow_private }
+    }
+}
+
+static WAKE: Notify = Notify::const_new();
+
+/// Den Zustelljob anstoßen — nach dem Commit, vorher sieht er die neuen
+/// Zustellungen nicht.
+pub fn wake() {
+    WAKE.notify_one();
+}
+
+/// Eine Zustellung je Webhook des Nutzers, der `event` abonniert hat. Die
+/// Payload wird hier festgelegt; Wiederholungen senden denselben Body.
+pub async fn enqueue(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+    event: WebhookEvent,
+    data: Value,
+) -> Result<usize, sqlx::Error> {
+    let hooks = sqlx::query!("SELECT id, events FROM webhooks WHERE user_id = ?", user_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    let now = Utc::now();
+    let mut queued = 0;
+    for hook in hooks {
+        if !hook.events.split(',').any(|e| WebhookEvent::parse(e) == Some(event)) {
+            continue;
+        }
+        let id = Uuid::new_v4().to_string();
+        let payload = json!({
+            "id": id,
+            "event": event.as_str(),
+            "created_at": now.to_rfc3339(),
+            "data": data,
+        })
+        .to_string();
+        let event_name = event.as_str();
+        let created_at = now.to_rfc3339();
+        let next_attempt_at = queue_time(now);
+        sqlx::query!(
+            "INSERT INTO webhook_deliveries (id, webhook_id, event, payload, next_attempt_at, created_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+            id,
+            hook.id,
+            event_name,
+            payload,
+            next_attempt_at,
+            created_at
+        )
+        .execute(&mut *conn)
+        .await?;
+        queued += 1;
+    }
+    Ok(queued)
+}
+
+async fn send(client: &Client, url: &str, secret: &str, event: &str, id: &str, payload: String) -> Result<(), String> {
+    let url = reqwest::Url::parse(url).map_err(|e| e.to_string())?;
+    if let Some(ip) = literal_ip(&url).filter(|ip| !client.allow_private && !is_public(*ip)) {
+        return Err(format!("{ip} is not a public address"));
+    }
+    let response = client
+        .http
+        .post(url)
+        .header("Content-Type", "application/json")
+        .header(EVENT_HEADER, event)
+        .header(DELIVERY_HEADER, id)
+        .header(SIGNATURE_HEADER, sign(secret, payload.as_bytes()))
+        .body(payload)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("HTTP {}", response.status()))
+    }
+}
+
+/// Fällige Zustellungen senden, je Host der Reihe nach und bis zu
+/// [`PARALLEL_HOSTS`] Hosts gleichzeitig — ein langsamer Empfänger hält die
+/// anderen nicht auf. Liefert die Zahl der Versuche, erfolgreich oder nicht.
+pub async fn deliver_due(pool: &SqlitePool, client: &Client, now: DateTime<Utc>) -> Result<usize, sqlx::Error> {
+    let due_at = queue_time(now);
+    let due = sqlx::query_as!(
+        DueDelivery,
+        r#"SELECT d.id, d.event, d.payload, d.attempts, w.url, w.secret
+           FROM webhook_deliveries d JOIN webhooks w ON w.id = d.webhook_id
+           WHERE d.status = 'pending' AND d.next_attempt_at <= ?
+           ORDER BY d.created_at
+           LIMIT ?"#,
+        due_at,
+        BATCH_SIZE
+    )
+    .fetch_all(pool)
+    .await?;
+    let count = due.len();
+
+    let mut by_host: HashMap<String, Vec<DueDelivery>> = HashMap::new();
+    for delivery in due {
+        let host = reqwest::Url::parse(&delivery.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+        by_host.entry(host).or_default().push(delivery);
+    }
+    let results: Vec<Result<(), sqlx::Error>> = stream::iter(by_host.into_values())
+        .map(|deliveries| async move {
+            for delivery in &deliveries {
+                deliver(pool, client, delivery, now).await?;
+            }
+            Ok(())
+        })
+        .buffer_unordered(PARALLEL_HOSTS)
+        .collect()
+        .await;
+    results.into_iter().collect::<Result<(), _>>()?;
+    Ok(count)
+}
+
+struct DueDelivery {
+    id: String,
+    event: String,
+    payload: String,
+    attempts: i64,
+    url: String,
+    secret: String,
+}
+
+async fn deliver(pool: &SqlitePool, client: &Client, delivery: &DueDelivery, now: DateTime<Utc>) -> Result<(), sqlx::Error> {
+    let attempts = delivery.attempts + 1;
+    let result = send(client, &delivery.url, &delivery.secret, &delivery.event, &delivery.id, delivery.payload.clone()).await;
+    match result {
+        Ok(()) => {
+            let delivered_at = now.to_rfc3339();
+            sqlx::query!(
+                "UPDATE webhook_deliveries SET status = 'delivered', attempts = ?, delivered_at = ?, last_error = NULL WHERE id = ?",
+                attempts,
+                delivered_at,
+                delivery.id
+            )
+            .execute(pool)
+            .await?;
+        }
+        Err(error) => {
+            tracing::warn!("webhook delivery {} to {} failed (attempt {}): {}", delivery.id, delivery.url, attempts, error);
+            let status = if attempts >= MAX_ATTEMPTS { "failed" } else { "pending" };
+            let next_attempt_at = queue_time(now + retry_delay(attempts));
+            sqlx::query!(
+                "UPDATE webhook_deliveries SET status = ?, attempts = ?, next_attempt_at = ?, last_error = ? WHERE id = ?",
+                status,
+                attempts,
+                next_attempt_at,
+                error,
+                delivery.id
+            )
+            .execute(pool)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Hintergrundjob für `deliver_due`: sofort nach [`wake`], sonst alle
+/// 30 Sekunden für die Wiederholungen.
+pub async fn delivery_job(database_pool: SqlitePool) {
+    let client = Client::new(private_targets_allowed());
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = WAKE.notified() => {}
+        }
+        // Ein voller Stapel heißt: es liegt noch mehr an.
+        loop {
+            match deliver_due(&database_pool, &client, Utc::now()).await {
+                Ok(sent) if sent as i64 == BATCH_SIZE => continue,
+                Ok(_) => break,
+                Err(e) => {
+                    tracing::error!("webhook delivery failed: {:?}", e);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Bytes, http::HeaderMap, http::StatusCode, routing::post};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_signature_and_retry_delay() {
+        // RFC 4231, Testfall 2.
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(retry_delay(1), chrono::Duration::seconds(30));
+        assert_eq!(retry_delay(3), chrono::Duration::minutes(2));
+        assert_eq!(WebhookEvent::parse("pr.achieved"), Some(WebhookEvent::PrAchieved));
+        assert_eq!(WebhookEvent::parse("pr"), None);
+    }
+
+    #[test]
+    fn test_private_targets_are_refused() {
+        for ip in [
+            "127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.10", "169.254.169.254", "100.64.0.1", "0.0.0.0",
+            "::1", "fd00::1", "fe80::1", "::ffff:192.168.1.10", "::127.0.0.1", "::a9fe:a9fe", "64:ff9b::7f00:1",
+            "64:ff9b::c0a8:10a", "2002:7f00:0001::", "2002:a9fe:a9fe::1", "2002:c0a8:10a::",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in [
+            "93.184.216.34", "2606:2800:220:1::1", "::ffff:93.184.216.34", "64:ff9b::5db8:d822", "2002:5db8:d822::1",
+        ] {
+            assert!(is_public(ip.parse().unwrap()), "{ip}");
+        }
+
+        assert!(check_url("https://hooks.example.com/x", false).is_ok());
+        assert!(check_url("http://192.168.1.10:8123/api/webhook/x", false).is_err());
+        assert!(check_url("http://[::1]/hook", false).is_err());
+        assert!(check_url("http://[2002:a9fe:a9fe::]/", false).is_err());
+        assert!(check_url("http://[64:ff9b::a9fe:a9fe]/latest/meta-data", false).is_err());
+        assert!(check_url("http://localhost:8123/hook", false).is_err());
+        assert!(check_url("http://192.168.1.10:8123/api/webhook/x", true).is_ok());
+        assert!(check_url("ftp://example.com/", true).is_err());
+    }
+
+    /// Empfänger auf einem freien Port: antwortet der Reihe nach mit
+    /// `statuses`, danach mit 200, und merkt sich Header und Body.
+    async fn receiver(statuses: Vec<StatusCode>) -> (String, Arc<Mutex<Vec<(HeaderMap, Bytes)>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let statuses = Arc::new(Mutex::new(statuses.into_iter()));
+        let log = received.clone();
+        let app = Router::new().route(
+            "/hook",
+            post(move |headers: HeaderMap, body: Bytes| async move {
+                log.lock().unwrap().push((headers, body));
+                statuses.lock().unwrap().next().unwrap_or(StatusCode::OK)
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, received)
+    }
+
+    async fn subscribe(pool: &SqlitePool, url: &str, events: &str) {
+        sqlx::query!(
+            "INSERT OR IGNORE INTO users (id, name, created_at) VALUES ('u-1', 'Anna', '2026-01-01T00:00:00Z')"
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        let id = Uuid::new_v4().to_string();
+        sqlx::query!(
+            "INSERT INTO webhooks (id, user_id, url, secret, events, created_at)
+             VALUES (?, 'u-1', ?, 'whsec_test', ?, '2026-01-01T00:00:00Z')",
+            id,
+            url,
+            events
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn deliveries_are_signed_and_retried_until_accepted(pool: SqlitePool) {
+        let (url, received) = receiver(vec![StatusCode::INTERNAL_SERVER_ERROR]).await;
+        subscribe(&pool, &url, "workout.finished,pr.achieved").await;
+        let client = Client::new(true);
+
+        let mut conn = pool.acquire().await.unwrap();
+        let data = json!({"exercise_id": "ex-squat"});
+        assert_eq!(enqueue(&mut conn, "u-1", WebhookEvent::PrAchieved, data.clone()).await.unwrap(), 1);
+        // Nicht abonniert, nichts in der Queue.
+        assert_eq!(enqueue(&mut conn, "u-1", WebhookEvent::SetCompleted, data).await.unwrap(), 0);
+        drop(conn);
+
+        // Erster Versuch scheitert mit 500 und wird verschoben.
+        let now = Utc::now();
+        assert_eq!(deliver_due(&pool, &client, now).await.unwrap(), 1);
+        let row = sqlx::query!("SELECT status, attempts, last_error FROM webhook_deliveries")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!((row.status.as_str(), row.attempts), ("pending", 1));
+        assert_eq!(row.last_error.as_deref(), Some("HTTP 500 Internal Server Error"));
+        assert_eq!(deliver_due(&pool, &client, now).await.unwrap(), 0);
+
+        // Nach der Wartezeit klappt es, mit demselben Body.
+        assert_eq!(deliver_due(&pool, &client, now + retry_delay(1)).await.unwrap(), 1);
+        let status = sqlx::query_scalar!("SELECT status FROM webhook_deliveries").fetch_one(&pool).await.unwrap();
+        assert_eq!(status, "delivered");
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].1, received[1].1);
+        let (headers, body) = &received[1];
+        assert_eq!(headers[EVENT_HEADER], "pr.achieved");
+        assert_eq!(headers[SIGNATURE_HEADER].to_str().unwrap(), sign("whsec_test", body));
+        let payload: Value = serde_json::from_slice(body).unwrap();
+        assert_eq!(payload["id"], headers[DELIVERY_HEADER].to_str().unwrap());
+        assert_eq!(payload["data"]["exercise_id"], "ex-squat");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn local_targets_and_redirects_are_not_followed(pool: SqlitePool) {
+        // /hook leitet auf /inside weiter, etwa auf einen Dienst im LAN.
+        let followed = Arc::new(Mutex::new(0));
+        let count = followed.clone();
+        let app = Router::new()
+            .route("/hook", post(|| async { axum::response::Redirect::temporary("/inside") }))
+            .route(
+                "/inside",
+                post(move || async move {
+                    *count.lock().unwrap() += 1;
+                    StatusCode::OK
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        // Über den Namen geht es durch den Resolver, über die IP nicht.
+        subscribe(&pool, &url, "workout.finished").await;
+        subscribe(&pool, &url.replace("127.0.0.1", "localhost"), "workout.finished").await;
+
+        let mut conn = pool.acquire().await.unwrap();
+        enqueue(&mut conn, "u-1", WebhookEvent::WorkoutFinished, json!({})).await.unwrap();
+        drop(conn);
+
+        let now = Utc::now();
+        assert_eq!(deliver_due(&pool, &Client::new(false), now).await.unwrap(), 2);
+        let errors = sqlx::query_scalar!("SELECT last_error FROM webhook_deliveries ORDER BY last_error")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(errors[0].as_deref(), Some("127.0.0.1 is not a public address"));
+        assert!(errors[1].as_deref().unwrap().starts_with("error sending request"), "{errors:?}");
+
+        // Mit Freigabe kommen beide an, der Weiterleitung folgt keiner.
+        assert_eq!(deliver_due(&pool, &Client::new(true), now + retry_delay(1)).await.unwrap(), 2);
+        let errors = sqlx::query_scalar!("SELECT last_error FROM webhook_deliveries")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(errors, vec![Some("HTTP 307 Temporary Redirect".to_string()); 2]);
+        assert_eq!(*followed.lock().unwrap(), 0);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn unreachable_receivers_give_up_after_the_last_attempt(pool: SqlitePool) {
+        // Port, auf dem sicher niemand lauscht.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        drop(listener);
+        subscribe(&pool, &url, "workout.finished").await;
+        let client = Client::new(true);
+
+        let mut conn = pool.acquire().await.unwrap();
+        enqueue(&mut conn, "u-1", WebhookEvent::WorkoutFinished, json!({})).await.unwrap();
+        drop(conn);
+
+        let mut now = Utc::now();
+        for attempt in 1..=MAX_ATTEMPTS {
+            assert_eq!(deliver_due(&pool, &client, now).await.unwrap(), 1, "attempt {attempt}");
+            now += retry_delay(attempt);
+        }
+        assert_eq!(deliver_due(&pool, &client, now).await.unwrap(), 0);
+        let row = sqlx::query!("SELECT status, attempts FROM webhook_deliveries").fetch_one(&pool).await.unwrap();
+        assert_eq!((row.status.as_str(), row.attempts), ("failed", MAX_ATTEMPTS));
+    }
+}
@@ -5,6 +5,8 @@
 //! das Format braucht — Block-Stil, zwei Ebenen, Skalare und ein Blockstring.
 //! Alles andere wird als Fehler gemeldet, nicht geraten.
 
+use crate::models::parse_rep_range;
+use crate::units::WeightUnit;
 use std::fmt;
 
 /// Ein Plan, wie er in der Datei steht: ohne IDs, Zeitstempel und Nutzerbezug.
@@ -14,6 +16,8 @@ pub struct WorkoutPlan {
     pub description: Option<String>,
     pub schedule_type: String,
     pub schedule_day: Option<i64>,
+    /// Einheit aller `weight`-Felder, ab Version 3. Davor immer kg.
+    pub unit: WeightUnit,
     pub exercises: Vec<PlanExercise>,
 }
 
@@ -24,7 +28,15 @@ pub struct PlanExercise {
     pub video_url: Option<String>,
     pub sets: i64,
     pub weight: Option<f64>,
+    /// Zielbereich der Wiederholungen, ab Version 2. `8` steht als 8–8.
+    pub reps_min: Option<i64>,
+    pub reps_max: Option<i64>,
+    /// Pause nach jedem Satz in Sekunden, ab Version 2.
+    pub rest_seconds: Option<i64>,
     pub notes: Option<String>,
+    /// Supersatz oder Zirkel, ab Version 4: direkt aufeinanderfolgende
+    /// Übungen mit derselben Gruppe. Großgeschrieben, `a` ist `A`.
+    pub group: Option<String>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -47,14 +59,30 @@ fn err<T>(line: usize, message: impl Into<String>) -> Result<T, ParseError> {
     Err(ParseError { line, message: message.into() })
 }
 
-pub const FORMAT_VERSION: i64 = 1;
+/// Die Version, die `to_yaml` schreibt. Gelesen wird jede von
+/// `OLDEST_VERSION` bis hier — ältere Dateien hebt `upgrade` an.
+///
+/// Wer sie erhöht: Feld in `FIELDS_SINCE` eintragen, Arm in `upgrade`
+/// ergänzen, Golden File `fixtures/workout_yaml/v<N>.yaml` anlegen.
+pub const FORMAT_VERSION: i64 = 4;
+pub const OLDEST_VERSION: i64 = 1;
 pub const MAX_INPUT_BYTES: usize = 64 * 1024;
 pub const MAX_EXERCISES: usize = 50;
 pub const MAX_SETS: i64 = 50;
 pub const MAX_NAME_LEN: usize = 100;
+pub const MAX_REST_SECONDS: i64 = 600;
+pub const MAX_GROUP_LEN: usize = 10;
 
 const SCHEDULE_TYPES: [&str; 4] = ["manual", "rotation", "weekly", "disabled"];
 
+/// Übungsfelder, die nicht von Anfang an dabei waren, mit ihrer Version.
+/// Eine Datei, die ein Feld vor seiner Zeit benutzt, ist ein Fehler — sonst
+/// liest ein älterer Build sie später anders als dieser.
+const FIELDS_SINCE: [(&str, i64); 3] = [("reps", 2), ("rest", 2), ("group", 4)];
+
+/// Dasselbe für Felder der obersten Ebene.
+const TOP_LEVEL_SINCE: [(&str, i64); 1] = [("unit", 3)];
+
 // ---------------------------------------------------------------- Schreiben
 
 /// Ein Skalar so ausgeben, dass er beim Lesen wieder derselbe ist.
@@ -107,6 +135,7 @@ pub fn to_yaml(plan: &WorkoutPlan) -> String {
         Some(day) => out.push_str(&format!("  day: {day}\n")),
         None => out.push_str("  day: null\n"),
     }
+    out.push_str(&format!("unit: {}\n", plan.unit));
 
     out.push_str("exercises:\n");
     for ex in &plan.exercises {
@@ -121,6 +150,17 @@ pub fn to_yaml(plan: &WorkoutPlan) -> String {
         if let Some(weight) = ex.weight {
             out.push_str(&format!("    weight: {weight}\n"));
         }
+        match (ex.reps_min, ex.reps_max) {
+            (Some(min), Some(max)) if min != max => out.push_str(&format!("    reps: {min}-{max}\n")),
+            (Some(reps), _) | (None, Some(reps)) => out.push_str(&format!("    reps: {reps}\n")),
+            (None, None) => {}
+        }
+        if let Some(rest) = ex.rest_seconds {
+            out.push_str(&format!("    rest: {rest}\n"));
+        }
+        if let Some(group) = ex.group.as_deref().filter(|g| !g.trim().is_empty()) {
+            out.push_str(&format!("    group: {}\n", scalar(group)));
+        }
         if let Some(notes) = ex.notes.as_deref().filter(|s| !s.trim().is_empty()) {
             out.push_str(&text_field("notes", notes, "    "));
         }
@@ -228,6 +268,15 @@ fn parse_number(value: &str, number: usize, field: &str) -> Result<f64, ParseErr
         .map_err(|_| ParseError { line: number, message: format!("{field} must be a number, found '{value}'") })
 }
 
+/// Ganze Zahl: `90.7` wird abgelehnt statt still zu 90 gekürzt.
+fn parse_whole(value: &str, number: usize, field: &str) -> Result<i64, ParseError> {
+    let raw = parse_number(value, number, field)?;
+    if raw.fract() != 0.0 || !raw.is_finite() {
+        return err(number, format!("{field} must be a whole number, found '{}'", value.trim()));
+    }
+    Ok(raw as i64)
+}
+
 pub fn from_yaml(input: &str) -> Result<WorkoutPlan, ParseError> {
     if input.len() > MAX_INPUT_BYTES {
         return err(0, format!("input is larger than {} KB", MAX_INPUT_BYTES / 1024));
@@ -241,11 +290,66 @@ pub fn from_yaml(input: &str) -> Result<WorkoutPlan, ParseError> {
         return err(0, "input is empty");
     }
 
-    let mut version = None;
+    // Die Version zuerst: welche Felder erlaubt sind, hängt an ihr.
+    let version = read_version(&lines)?;
+    let plan = parse_plan(&lines, version)?;
+    Ok(upgrade(plan, version))
+}
+
+/// `version` aus der obersten Ebene lesen, bevor irgendetwas anderes geprüft
+/// wird. Unbekannte künftige Versionen werden abgelehnt, nicht geraten.
+fn read_version(lines: &[Line<'_>]) -> Result<i64, ParseError> {
+    let mut found = None;
+    for line in lines.iter().filter(|l| l.indent == 0) {
+        let (key, value) = split_pair(line.text, line.number)?;
+        if key != "version" {
+            continue;
+        }
+        if found.is_some() {
+            return err(line.number, "'version' is given twice");
+        }
+        found = Some((parse_whole(value, line.number, "version")?, line.number));
+    }
+
+    match found {
+        None => err(0, format!("missing 'version: {FORMAT_VERSION}' — every plan states its format version")),
+        Some((v, number)) if !(OLDEST_VERSION..=FORMAT_VERSION).contains(&v) => err(
+            number,
+            format!("unsupported format version {v}, this build reads versions {OLDEST_VERSION} to {FORMAT_VERSION}"),
+        ),
+        Some((v, _)) => Ok(v),
+    }
+}
+
+/// Einen Plan der Fassung `from` auf den heutigen Stand heben.
+///
+/// Ein Arm je Versionssprung, nacheinander durchlaufen — so bleibt eine Datei
+/// aus Version 1 lesbar, egal wie viele Versionen noch folgen.
+fn upgrade(plan: WorkoutPlan, from: i64) -> WorkoutPlan {
+    let mut version = from;
+    while version < FORMAT_VERSION {
+        match version {
+            // 1 → 2: Wiederholungen und Pause gab es noch nicht. Sie bleiben
+            // leer; ein Zielbereich wird nicht erfunden.
+            1 => {}
+            // 2 → 3: ohne `unit` waren Gewichte kg — das setzt schon
+            // `parse_plan` als Vorgabe.
+            2 => {}
+            // 3 → 4: ohne `group` steht jede Übung für sich.
+            3 => {}
+            other => unreachable!("no upgrade step from version {other}"),
+        }
+        version += 1;
+    }
+    plan
+}
+
+fn parse_plan(lines: &[Line<'_>], version: i64) -> Result<WorkoutPlan, ParseError> {
     let mut name = None;
     let mut description = None;
     let mut schedule_type = "manual".to_string();
     let mut schedule_day = None;
+    let mut unit = WeightUnit::Kg;
     let mut exercises: Vec<PlanExercise> = Vec::new();
     let mut seen_exercises = false;
 
@@ -258,13 +362,21 @@ pub fn from_yaml(input: &str) -> Result<WorkoutPlan, ParseError> {
         let (key, value) = split_pair(line.text, line.number)?;
         i += 1;
 
+        if let Some((_, since)) = TOP_LEVEL_SINCE.iter().find(|(field, _)| *field == key)
+            && version < *since
+        {
+            return err(
+                line.number,
+                format!("field '{key}' needs format version {since}, the file declares version {version}"),
+            );
+        }
+
         match key {
-            "version" => {
-                version = Some(parse_number(value, line.number, "version")? as i64);
-            }
+            // schon von read_version geprüft
+            "version" => {}
             "name" | "description" => {
                 let text = if value == "|" || value == "|-" {
-                    let (block, next) = block_scalar(&lines, i, 0);
+                    let (block, next) = block_scalar(lines, i, 0);
                     i = next;
                     Some(block)
                 } else {
@@ -272,6 +384,14 @@ pub fn from_yaml(input: &str) -> Result<WorkoutPlan, ParseError> {
                 };
                 if key == "name" { name = text } else { description = text }
             }
+            "unit" => {
+                let raw = unquote(value).unwrap_or_default();
+                unit = match raw.as_str() {
+                    "kg" => WeightUnit::Kg,
+                    "lb" => WeightUnit::Lb,
+                    _ => return err(line.number, format!("unknown unit '{raw}', expected kg or lb")),
+                };
+            }
             "schedule" => {
                 if !value.is_empty() {
                     return err(line.number, "schedule must be a block with 'type' and 'day'");
@@ -294,7 +414,7 @@ pub fn from_yaml(input: &str) -> Result<WorkoutPlan, ParseError> {
                             schedule_day = match unquote(sub_value) {
                                 None => None,
                                 Some(raw) => {
-                                    let day = parse_number(&raw, sub.number, "schedule day")? as i64;
+                                    let day = parse_whole(&raw, sub.number, "schedule day")?;
                                     if !(0..=6).contains(&day) {
                                         return err(sub.number, "schedule day must be between 0 (Sunday) and 6");
                                     }
@@ -334,7 +454,7 @@ pub fn from_yaml(input: &str) -> Result<WorkoutPlan, ParseError> {
                         let (field_key, field_value) = split_pair(field.text, field.number)?;
                         i += 1;
                         if field_value == "|" || field_value == "|-" {
-                            let (block, next) = block_scalar(&lines, i, field.indent);
+                            let (block, next) = block_scalar(lines, i, field.indent);
                             i = next;
                             fields.push((field_key, block, field.number));
                         } else {
@@ -342,7 +462,7 @@ pub fn from_yaml(input: &str) -> Result<WorkoutPlan, ParseError> {
                         }
                     }
 
-                    exercises.push(build_exercise(&fields, exercises.len() + 1)?);
+                    exercises.push(build_exercise(&fields, exercises.len() + 1, version)?);
                     if exercises.len() > MAX_EXERCISES {
                         return err(item.number, format!("a plan holds at most {MAX_EXERCISES} exercises"));
                     }
@@ -353,14 +473,6 @@ pub fn from_yaml(input: &str) -> Result<WorkoutPlan, ParseError> {
     }
 
     // --- Prüfungen, bevor irgendetwas geschrieben wird
-    match version {
-        None => return err(0, "missing 'version: 1' — every plan states its format version"),
-        Some(v) if v != FORMAT_VERSION => {
-            return err(0, format!("unsupported format version {v}, this build reads version {FORMAT_VERSION}"));
-        }
-        _ => {}
-    }
-
     let name = name
         .map(|n| n.trim().to_string())
         .filter(|n| !n.is_empty())
@@ -371,29 +483,57 @@ pub fn from_yaml(input: &str) -> Result<WorkoutPlan, ParseError> {
     if !seen_exercises || exercises.is_empty() {
         return err(0, "a plan needs at least one exercise");
     }
+    // Eine Gruppe ist ein Block: taucht sie nach einer anderen Übung wieder
+    // auf, wäre unklar, ob zwei Supersätze gemeint sind oder ein Tippfehler.
+    for (index, exercise) in exercises.iter().enumerate().skip(1) {
+        let Some(group) = exercise.group.as_deref() else { continue };
+        if exercises[index - 1].group.as_deref() != Some(group)
+            && exercises[..index - 1].iter().any(|e| e.group.as_deref() == Some(group))
+        {
+            return err(
+                0,
+                format!("exercise {}: group '{group}' must directly follow the other exercises of that group", index + 1),
+            );
+        }
+    }
     if schedule_type != "weekly" {
         schedule_day = None;
     }
 
-    Ok(WorkoutPlan { name, description, schedule_type, schedule_day, exercises })
+    Ok(WorkoutPlan { name, description, schedule_type, schedule_day, unit, exercises })
 }
 
-fn build_exercise(fields: &[(&str, String, usize)], position: usize) -> Result<PlanExercise, ParseError> {
+fn build_exercise(
+    fields: &[(&str, String, usize)],
+    position: usize,
+    version: i64,
+) -> Result<PlanExercise, ParseError> {
     let mut name = None;
     let mut instructions = None;
     let mut video_url = None;
     let mut sets = None;
     let mut weight = None;
+    let mut reps = None;
+    let mut rest_seconds = None;
     let mut notes = None;
+    let mut group = None;
 
     for (key, value, number) in fields {
+        if let Some((_, since)) = FIELDS_SINCE.iter().find(|(field, _)| field == key)
+            && version < *since
+        {
+            return err(
+                *number,
+                format!("exercise {position}: field '{key}' needs format version {since}, the file declares version {version}"),
+            );
+        }
         match *key {
             "name" => name = unquote(value),
             "instructions" => instructions = unquote(value),
             "video_url" => video_url = unquote(value),
             "notes" => notes = unquote(value),
             "sets" => {
-                let parsed = parse_number(value, *number, &format!("exercise {position}: sets"))? as i64;
+                let parsed = parse_whole(value, *number, &format!("exercise {position}: sets"))?;
                 if !(1..=MAX_SETS).contains(&parsed) {
                     return err(*number, format!("exercise {position}: sets must be between 1 and {MAX_SETS}"));
                 }
@@ -411,6 +551,48 @@ fn build_exercise(fields: &[(&str, String, usize)], position: usize) -> Result<P
                     }
                 };
             }
+            "reps" => {
+                reps = match unquote(value) {
+                    None => None,
+                    // Dieselbe Prüfung wie im Formular.
+                    Some(raw) => parse_rep_range(&raw)
+                        .map_err(|message| ParseError { line: *number, message: format!("exercise {position}: {message}") })?
+                        .map(|(min, max)| (min as i64, max as i64)),
+                };
+            }
+            "group" => {
+                group = match unquote(value) {
+                    None => None,
+                    Some(raw) => {
+                        let raw = raw.trim();
+                        if raw.is_empty()
+                            || raw.chars().count() > MAX_GROUP_LEN
+                            || !raw.chars().all(char::is_alphanumeric)
+                        {
+                            return err(
+                                *number,
+                                format!("exercise {position}: group must be up to {MAX_GROUP_LEN} letters or digits, like A"),
+                            );
+                        }
+                        Some(raw.to_uppercase())
+                    }
+                };
+            }
+            "rest" => {
+                rest_seconds = match unquote(value) {
+                    None => None,
+                    Some(raw) => {
+                        let parsed = parse_whole(&raw, *number, &format!("exercise {position}: rest"))?;
+                        if !(0..=MAX_REST_SECONDS).contains(&parsed) {
+                            return err(
+                                *number,
+                                format!("exercise {position}: rest must be between 0 and {MAX_REST_SECONDS} seconds"),
+                            );
+                        }
+                        Some(parsed)
+                    }
+                };
+            }
             other => {
                 return err(*number, format!("exercise {position}: unknown field '{other}'"));
             }
@@ -431,7 +613,11 @@ fn build_exercise(fields: &[(&str, String, usize)], position: usize) -> Result<P
         video_url,
         sets: sets.unwrap_or(3),
         weight,
+        reps_min: reps.map(|(min, _)| min),
+        reps_max: reps.map(|(_, max)| max),
+        rest_seconds,
         notes,
+        group,
     })
 }
 
@@ -445,6 +631,7 @@ mod tests {
             description: Some("Brust, Schultern, Trizeps".into()),
             schedule_type: "weekly".into(),
             schedule_day: Some(3),
+            unit: WeightUnit::Kg,
             exercises: vec![
                 PlanExercise {
                     name: "Bench Press".into(),
@@ -452,7 +639,11 @@ mod tests {
                     video_url: Some("https://www.youtube.com/watch?v=abc".into()),
                     sets: 4,
                     weight: Some(80.0),
+                    reps_min: Some(6),
+                    reps_max: Some(8),
+                    rest_seconds: Some(120),
                     notes: Some("Aufwärmen mit 60kg".into()),
+                    group: Some("A".into()),
                 },
                 PlanExercise {
                     name: "Pull-up".into(),
@@ -460,12 +651,43 @@ mod tests {
                     video_url: None,
                     sets: 3,
                     weight: None,
+                    reps_min: Some(10),
+                    reps_max: Some(10),
+                    rest_seconds: None,
                     notes: None,
+                    group: Some("A".into()),
                 },
             ],
         }
     }
 
+    /// Golden Files: eine Datei je Version, die dieser Build je geschrieben
+    /// hat. Sie werden nie angepasst, nur ergänzt — eine geänderte Datei
+    /// würde genau das verdecken, was der Test finden soll.
+    fn golden(version: i64) -> String {
+        let path = format!("{}/fixtures/workout_yaml/v{version}.yaml", env!("CARGO_MANIFEST_DIR"));
+        std::fs::read_to_string(&path).unwrap_or_else(|_| panic!("golden file {path} is missing"))
+    }
+
+    /// Was jede Golden File nach dem Einlesen ergeben muss. Felder, die es in
+    /// einer Version noch nicht gab, bleiben leer.
+    fn golden_plan(version: i64) -> WorkoutPlan {
+        let mut plan = sample();
+        if version < 2 {
+            for exercise in &mut plan.exercises {
+                exercise.reps_min = None;
+                exercise.reps_max = None;
+                exercise.rest_seconds = None;
+            }
+        }
+        if version < 4 {
+            for exercise in &mut plan.exercises {
+                exercise.group = None;
+            }
+        }
+        plan
+    }
+
     #[test]
     fn round_trip_keeps_every_field() {
         let plan = sample();
@@ -507,9 +729,128 @@ mod tests {
 
     #[test]
     fn unsupported_version_is_rejected() {
-        let yaml = "version: 2\nname: Leg Day\nexercises:\n  - name: Squat\n    sets: 3\n";
+        let yaml = format!(
+            "version: {}\nname: Leg Day\nexercises:\n  - name: Squat\n    sets: 3\n",
+            FORMAT_VERSION + 1
+        );
+        let error = from_yaml(&yaml).unwrap_err();
+        assert!(error.message.contains(&format!("version {}", FORMAT_VERSION + 1)), "got: {error}");
+    }
+
+    #[test]
+    fn version_zero_and_fractions_are_rejected() {
+        let zero = "version: 0\nname: X\nexercises:\n  - name: Squat\n";
+        assert!(from_yaml(zero).unwrap_err().message.contains("unsupported format version 0"));
+        let fraction = "version: 1.5\nname: X\nexercises:\n  - name: Squat\n";
+        assert!(from_yaml(fraction).unwrap_err().message.contains("whole number"));
+    }
+
+    #[test]
+    fn fractional_counts_are_rejected() {
+        for (field, yaml) in [
+            ("rest", "version: 4\nname: X\nexercises:\n  - name: Squat\n    rest: 90.7\n"),
+            ("sets", "version: 4\nname: X\nexercises:\n  - name: Squat\n    sets: 3.5\n"),
+            ("reps", "version: 4\nname: X\nexercises:\n  - name: Squat\n    reps: 8.5\n"),
+            ("day", "version: 4\nname: X\nschedule:\n  type: weekly\n  day: 1.5\nexercises:\n  - name: Squat\n"),
+        ] {
+            let error = from_yaml(yaml).unwrap_err();
+            assert_eq!(error.line, 5, "{field}: {error}");
+            assert!(
+                error.message.contains("whole number") || error.message.contains("Reps must be a number"),
+                "{field}: {error}"
+            );
+        }
+    }
+
+    #[test]
+    fn every_past_version_still_imports() {
+        for version in OLDEST_VERSION..=FORMAT_VERSION {
+            let plan = from_yaml(&golden(version)).unwrap_or_else(|e| panic!("v{version}: {e}"));
+            assert_eq!(plan, golden_plan(version), "golden file v{version}");
+        }
+    }
+
+    #[test]
+    fn current_golden_file_is_what_export_writes() {
+        // Schlägt fehl, wenn to_yaml sich ändert, ohne dass FORMAT_VERSION
+        // erhöht und eine neue Golden File angelegt wurde.
+        assert_eq!(to_yaml(&golden_plan(FORMAT_VERSION)), golden(FORMAT_VERSION));
+    }
+
+    #[test]
+    fn pound_plans_keep_their_unit() {
+        let mut plan = sample();
+        plan.unit = WeightUnit::Lb;
+        plan.exercises[0].weight = Some(175.0);
+        let yaml = to_yaml(&plan);
+        assert!(yaml.contains("unit: lb\n"), "{yaml}");
+        assert_eq!(from_yaml(&yaml).unwrap(), plan);
+
+        let stone = yaml.replace("unit: lb", "unit: st");
+        assert!(from_yaml(&stone).unwrap_err().message.contains("unknown unit 'st'"));
+    }
+
+    #[test]
+    fn unit_before_version_3_is_rejected() {
+        let yaml = "version: 2\nname: X\nunit: lb\nexercises:\n  - name: Squat\n    sets: 3\n";
         let error = from_yaml(yaml).unwrap_err();
-        assert!(error.message.contains("version 2"), "got: {error}");
+        assert_eq!(error.line, 3);
+        assert!(error.message.contains("needs format version 3"), "got: {error}");
+    }
+
+    #[test]
+    fn field_from_a_later_version_is_rejected() {
+        let yaml = "version: 1\nname: X\nexercises:\n  - name: Squat\n    sets: 3\n    reps: 5\n";
+        let error = from_yaml(yaml).unwrap_err();
+        assert_eq!(error.line, 6);
+        assert!(error.message.contains("needs format version 2"), "got: {error}");
+    }
+
+    #[test]
+    fn rep_ranges_are_validated() {
+        let parse = |reps: &str| {
+            from_yaml(&format!("version: 2\nname: X\nexercises:\n  - name: Squat\n    reps: {reps}\n"))
+                .map(|plan| (plan.exercises[0].reps_min, plan.exercises[0].reps_max))
+        };
+        assert_eq!(parse("8-12"), Ok((Some(8), Some(12))));
+        assert_eq!(parse("5"), Ok((Some(5), Some(5))));
+        assert!(parse("12-8").unwrap_err().message.contains("above upper bound"));
+        assert!(parse("0").unwrap_err().message.contains("between 1 and 100"));
+        assert!(parse("viele").unwrap_err().message.contains("must be a number"));
+        // Fehler aus dem gemeinsamen Parser tragen Zeile und Übung.
+        let error = parse("8-").unwrap_err();
+        assert_eq!(error.line, 5);
+        assert!(error.message.starts_with("exercise 1: "), "got: {error}");
+    }
+
+    #[test]
+    fn groups_must_be_neighbours() {
+        let plan = |groups: [&str; 3]| {
+            let mut yaml = "version: 4\nname: X\nexercises:\n".to_string();
+            for (name, group) in ["Squat", "Row", "Plank"].iter().zip(groups) {
+                yaml.push_str(&format!("  - name: {name}\n    group: {group}\n"));
+            }
+            from_yaml(&yaml)
+        };
+        let circuit = plan(["a", "A", "A"]).unwrap();
+        assert!(circuit.exercises.iter().all(|e| e.group.as_deref() == Some("A")));
+        assert!(plan(["A", "A", "B"]).is_ok());
+
+        let split = plan(["A", "B", "A"]).unwrap_err();
+        assert!(split.message.contains("exercise 3: group 'A' must directly follow"), "got: {split}");
+        assert!(plan(["A", "B", "A-1"]).unwrap_err().message.contains("letters or digits"));
+    }
+
+    #[test]
+    fn group_before_version_4_is_rejected() {
+        let yaml = "version: 3\nname: X\nexercises:\n  - name: Squat\n    group: A\n";
+        assert!(from_yaml(yaml).unwrap_err().message.contains("needs format version 4"));
+    }
+
+    #[test]
+    fn rest_is_limited() {
+        let yaml = "version: 2\nname: X\nexercises:\n  - name: Squat\n    rest: 3600\n";
+        assert!(from_yaml(yaml).unwrap_err().message.contains("rest must be between"));
     }
 
     #[test]
@@ -572,13 +913,18 @@ mod tests {
             description: None,
             schedule_type: "manual".into(),
             schedule_day: None,
+            unit: WeightUnit::Kg,
             exercises: vec![PlanExercise {
                 name: "Squat".into(),
                 instructions: None,
                 video_url: None,
                 sets: 3,
                 weight: None,
+                reps_min: None,
+                reps_max: None,
+                rest_seconds: None,
                 notes: Some("# not a comment".into()),
+                group: None,
             }],
         };
         assert_eq!(from_yaml(&to_yaml(&plan)).unwrap(), plan);
@@ -0,0 +1,213 @@
+// Service Worker: App-Shell und zuletzt besuchte Seiten für den Betrieb
+// ohne Netz, dazu eine Queue für Sätze, die im Funkloch geloggt wurden.
+//
+// Ausgeliefert unter /sw.js (nicht /static), damit der Scope die ganze App
+// umfasst. Bei Änderungen CACHE_VERSION hochzählen.
+
+const CACHE_VERSION = 'v2';
+const SHELL_CACHE = `woplanner-shell-${CACHE_VERSION}`;
+const PAGE_CACHE = `woplanner-pages-${CACHE_VERSION}`;
+const SHELL = [
+  '/static/css/bulma.min.css',
+  '/static/css/style.css',
+  '/static/js/htmx.min.js',
+  '/static/manifest.json',
+  '/static/icon-192x192.png',
+];
+
+const QUEUE_DB = 'woplanner-offline';
+const QUEUE_STORE = 'sets';
+// Sätze, die der Server abgelehnt hat (4xx außer 401/403). Sie bleiben zum
+// Nachtragen sichtbar, bis der Nutzer sie verwirft.
+const REJECTED_STORE = 'rejected';
+const SYNC_TAG = 'replay-sets';
+const COMPLETE_SET = /^\/live-training\/[^/]+\/complete-set$/;
+
+self.addEventListener('install', (event) => {
+  event.waitUntil(
+    caches.open(SHELL_CACHE).then((cache) => cache.addAll(SHELL)).then(() => self.skipWaiting())
+  );
+});
+
+self.addEventListener('activate', (event) => {
+  event.waitUntil(
+    caches.keys()
+      .then((keys) => Promise.all(
+        keys.filter((key) => key.startsWith('woplanner-') && !key.endsWith(CACHE_VERSION))
+          .map((key) => caches.delete(key))
+      ))
+      .then(() => self.clients.claim())
+      .then(replaySets)
+  );
+});
+
+self.addEventListener('fetch', (event) => {
+  const request = event.request;
+  const url = new URL(request.url);
+  if (url.origin !== self.location.origin) return;
+
+  if (request.method === 'POST' && COMPLETE_SET.test(url.pathname)) {
+    event.respondWith(fetch(request.clone()).catch(() => queueSet(request)));
+    return;
+  }
+  if (request.method !== 'GET') return;
+
+  if (url.pathname.startsWith('/static/')) {
+    event.respondWith(
+      caches.match(request).then((cached) => cached || fetch(request))
+    );
+    return;
+  }
+  if (request.mode === 'navigate') {
+    event.respondWith(networkFirst(request));
+  }
+});
+
+// Seiten frisch vom Server, ohne Netz die zuletzt gesehene Fassung.
+async function networkFirst(request) {
+  const cache = await caches.open(PAGE_CACHE);
+  try {
+    const response = await fetch(request);
+    if (response.ok) cache.put(request, response.clone());
+    return response;
+  } catch (error) {
+    const cached = await cache.match(request);
+    if (cached) return cached;
+    throw error;
+  }
+}
+
+self.addEventListener('sync', (event) => {
+  if (event.tag === SYNC_TAG) event.waitUntil(replaySets());
+});
+
+// Seiten melden sich, sobald sie wieder Netz haben — nicht jeder Browser
+// kennt Background Sync. Abgelehnte Sätze fragen sie hier ab und verwerfen
+// sie; die Antwort geht über den mitgegebenen Port.
+self.addEventListener('message', (event) => {
+  const data = event.data || {};
+  const reply = (result) => { if (event.ports[0]) event.ports[0].postMessage(result); };
+  if (data.type === 'replay-sets') {
+    event.waitUntil(replaySets());
+  } else if (data.type === 'list-rejected') {
+    event.waitUntil(inStore(REJECTED_STORE, 'readonly', (store) => store.getAll()).then(reply));
+  } else if (data.type === 'dismiss-rejected') {
+    event.waitUntil(inStore(REJECTED_STORE, 'readwrite', (store) => store.delete(data.id)).then(reply));
+  }
+});
+
+function openQueue() {
+  return new Promise((resolve, reject) => {
+    const open = indexedDB.open(QUEUE_DB, 2);
+    open.onupgradeneeded = () => {
+      for (const name of [QUEUE_STORE, REJECTED_STORE]) {
+        if (!open.result.objectStoreNames.contains(name)) {
+          open.result.createObjectStore(name, { keyPath: 'id', autoIncrement: true });
+        }
+      }
+    };
+    open.onsuccess = () => resolve(open.result);
+    open.onerror = () => reject(open.error);
+  });
+}
+
+function inStore(name, mode, action) {
+  return openQueue().then((db) => new Promise((resolve, reject) => {
+    const tx = db.transaction(name, mode);
+    const result = action(tx.objectStore(name));
+    tx.oncomplete = () => resolve(result.result);
+    tx.onerror = () => reject(tx.error);
+  }));
+}
+
+// Aus der Queue in die abgelehnten Sätze, in einer Transaktion: ein Satz
+// steht nie in beiden und geht nie ganz verloren.
+function rejectSet(entry, status, error) {
+  return openQueue().then((db) => new Promise((resolve, reject) => {
+    const tx = db.transaction([QUEUE_STORE, REJECTED_STORE], 'readwrite');
+    tx.objectStore(QUEUE_STORE).delete(entry.id);
+    tx.objectStore(REJECTED_STORE).add({
+      url: entry.url,
+      body: entry.body,
+      status,
+      error,
+      rejected_at: new Date().toISOString(),
+    });
+    tx.oncomplete = () => resolve();
+    tx.onerror = () => reject(tx.error);
+  }));
+}
+
+// Fehlertext aus der JSON-Antwort von AppError, sonst der Statustext.
+async function errorMessage(response) {
+  try {
+    const body = await response.json();
+    if (body && body.error) return String(body.error);
+  } catch (_) { /* keine JSON-Antwort */ }
+  return response.statusText || `HTTP ${response.status}`;
+}
+
+// Satz für später ablegen. Die Antwort sagt htmx, dass nichts neu zu laden
+// ist; die Seite zeigt einen Hinweis. client_request_id steckt schon im
+// Body, der Server erkennt Wiederholungen daran.
+async function queueSet(request) {
+  const body = await request.text();
+  await inStore(QUEUE_STORE, 'readwrite', (store) => store.add({ url: request.url, body }));
+  if (self.registration.sync) {
+    try { await self.registration.sync.register(SYNC_TAG); } catch (_) { /* ohne Background Sync */ }
+  }
+  return new Response('Saved offline', {
+    status: 202,
+    headers: { 'HX-Trigger': 'set-queued' },
+  });
+}
+
+let replaying = null;
+
+// Abgelegte Sätze der Reihe nach senden. Ohne Netz oder bei Serverfehlern
+// bleibt der Rest liegen. 401/403 heißt abgemeldet: alles bleibt liegen, bis
+// nach der nächsten Anmeldung eine Seite lädt und erneut anstößt. Jedes
+// andere 4xx wird nie angenommen; der Satz wandert zu den abgelehnten, und
+// offene Seiten erfahren davon.
+function replaySets() {
+  if (!replaying) {
+    replaying = doReplay().finally(() => { replaying = null; });
+  }
+  return replaying;
+}
+
+async function doReplay() {
+  const entries = await inStore(QUEUE_STORE, 'readonly', (store) => store.getAll());
+  let rejected = 0;
+  for (const entry of entries) {
+    const outcome = await replayOne(entry);
+    if (outcome === 'stop') break;
+    if (outcome === 'rejected') rejected += 1;
+  }
+  if (rejected > 0) {
+    const clients = await self.clients.matchAll({ type: 'window' });
+    clients.forEach((client) => client.postMessage({ type: 'sets-rejected' }));
+  }
+}
+
+// 'sent', 'rejected' oder 'stop' (jetzt nicht weiter versuchen).
+async function replayOne(entry) {
+  let response;
+  try {
+    response = await fetch(entry.url, {
+      method: 'POST',
+      body: entry.body,
+      credentials: 'same-origin',
+      headers: { 'Content-Type': 'application/x-www-form-urlencoded', 'HX-Request': 'true' },
+    });
+  } catch (_) {
+    return 'stop';
+  }
+  if (response.status >= 500 || response.status === 401 || response.status === 403) return 'stop';
+  if (!response.ok) {
+    await rejectSet(entry, response.status, await errorMessage(response));
+    return 'rejected';
+  }
+  await inStore(QUEUE_STORE, 'readwrite', (store) => store.delete(entry.id));
+  return 'sent';
+}